@@ -0,0 +1,161 @@
+//! Shows the devtools of an app started with `Runtime::serve_devtools`
+//! in a window of its own, so they don't take up room in the app.
+//!
+//! Usage: `moxie-devtools <token> [port]`, where the token is the one
+//! `serve_devtools` returned to the app.
+
+use log::info;
+use moxie::state;
+use moxie_native::dom::devtools::{Command, CommandSender, DevToolsClient, Update};
+use moxie_native::prelude::*;
+use moxie_native::{Runtime, UpdateHandle};
+use moxie_native_devtools::{inspector, Inspection, DEFAULT_PORT};
+use std::env;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before connecting again when the app isn't running.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Connection {
+    port: u16,
+    token: String,
+    /// What the app has sent so far, or `None` while not connected.
+    inspection: Mutex<Option<Inspection>>,
+    sender: Mutex<Option<CommandSender>>,
+}
+
+impl Connection {
+    fn new(port: u16, token: String) -> Connection {
+        Connection {
+            port,
+            token,
+            inspection: Mutex::new(None),
+            sender: Mutex::new(None),
+        }
+    }
+
+    /// Sends a command to the app, showing its effect straight away.
+    fn apply(&self, command: &Command) {
+        if let Some(ref mut inspection) = *self.inspection.lock().unwrap() {
+            inspection.apply(command);
+        }
+        if let Some(ref mut sender) = *self.sender.lock().unwrap() {
+            if let Err(err) = sender.send(command) {
+                info!("failed to send command: {}", err);
+            }
+        }
+    }
+
+    fn receive(&self, update: Update) {
+        // Picking a node in the app selects it here too.
+        if let Update::Picked(id) = update {
+            self.apply(&Command::Inspect(Some(id)));
+            return;
+        }
+
+        if let Some(ref mut inspection) = *self.inspection.lock().unwrap() {
+            match update {
+                Update::Dom(root) => inspection.root = Some(root),
+                Update::Layouts(layouts) => inspection.layouts = layouts,
+                Update::Style(style) => inspection.style = style,
+                Update::Picking(picking) => inspection.picking = picking,
+                Update::Picked(_) => {}
+            }
+        }
+    }
+
+    /// Keeps connecting to the app and receiving its updates until the
+    /// viewer exits.
+    fn run(&self, updates: UpdateHandle) {
+        loop {
+            if let Ok((mut client, sender)) = DevToolsClient::connect(self.port, &self.token) {
+                *self.sender.lock().unwrap() = Some(sender);
+                *self.inspection.lock().unwrap() = Some(Inspection::default());
+                updates.request_update();
+
+                loop {
+                    match client.receive() {
+                        Ok(update) => self.receive(update),
+                        Err(err) => {
+                            info!("disconnected: {}", err);
+                            break;
+                        }
+                    }
+                    updates.request_update();
+                }
+
+                *self.sender.lock().unwrap() = None;
+                *self.inspection.lock().unwrap() = None;
+                updates.request_update();
+            }
+            thread::sleep(RETRY_INTERVAL);
+        }
+    }
+}
+
+#[topo::nested]
+fn viewer(connection: Arc<Connection>) -> Node<App> {
+    let (pending, commands) = state(Vec::new);
+    if !pending.is_empty() {
+        for command in pending.iter() {
+            connection.apply(command);
+        }
+        commands.set(vec![]);
+    }
+
+    let inspection = connection.inspection.lock().unwrap().clone();
+
+    mox! {
+        <app>
+            <window title="Devtools">
+                {match inspection {
+                    Some(inspection) => mox! {
+                        <inspector _=(inspection, commands) />
+                    },
+                    None => mox! {
+                        <view>
+                            <span>
+                                {% "Waiting for an app serving devtools on port {}", connection.port}
+                            </span>
+                        </view>
+                    },
+                }}
+            </window>
+        </app>
+    }
+}
+
+const USAGE: &str = "usage: moxie-devtools <token> [port]";
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let token = match args.next() {
+        Some(token) => token,
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    let port = match args.next() {
+        Some(arg) => match arg.parse() {
+            Ok(port) => port,
+            Err(_) => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        },
+        None => DEFAULT_PORT,
+    };
+
+    let connection = Arc::new(Connection::new(port, token));
+    let runtime = Runtime::new({
+        let connection = connection.clone();
+        move || viewer(connection.clone())
+    });
+    let updates = runtime.update_handle();
+    thread::spawn(move || connection.run(updates));
+    runtime.start();
+}
@@ -3,13 +3,19 @@
 use log::debug;
 use moxie::{state, Key};
 use moxie_native::dom::{
-    devtools::{register_devtools, DevTools},
-    element::DynamicNode,
-    node::{AnyNode, NodeRef},
+    devtools::{
+        apply_command, inspected_style, picking, register_devtools, take_picked, ChildSnapshot,
+        Command, DevTools, LayoutSnapshot, NodeSnapshot, Sides, StyleSnapshot,
+    },
+    element::{ElementState, ElementStates},
 };
 use moxie_native::prelude::*;
+use std::collections::HashMap;
 use topo;
 
+/// The port that `moxie-devtools` connects to unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7399;
+
 define_style! {
     static VIEW = {
         background_color: rgba(0, 0, 0, 0),
@@ -29,8 +35,106 @@ define_style! {
         background_color: rgba(0, 0, 0, 0),
     };
 
-    // Needs to be static to maintain object identity
-    static SENTINEL_STYLE = {
+    static NAME_BUTTON_STYLE = {
+        background_color: rgba(0, 0, 0, 0),
+        if state: hover {
+            background_color: rgb(235, 241, 247),
+        }
+    };
+
+    static SELECTED_STYLE = {
+        background_color: rgb(207, 232, 252),
+    };
+
+    static PANEL_STYLE = {
+        margin: Edges {
+            top: 10 px,
+        },
+        padding: 5 px,
+        background_color: rgb(255, 255, 255),
+        border: Edges {
+            top: border(1 px, solid, rgb(210, 210, 210)),
+        },
+    };
+
+    static TOOLBAR_STYLE = {
+        direction: horizontal,
+        padding: 5 px,
+        border: Edges {
+            bottom: border(1 px, solid, rgb(210, 210, 210)),
+        },
+    };
+
+    static PICK_STYLE = {
+        padding: Edges {
+            left: 6 px,
+            right: 6 px,
+            top: 2 px,
+            bottom: 2 px,
+        },
+        background_color: rgb(240, 240, 240),
+        corner_radius: 3 px,
+        if state: hover {
+            background_color: rgb(225, 225, 225),
+        }
+        if class: active {
+            background_color: rgb(207, 232, 252),
+        }
+    };
+
+    static MARGIN_BOX_STYLE = {
+        align_items: center,
+        background_color: rgb(249, 204, 157),
+    };
+
+    static BORDER_BOX_STYLE = {
+        align_items: center,
+        background_color: rgb(253, 221, 155),
+        border: border(1 px, solid, rgb(0, 0, 0)),
+    };
+
+    static PADDING_BOX_STYLE = {
+        align_items: center,
+        background_color: rgb(195, 208, 139),
+    };
+
+    static CONTENT_BOX_STYLE = {
+        padding: 4 px,
+        background_color: rgb(140, 182, 192),
+        border: border(1 px, solid, rgb(110, 110, 110)),
+    };
+
+    static BOX_LABEL_STYLE = {
+        align_self: start,
+        padding: Edges {
+            left: 3 px,
+        },
+        background_color: rgba(0, 0, 0, 0),
+    };
+
+    static BOX_ROW_STYLE = {
+        direction: horizontal,
+        align_items: center,
+        background_color: rgba(0, 0, 0, 0),
+    };
+
+    static BOX_SIDE_STYLE = {
+        padding: Edges {
+            left: 6 px,
+            right: 6 px,
+        },
+        background_color: rgba(0, 0, 0, 0),
+    };
+
+    static HEADING_STYLE = {
+        text_color: rgb(110, 110, 110),
+    };
+
+    static SELECTOR_STYLE = {
+        text_color: rgb(145, 70, 140),
+    };
+
+    static ROOT_STYLE = {
         text_color: rgb(0, 0, 0),
         background_color: rgba(0, 0, 0, 0),
     };
@@ -49,65 +153,115 @@ define_style! {
     };
 }
 
+/// Everything the inspector shows, which comes straight from the app for
+/// `devtools`, or over a connection for the `moxie-devtools` viewer.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Inspection {
+    pub root: Option<NodeSnapshot>,
+    pub layouts: Vec<LayoutSnapshot>,
+    pub style: Option<StyleSnapshot>,
+    pub picking: bool,
+    pub selected: Option<u64>,
+    /// The states forced on each node, see `force_states`.
+    pub forced: HashMap<u64, ElementStates>,
+}
+
+impl Inspection {
+    /// Shows the effect of a command before the app has applied it.
+    pub fn apply(&mut self, command: &Command) {
+        match *command {
+            Command::Inspect(id) => self.selected = id,
+            Command::SetPicking(picking) => self.picking = picking,
+            Command::ForceStates(id, states) => {
+                if states.is_empty() {
+                    self.forced.remove(&id);
+                } else {
+                    self.forced.insert(id, states);
+                }
+            }
+        }
+    }
+}
+
+/// Queues a command from a click handler, to be sent by whoever owns
+/// `commands` on its next update.
+fn queue(commands: &Key<Vec<Command>>, command: Command) {
+    commands.update(|pending| {
+        let mut pending = pending.clone();
+        pending.push(command);
+        Some(pending)
+    });
+}
+
 #[topo::nested]
-fn describe_node(
-    name: &str,
-    style: Option<Style>,
-    attributes: Vec<(&'static str, String)>,
-    has_children: bool,
-) -> Node<Span> {
+fn describe_node(node: &NodeSnapshot) -> Node<Span> {
     mox! {
         <span>
             "<"
             <span style={NAME_STYLE}>
-                {% "{}", name}
+                {% "{}", node.name}
             </span>
-            {style.map(|style| mox! {
+            {if node.styles.is_empty() { None } else { Some(mox! {
                 <span>
                     <span style={ATTR_STYLE}>" style"</span>
                     "="
-                    <span style={CONTENT_STYLE}>{% "{}", style.name()}</span>
+                    <span style={CONTENT_STYLE}>
+                        {% "{}", node.styles.join(", ")}
+                    </span>
                 </span>
-            })}
-            {attributes.iter().map(|(name, value)| mox! {
+            })}}
+            {if node.classes.is_empty() { None } else { Some(mox! {
+                <span>
+                    <span style={ATTR_STYLE}>" class"</span>
+                    "="
+                    <span style={CONTENT_STYLE}>{% "{:?}", node.classes.join(" ")}</span>
+                </span>
+            })}}
+            {node.attributes.iter().map(|(name, value)| mox! {
                 <span>
                     <span style={ATTR_STYLE}>{% " {}", name}</span>
                     "="
                     <span style={CONTENT_STYLE}>{value.clone()}</span>
                 </span>
             }).collect::<Vec<_>>()}
-            {% "{}", if has_children { "" } else { " /" }}
+            {% "{}", if node.children.is_empty() { " /" } else { "" }}
             ">"
         </span>
     }
 }
 
 #[topo::nested]
-fn node_view(node: NodeRef) -> Node<View> {
-    if let Some(style) = node.style() {
-        if style == SENTINEL_STYLE {
-            return mox! {
-                <view style={NODE_STYLE}>
-                    <describe_node _=("devtools", None, vec![], false) />
-                </view>
-            };
-        }
-    }
+fn node_view(
+    node: &NodeSnapshot,
+    selected: Option<u64>,
+    commands: Key<Vec<Command>>,
+) -> Node<View> {
+    let has_children = !node.children.is_empty();
 
-    let name = node.name();
-    let has_children = node.children().next().is_some();
+    let id = node.id;
+    let style = if selected == Some(id) {
+        SELECTED_STYLE
+    } else {
+        NAME_BUTTON_STYLE
+    };
+    let on_click = {
+        let commands = commands.clone();
+        move |_event: &ClickEvent| queue(&commands, Command::Inspect(Some(id)))
+    };
 
     mox! {
         <view style={NODE_STYLE}>
-            <describe_node _=(name, node.style(), node.attributes(), has_children) />
+            <button style={style} on_click={on_click}>
+                <describe_node _=(node) />
+            </button>
             <view style={CHILD_STYLE}>
-                {node.children().map(|child| match child {
-                    DynamicNode::Node(child) => mox! {
+                {node.children.iter().map(|child| match child {
+                    ChildSnapshot::Node(child) => mox! {
                         <view style={VIEW}>
-                            <node_view _=(child) />
+                            <node_view _=(child, selected, commands.clone()) />
                         </view>
                     },
-                    DynamicNode::Text(text) => mox! {
+                    ChildSnapshot::Text(text) => mox! {
                         <view style={CONTENT_STYLE}>
                             <span>{% "{:?}", text}</span>
                         </view>
@@ -118,7 +272,7 @@ fn node_view(node: NodeRef) -> Node<View> {
                 <span>
                     "</"
                     <span style={NAME_STYLE}>
-                        {% "{}", name}
+                        {% "{}", node.name}
                     </span>
                     ">"
                 </span>
@@ -129,37 +283,325 @@ fn node_view(node: NodeRef) -> Node<View> {
     }
 }
 
+#[topo::nested]
+fn declaration_view(name: &str, value: &str) -> Node<View> {
+    mox! {
+        <view style={VIEW}>
+            <span>
+                <span style={ATTR_STYLE}>{% "{}", name}</span>
+                ": "
+                <span style={CONTENT_STYLE}>{% "{}", value}</span>
+            </span>
+        </view>
+    }
+}
+
+/// Toggles styling the selected node as if it were in `state`.
+#[topo::nested]
+fn force_button(
+    label: &str,
+    state: ElementState,
+    node: u64,
+    forced: ElementStates,
+    commands: Key<Vec<Command>>,
+) -> Node<Button> {
+    let active = forced.contains(state);
+    let on_click = move |_event: &ClickEvent| {
+        let mut states = forced;
+        if active {
+            states.remove(state);
+        } else {
+            states.insert(state);
+        }
+        queue(&commands, Command::ForceStates(node, states));
+    };
+
+    mox! {
+        <button style={PICK_STYLE} class={if active { Some("active") } else { None }} on_click={on_click}>
+            <span>{% "{}", label}</span>
+        </button>
+    }
+}
+
+/// Shows the rules that matched the selected node, in the order they
+/// were applied, followed by the values they computed to.
+#[topo::nested]
+fn style_panel(
+    style: StyleSnapshot,
+    forced: ElementStates,
+    commands: Key<Vec<Command>>,
+) -> Node<View> {
+    mox! {
+        <view style={PANEL_STYLE}>
+            <view style={TOOLBAR_STYLE}>
+                <span style={HEADING_STYLE}>"Force state "</span>
+                <force_button _=(":hover", ElementState::Hover, style.node, forced, commands.clone()) />
+                <force_button _=(":press", ElementState::Press, style.node, forced, commands.clone()) />
+                <force_button _=(":focus", ElementState::Focus, style.node, forced, commands.clone()) />
+                <force_button _=(":checked", ElementState::Checked, style.node, forced, commands.clone()) />
+                <force_button _=(":open", ElementState::Open, style.node, forced, commands) />
+            </view>
+            <span style={HEADING_STYLE}>"Matched rules"</span>
+            {style.rules.iter().map(|rule| mox! {
+                <view style={VIEW}>
+                    <span>
+                        <span style={NAME_STYLE}>{% "{}", rule.style}</span>
+                        {rule.selector.as_ref().map(|selector| mox! {
+                            <span style={SELECTOR_STYLE}>{% " if {}", selector}</span>
+                        })}
+                    </span>
+                    <view style={CHILD_STYLE}>
+                        {rule.attributes.iter().map(|(name, value)| mox! {
+                            <declaration_view _=(name, value) />
+                        }).collect::<Vec<_>>()}
+                    </view>
+                </view>
+            }).collect::<Vec<_>>()}
+            <span style={HEADING_STYLE}>"Computed"</span>
+            <view style={CHILD_STYLE}>
+                {style.computed.iter().map(|(name, value)| mox! {
+                    <declaration_view _=(name, value) />
+                }).collect::<Vec<_>>()}
+            </view>
+        </view>
+    }
+}
+
+/// Formats a length from the layout, leaving out float noise.
+fn number(value: f32) -> String {
+    format!("{}", (value * 100.0).round() / 100.0)
+}
+
+/// Zero sides are shown as a dash so that the set ones stand out.
+fn side(value: f32) -> String {
+    if value == 0.0 {
+        "-".to_owned()
+    } else {
+        number(value)
+    }
+}
+
+/// One of the nested boxes of the box model diagram, with the sizes of
+/// its sides around `inner`.
+#[topo::nested]
+fn box_layer(label: &str, style: Style, sides: Sides, inner: Node<View>) -> Node<View> {
+    mox! {
+        <view style={style}>
+            <view style={BOX_LABEL_STYLE}>
+                <span style={HEADING_STYLE}>{% "{}", label}</span>
+            </view>
+            <span>{side(sides.top)}</span>
+            <view style={BOX_ROW_STYLE}>
+                <view style={BOX_SIDE_STYLE}>
+                    <span>{side(sides.left)}</span>
+                </view>
+                {inner}
+                <view style={BOX_SIDE_STYLE}>
+                    <span>{side(sides.right)}</span>
+                </view>
+            </view>
+            <span>{side(sides.bottom)}</span>
+        </view>
+    }
+}
+
+/// Shows the margin, border, padding and content boxes of a node like
+/// browser devtools do.
+#[topo::nested]
+fn box_model(layout: LayoutSnapshot) -> Node<View> {
+    let content_width = layout.width
+        - layout.border.left
+        - layout.border.right
+        - layout.padding.left
+        - layout.padding.right;
+    let content_height = layout.height
+        - layout.border.top
+        - layout.border.bottom
+        - layout.padding.top
+        - layout.padding.bottom;
+
+    let content = mox! {
+        <view style={CONTENT_BOX_STYLE}>
+            <span>{% "{} × {}", number(content_width), number(content_height)}</span>
+        </view>
+    };
+    let padding = box_layer("padding", PADDING_BOX_STYLE, layout.padding, content);
+    let border = box_layer("border", BORDER_BOX_STYLE, layout.border, padding);
+    let margin = box_layer("margin", MARGIN_BOX_STYLE, layout.margin, border);
+
+    mox! {
+        <view style={VIEW}>
+            <span style={HEADING_STYLE}>"Box model"</span>
+            {margin}
+        </view>
+    }
+}
+
+/// Dumps a layout tree with the rect of every node and line of text.
+#[topo::nested]
+fn layout_view(layout: LayoutSnapshot) -> Node<View> {
+    let margin = layout.margin;
+    let has_margin =
+        margin.top != 0.0 || margin.right != 0.0 || margin.bottom != 0.0 || margin.left != 0.0;
+
+    mox! {
+        <view style={VIEW}>
+            <span>
+                {match layout.text {
+                    Some(ref text) => mox! {
+                        <span style={CONTENT_STYLE}>{% "{:?}", text}</span>
+                    },
+                    None => mox! {
+                        <span style={NAME_STYLE}>{% "{}", layout.name}</span>
+                    },
+                }}
+                <span style={ATTR_STYLE}>
+                    {% " {} × {} at ({}, {})", number(layout.width), number(layout.height), number(layout.x), number(layout.y)}
+                </span>
+                {if has_margin { Some(mox! {
+                    <span style={ATTR_STYLE}>
+                        {% " margin {} {} {} {}", number(margin.top), number(margin.right), number(margin.bottom), number(margin.left)}
+                    </span>
+                })} else {
+                    None
+                }}
+            </span>
+            <view style={CHILD_STYLE}>
+                {layout.children.iter().map(|child| mox! {
+                    <layout_view _=(child.clone()) />
+                }).collect::<Vec<_>>()}
+            </view>
+        </view>
+    }
+}
+
+/// Shows the box model of the selected node and dumps its layout, or
+/// dumps the layout of every window if nothing is selected.
+#[topo::nested]
+fn layout_panel(layouts: Vec<LayoutSnapshot>, selected: Option<u64>) -> Node<View> {
+    let (model, dumped) = match selected {
+        Some(id) => match layouts.iter().find_map(|layout| layout.find(id)) {
+            Some(layout) => (Some(layout.clone()), vec![layout.clone()]),
+            None => (None, vec![]),
+        },
+        None => (None, layouts),
+    };
+
+    mox! {
+        <view style={PANEL_STYLE}>
+            {model.map(|layout| mox! {
+                <box_model _=(layout) />
+            })}
+            <span style={HEADING_STYLE}>"Layout"</span>
+            {dumped.into_iter().map(|layout| mox! {
+                <layout_view _=(layout) />
+            }).collect::<Vec<_>>()}
+        </view>
+    }
+}
+
+/// Shows an inspection, queueing the commands that clicking it makes in
+/// `commands`.
+#[topo::nested]
+pub fn inspector(inspection: Inspection, commands: Key<Vec<Command>>) -> Node<View> {
+    let root = match inspection.root {
+        Some(ref root) => root,
+        None => {
+            return mox! {
+                <view style={VIEW}>
+                    <span>
+                        "Loading"
+                    </span>
+                </view>
+            }
+        }
+    };
+
+    let active = inspection.picking;
+    let on_pick = {
+        let commands = commands.clone();
+        move |_event: &ClickEvent| queue(&commands, Command::SetPicking(!active))
+    };
+    let style = inspection
+        .style
+        .clone()
+        .filter(|style| Some(style.node) == inspection.selected);
+
+    mox! {
+        <view style={VIEW}>
+            <view style={TOOLBAR_STYLE}>
+                <button style={PICK_STYLE} class={if active { Some("active") } else { None }} on_click={on_pick}>
+                    <span>{% "{}", if active { "Click an element…" } else { "Pick element" }}</span>
+                </button>
+            </view>
+            <node_view _=(root, inspection.selected, commands.clone()) />
+            {style.map(|style| {
+                let forced = inspection.forced.get(&style.node).copied().unwrap_or_default();
+                mox! {
+                    <style_panel _=(style, forced, commands.clone()) />
+                }
+            })}
+            <layout_panel _=(inspection.layouts.clone(), inspection.selected) />
+        </view>
+    }
+}
+
 #[derive(Debug)]
 struct Tools {
-    root: Key<Option<AnyNode>>,
+    root: Key<Option<NodeSnapshot>>,
+    layouts: Key<Vec<LayoutSnapshot>>,
 }
 
 impl DevTools for Tools {
-    fn on_update(&mut self, node: AnyNode) {
-        debug!("new node {}", node.name());
+    fn on_update(&mut self, node: NodeSnapshot) {
+        debug!("new node {}", node.name);
         self.root.set(Some(node));
     }
+
+    fn on_layout(&mut self, layouts: Vec<LayoutSnapshot>) {
+        self.layouts.set(layouts);
+    }
 }
 
+/// Devtools for the app they're part of, usually placed next to the
+/// rest of a window's content.
 #[topo::nested]
 pub fn devtools() -> Node<View> {
     let (current_root, root) = state(|| None);
+    let (current_layouts, layouts) = state(Vec::new);
+    let (current_inspection, inspection) = state(Inspection::default);
+    let (pending, commands) = state(Vec::new);
 
-    register_devtools(Tools { root });
+    let handle = moxie::once(|| register_devtools(Tools { root, layouts }));
 
-    if let Some(ref node) = *current_root {
-        mox! {
-            <view style={SENTINEL_STYLE}>
-                <node_view _=(node.into()) />
-            </view>
-        }
-    } else {
-        mox! {
-            <view style={SENTINEL_STYLE}>
-                <span>
-                    "Loading"
-                </span>
-            </view>
+    // Click handlers run outside of the devtools' environment, so the
+    // commands they queue are applied here instead.
+    let mut pending = (*pending).clone();
+    if let Some(id) = take_picked() {
+        pending.push(Command::Inspect(Some(id)));
+    }
+    let mut current = (*current_inspection).clone();
+    if !pending.is_empty() {
+        for command in &pending {
+            current.apply(command);
+            apply_command(command);
         }
+        inspection.set(current.clone());
+        commands.set(vec![]);
     }
+
+    current.root = (*current_root).clone();
+    current.layouts = (*current_layouts).clone();
+    current.picking = picking();
+    current.style = current
+        .selected
+        .and_then(|id| inspected_style().map(|style| style.snapshot(id)));
+
+    let view = mox! {
+        <view style={ROOT_STYLE}>
+            <inspector _=(current, commands) />
+        </view>
+    };
+    handle.set_root(Some(view.id()));
+    view
 }
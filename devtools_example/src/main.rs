@@ -61,6 +61,9 @@ fn my_app() -> Node<App> {
 
 fn main() {
     // The entrypoint to the application is creating a runtime and starting it.
-    let runtime = moxie_native::Runtime::new(my_app);
+    let mut runtime = moxie_native::Runtime::new(my_app);
+    // Also let the `moxie-devtools` viewer connect from another process.
+    let token = runtime.serve_devtools(DEFAULT_PORT);
+    println!("Open devtools with: moxie-devtools {}", token);
     runtime.start();
 }
@@ -18,6 +18,12 @@ define_style! {
         if state: hover {
             background_color: rgb(112, 167, 255),
         }
+
+        // Elements can be given classes with `class="a b"`, and several
+        // styles can be layered with `style={[BASE, VARIANT]}`.
+        if class: danger {
+            background_color: rgb(245, 66, 66),
+        }
     };
 }
 
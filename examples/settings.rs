@@ -0,0 +1,158 @@
+#![recursion_limit = "256"]
+
+use moxie::state;
+use moxie_native::prelude::*;
+
+define_style! {
+    static CONTAINER_STYLE = {
+        padding: 10 px,
+        background_color: rgb(238, 238, 238),
+    };
+
+    static ROW_STYLE = {
+        direction: horizontal,
+        align_items: center,
+        padding: 4 px,
+    };
+
+    static DIALOG_STYLE = {
+        margin: auto,
+        padding: 16 px,
+        background_color: rgb(255, 255, 255),
+        corner_radius: 6 px,
+    };
+
+    static SWITCH_STYLE = {
+        if state: checked {
+            background_color: rgb(60, 170, 90),
+        }
+    };
+}
+
+const SIZES: [&str; 3] = ["Small", "Medium", "Large"];
+
+#[topo::nested]
+fn settings() -> Node<App> {
+    let (notifications, set_notifications) = state(|| true);
+    let (dark_mode, set_dark_mode) = state(|| false);
+    let (size, set_size) = state(|| 1);
+    let (volume, set_volume) = state(|| 50.0);
+    let (language, set_language) = state(|| "en".to_owned());
+    let (notes, set_notes) = state(String::new);
+    let (confirming, set_confirming) = state(|| false);
+
+    let ask = set_confirming.clone();
+    let cancel = set_confirming.clone();
+    let reset = {
+        let set_notifications = set_notifications.clone();
+        let set_dark_mode = set_dark_mode.clone();
+        let set_volume = set_volume.clone();
+        move |_: &ClickEvent| {
+            set_notifications.set(true);
+            set_dark_mode.set(false);
+            set_volume.set(50.0);
+            set_confirming.set(false);
+        }
+    };
+
+    mox! {
+        <app>
+            <window title="Settings" size={(420.0, 320.0)} min_size={(320.0, 240.0)}>
+                <view style={CONTAINER_STYLE}>
+                    <view style={ROW_STYLE}>
+                        <checkbox
+                            label="Notifications"
+                            checked={*notifications}
+                            on_change={move |event: &ChangeEvent<bool>| set_notifications.set(event.value)}
+                        />
+                        <span>"Notifications"</span>
+                    </view>
+                    <view style={ROW_STYLE}>
+                        <switch
+                            style={SWITCH_STYLE}
+                            label="Dark mode"
+                            checked={*dark_mode}
+                            on_change={move |event: &ChangeEvent<bool>| set_dark_mode.set(event.value)}
+                        />
+                        <span>"Dark mode"</span>
+                    </view>
+                    <view style={ROW_STYLE}>
+                        <slider
+                            label="Volume"
+                            max={100.0}
+                            step={5.0}
+                            value={*volume}
+                            on_change={move |event: &ChangeEvent<f64>| set_volume.set(event.value)}
+                        />
+                        <span>{format!("Volume {}", *volume)}</span>
+                    </view>
+                    <view style={ROW_STYLE}>
+                        <select
+                            label="Language"
+                            value={&*language}
+                            on_change={move |event: &ChangeEvent<String>| set_language.set(event.value.clone())}
+                        >
+                            <option value="en">"English"</option>
+                            <option value="fr">"Français"</option>
+                            <option value="de">"Deutsch"</option>
+                        </select>
+                        <span>"Language"</span>
+                    </view>
+                    {SIZES.iter().enumerate().map(|(index, name)| {
+                        let set_size = set_size.clone();
+                        mox! {
+                            <view style={ROW_STYLE}>
+                                <radio
+                                    group="size"
+                                    label={*name}
+                                    checked={*size == index}
+                                    on_change={move |_: &ChangeEvent<bool>| set_size.set(index)}
+                                />
+                                <span>{*name}</span>
+                            </view>
+                        }
+                    })}
+                    <view style={ROW_STYLE}>
+                        <textarea
+                            label="Notes"
+                            value={&*notes}
+                            on_change={move |event: &ChangeEvent<String>| set_notes.set(event.value.clone())}
+                        />
+                    </view>
+                    <view style={ROW_STYLE}>
+                        <button
+                            title="Restore the default settings"
+                            on_click={move |_: &ClickEvent| ask.set(true)}
+                        >
+                            <span>"Reset"</span>
+                        </button>
+                    </view>
+                    {if *confirming {
+                        Some(mox! {
+                            <overlay modal={true} label="Reset settings">
+                                <view style={DIALOG_STYLE}>
+                                    <span>"Reset notifications, dark mode and volume?"</span>
+                                    <view style={ROW_STYLE}>
+                                        <button on_click={move |_: &ClickEvent| cancel.set(false)}>
+                                            <span>"Cancel"</span>
+                                        </button>
+                                        <button on_click={reset}>
+                                            <span>"Reset"</span>
+                                        </button>
+                                    </view>
+                                </view>
+                            </overlay>
+                        })
+                    } else {
+                        None
+                    }}
+                </view>
+            </window>
+        </app>
+    }
+}
+
+fn main() {
+    let runtime = moxie_native::Runtime::new(settings);
+    runtime.start();
+}
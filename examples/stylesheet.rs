@@ -0,0 +1,52 @@
+use moxie::state;
+use moxie_native::prelude::*;
+
+/// The styles this app uses from the stylesheet.
+#[derive(Copy, Clone)]
+struct Styles {
+    container: Style,
+    button: Style,
+}
+
+#[topo::nested]
+fn my_app(styles: Styles) -> Node<App> {
+    let (current_click_count, click_count) = state(|| 0usize);
+
+    let on_click = move |_: &ClickEvent| {
+        click_count.update(|count| Some(count + 1));
+    };
+
+    mox! {
+        <app>
+            <window title="Stylesheet Demo">
+                <view style={styles.container}>
+                    <button style={styles.button} on_click={on_click}>
+                        <span>
+                            "Clicks: "
+                            {% "{}", current_click_count}
+                        </span>
+                    </button>
+                </view>
+            </window>
+        </app>
+    }
+}
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/stylesheet.style");
+    let sheet = match Stylesheet::load(path) {
+        Ok(sheet) => sheet,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let styles = Styles {
+        container: sheet.style("CONTAINER"),
+        button: sheet.style("BUTTON"),
+    };
+
+    let mut runtime = moxie_native::Runtime::new(move || my_app(styles));
+    runtime.watch_stylesheet(sheet);
+    runtime.start();
+}
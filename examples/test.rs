@@ -5,6 +5,7 @@ use moxie_native::prelude::*;
 
 define_style! {
     static CONTAINER_STYLE = {
+        var(accent): rgb(238, 238, 238),
         padding: 10 px,
     };
 
@@ -13,7 +14,15 @@ define_style! {
     };
 
     static BUTTON_STYLE = {
-        background_color: rgb(238, 238, 238),
+        background_color: var(accent, rgb(200, 200, 200)),
+    };
+
+    static ROUNDED_STYLE = {
+        corner_radius: 5 px,
+
+        if class: danger {
+            background_color: rgb(255, 200, 200),
+        }
     };
 
     static VIEW1_STYLE = {
@@ -37,7 +46,7 @@ define_style! {
 
     static SQUARE_STYLE = {
         background_color: rgb(0, 0, 0),
-        display: block,
+        display: inline_block,
         width: 10 px,
         height: 10 px,
     };
@@ -62,13 +71,18 @@ fn foo() -> Node<App> {
                         "foo bar baz"
                         " the quick brown fox "<span style={H1_STYLE}>"jumps"</span><view style={SQUARE_STYLE}></view>" over the lazy dog"
                     </span>
-                    <button on_click={on_click} style={BUTTON_STYLE}>
+                    <button on_click={on_click} style={[BUTTON_STYLE, ROUNDED_STYLE]} class="danger">
                         <span>
                             "Clicked " {% "{}", current_count} " times)"
                         </span>
                     </button>
                     <view style={VIEW1_STYLE}></view>
-                    <view style={VIEW2_STYLE}></view>
+                    <view style={VIEW2_STYLE} inline_style={
+                        InlineStyle::new().set(
+                            style_impl::attribute::width(),
+                            style_impl::pixels(250.0 + 10.0 * *current_count as f64),
+                        )
+                    }></view>
                 </view>
             </window>
         </app>
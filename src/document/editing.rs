@@ -0,0 +1,424 @@
+//! Editing the text of elements like `<textarea>`. The document does it
+//! for them, since the caret, selection and history have to outlive the
+//! elements, which are rebuilt on every update.
+
+use crate::dom::input::Key;
+use std::ops::Range;
+
+/// What an edit did, so that a run of typing or deleting is undone as
+/// one step.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+struct Snapshot {
+    text: String,
+    caret: usize,
+    anchor: usize,
+}
+
+/// The text being edited along with the caret and selection, which are
+/// byte offsets into it, and the visual lines it was last laid out as.
+#[derive(Debug)]
+pub(crate) struct TextEditor {
+    text: String,
+    /// The element's value when it was last synced, to tell the app
+    /// changing it apart from it not having caught up with edits yet.
+    value: String,
+    caret: usize,
+    /// The other end of the selection, which is empty when it's at the
+    /// caret.
+    anchor: usize,
+    /// Where the caret is kept horizontally while moving up and down, so
+    /// that it comes back after passing through short lines.
+    goal_x: Option<f32>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+    lines: Vec<Range<usize>>,
+    /// The first visible line.
+    scroll: usize,
+    /// How many lines fit in the element.
+    visible: usize,
+    line_height: f32,
+    /// Set when the element is pressed, so that the first pointer move
+    /// puts the caret down instead of selecting.
+    pub(crate) pressed: bool,
+}
+
+impl TextEditor {
+    pub fn new(value: &str) -> TextEditor {
+        TextEditor {
+            text: value.to_owned(),
+            value: value.to_owned(),
+            caret: value.len(),
+            anchor: value.len(),
+            goal_x: None,
+            undo: vec![],
+            redo: vec![],
+            last_edit: None,
+            lines: vec![0..value.len()],
+            scroll: 0,
+            visible: 1,
+            line_height: 0.0,
+            pressed: false,
+        }
+    }
+
+    /// Takes the element's value when the app changed it.
+    pub fn sync(&mut self, value: &str) {
+        if value == self.value {
+            return;
+        }
+        self.value = value.to_owned();
+        if value != self.text {
+            self.text = value.to_owned();
+            self.caret = floor_boundary(&self.text, self.caret);
+            self.anchor = floor_boundary(&self.text, self.anchor);
+            self.last_edit = None;
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    pub fn lines(&self) -> &[Range<usize>] {
+        &self.lines
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Records the visual lines the text was laid out as, and scrolls so
+    /// that the caret is on one of the `visible` ones.
+    pub fn set_lines(&mut self, lines: Vec<Range<usize>>, visible: usize, line_height: f32) {
+        self.lines = lines;
+        self.visible = visible.max(1);
+        self.line_height = line_height;
+        let line = self.line_of(self.caret);
+        if line < self.scroll {
+            self.scroll = line;
+        } else if line >= self.scroll + self.visible {
+            self.scroll = line + 1 - self.visible;
+        }
+        self.scroll = self
+            .scroll
+            .min(self.lines.len().saturating_sub(self.visible));
+    }
+
+    /// The visual line `offset` is on.
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.start <= offset)
+            .unwrap_or(0)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            caret: self.caret,
+            anchor: self.anchor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.caret = snapshot.caret;
+        self.anchor = snapshot.anchor;
+        self.goal_x = None;
+        self.last_edit = None;
+    }
+
+    /// Saves the text for undo before an edit, unless the edit continues
+    /// the previous one.
+    fn record(&mut self, kind: EditKind) {
+        let continues =
+            kind != EditKind::Other && self.last_edit == Some(kind) && self.caret == self.anchor;
+        if !continues {
+            self.undo.push(self.snapshot());
+        }
+        self.redo.clear();
+        self.last_edit = Some(kind);
+        self.goal_x = None;
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.text.replace_range(range.clone(), text);
+        self.caret = range.start + text.len();
+        self.anchor = self.caret;
+    }
+
+    /// Replaces the selection with `text`.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let selection = self.selection();
+        let kind = if selection.is_empty() && !text.contains(char::is_whitespace) {
+            EditKind::Insert
+        } else {
+            EditKind::Other
+        };
+        self.record(kind);
+        self.replace(selection, text);
+        true
+    }
+
+    /// Deletes the selection, or else the character before the caret, or
+    /// after it if `forward`.
+    pub fn delete(&mut self, forward: bool) -> bool {
+        let mut range = self.selection();
+        if range.is_empty() {
+            range = if forward {
+                self.caret..next_boundary(&self.text, self.caret)
+            } else {
+                previous_boundary(&self.text, self.caret)..self.caret
+            };
+        }
+        if range.is_empty() {
+            return false;
+        }
+        self.record(if self.caret == self.anchor {
+            EditKind::Delete
+        } else {
+            EditKind::Other
+        });
+        self.replace(range, "");
+        true
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(snapshot) => {
+                self.redo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(snapshot) => {
+                self.undo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.caret = self.text.len();
+        self.last_edit = None;
+    }
+
+    /// Puts the caret at `offset`, selecting from the anchor if `extend`.
+    pub fn move_to(&mut self, offset: usize, extend: bool) {
+        self.caret = offset;
+        if !extend {
+            self.anchor = offset;
+        }
+        self.last_edit = None;
+    }
+
+    /// Moves the caret for the key, by visual line for the vertical ones,
+    /// selecting as it goes if `extend`. `measure` gives the width of
+    /// text. Returns whether the key moves the caret.
+    pub fn move_caret(&mut self, key: Key, extend: bool, measure: &dyn Fn(&str) -> f32) -> bool {
+        let selection = self.selection();
+        let line = self.line_of(self.caret);
+        let offset = match key {
+            Key::Left if !extend && !selection.is_empty() => selection.start,
+            Key::Right if !extend && !selection.is_empty() => selection.end,
+            Key::Left => previous_boundary(&self.text, self.caret),
+            Key::Right => next_boundary(&self.text, self.caret),
+            Key::Home => self.lines[line].start,
+            Key::End => self.lines[line].end,
+            Key::Up | Key::Down | Key::PageUp | Key::PageDown => {
+                let goal_x = match self.goal_x {
+                    Some(goal_x) => goal_x,
+                    None => self.x_of(self.caret, measure),
+                };
+                let target = match key {
+                    Key::Up => line.checked_sub(1),
+                    Key::Down => Some(line + 1).filter(|&line| line < self.lines.len()),
+                    Key::PageUp => Some(line.saturating_sub(self.visible)),
+                    _ => Some((line + self.visible).min(self.lines.len() - 1)),
+                };
+                let offset = match target {
+                    Some(target) => self.offset_in_line(target, goal_x, measure),
+                    None if key == Key::Up => 0,
+                    None => self.text.len(),
+                };
+                self.move_to(offset, extend);
+                self.goal_x = Some(goal_x);
+                return true;
+            }
+            Key::Escape | Key::Backspace | Key::Delete | Key::Undo | Key::Redo | Key::SelectAll => {
+                return false
+            }
+        };
+        self.move_to(offset, extend);
+        self.goal_x = None;
+        true
+    }
+
+    /// How far `offset` is from the start of its visual line.
+    pub fn x_of(&self, offset: usize, measure: &dyn Fn(&str) -> f32) -> f32 {
+        let line = &self.lines[self.line_of(offset)];
+        let end = offset.min(line.end).max(line.start);
+        measure(&self.text[line.start..end])
+    }
+
+    /// The offset on visual line `line` closest to `x` from its start.
+    fn offset_in_line(&self, line: usize, x: f32, measure: &dyn Fn(&str) -> f32) -> usize {
+        let range = self.lines[line].clone();
+        let text = &self.text[range.clone()];
+        let boundaries = text
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(Some(text.len()))
+            .collect::<Vec<_>>();
+        // Widths only grow along the line, so the closest boundary is
+        // found by bisecting.
+        let after = boundaries.partition_point(|&end| measure(&text[..end]) < x);
+        let closest = match after {
+            0 => 0,
+            after if after == boundaries.len() => after - 1,
+            after => {
+                let before = measure(&text[..boundaries[after - 1]]);
+                let next = measure(&text[..boundaries[after]]);
+                if x - before < next - x {
+                    after - 1
+                } else {
+                    after
+                }
+            }
+        };
+        range.start + boundaries[closest]
+    }
+
+    /// The offset closest to the point, relative to the start of the
+    /// first visible line.
+    pub fn offset_at(&self, x: f32, y: f32, measure: &dyn Fn(&str) -> f32) -> usize {
+        let row = if self.line_height > 0.0 {
+            (y / self.line_height).floor().max(0.0) as usize
+        } else {
+            0
+        };
+        let line = (self.scroll + row).min(self.lines.len() - 1);
+        self.offset_in_line(line, x, measure)
+    }
+}
+
+fn floor_boundary(text: &str, mut offset: usize) -> usize {
+    offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn previous_boundary(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+fn next_boundary(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map_or(offset, |ch| offset + ch.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every character is 10 pixels wide.
+    fn measure(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    fn editor(text: &str, lines: Vec<Range<usize>>) -> TextEditor {
+        let mut editor = TextEditor::new(text);
+        editor.set_lines(lines, 2, 20.0);
+        editor
+    }
+
+    #[test]
+    fn moves_by_visual_line() {
+        // "hello world" wrapped after "hello ", then a hard line "ab".
+        let mut editor = editor("hello world\nab", vec![0..6, 6..11, 12..14]);
+        editor.move_to(9, false);
+        assert!(editor.move_caret(Key::Up, false, &measure));
+        assert_eq!(editor.caret(), 3);
+        assert!(editor.move_caret(Key::Down, false, &measure));
+        assert!(editor.move_caret(Key::Down, false, &measure));
+        assert_eq!(editor.caret(), 14);
+        assert!(editor.move_caret(Key::Up, false, &measure));
+        assert_eq!(editor.caret(), 9);
+        assert!(editor.move_caret(Key::Home, true, &measure));
+        assert_eq!(editor.selection(), 6..9);
+    }
+
+    #[test]
+    fn scrolls_to_the_caret() {
+        let mut editor = editor("a\nb\nc\nd", vec![0..1, 2..3, 4..5, 6..7]);
+        assert_eq!(editor.scroll(), 2);
+        editor.move_to(0, false);
+        editor.set_lines(editor.lines().to_vec(), 2, 20.0);
+        assert_eq!(editor.scroll(), 0);
+        assert_eq!(editor.offset_at(15.0, 25.0, &measure), 3);
+    }
+
+    #[test]
+    fn undoes_runs_of_typing() {
+        let mut editor = editor("", vec![0..0]);
+        editor.insert("a");
+        editor.insert("b");
+        editor.insert(" ");
+        editor.insert("c");
+        assert_eq!(editor.text(), "ab c");
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "ab ");
+        assert!(editor.undo());
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "");
+        assert!(!editor.undo());
+        assert!(editor.redo());
+        assert_eq!(editor.text(), "ab");
+        editor.select_all();
+        editor.delete(false);
+        assert_eq!(editor.text(), "");
+        assert!(!editor.redo());
+    }
+
+    #[test]
+    fn keeps_edits_the_app_has_not_caught_up_with() {
+        let mut editor = editor("ab", vec![0..2]);
+        editor.insert("c");
+        editor.sync("ab");
+        assert_eq!(editor.text(), "abc");
+        editor.sync("ABC");
+        assert_eq!(editor.text(), "ABC");
+        assert_eq!(editor.caret(), 3);
+    }
+}
@@ -1,45 +1,135 @@
-use crate::dom::element::{DynamicNode, ElementStates};
-use crate::dom::input::{InputEvent, State};
+use crate::dom::accessibility::{self, AccessibilityTree, Action};
+use crate::dom::devtools::{FrameTimings, LayoutSnapshot, NodeStyle, Phase};
+use crate::dom::element::{DynamicNode, ElementState, ElementStates};
+use crate::dom::input::{InputEvent, Key, PointerPosition, State};
 use crate::dom::node::{AnyNode, NodeRef};
-use crate::dom::{Node, Window};
-use crate::layout::{LayoutEngine, LayoutTreeNode, LogicalSize};
-use crate::style::ComputedValues;
+use crate::dom::{Node, Tooltip, Window};
+use crate::layout::{LayoutEngine, LayoutText, LayoutTreeNode, LogicalPoint, LogicalSize};
+use crate::style::{ComputedValues, Variables};
 use crate::util::equal_rc::EqualRc;
+use editing::TextEditor;
 use moxie::runtime::Runtime;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long the pointer has to rest on a node before its tooltip shows.
+const HOVER_DELAY: Duration = Duration::from_millis(500);
+
+/// The id of the node the shown tooltip is drawn in, which no node built
+/// by the app gets.
+const TOOLTIP_ID: u64 = u64::MAX;
 
 struct NodeState {
     node: AnyNode,
     states: ElementStates,
     computed_values: Option<ComputedValues>,
+    style_inputs: Option<styling::StyleInputs>,
+    variables: Variables,
+    parent: Option<u64>,
     live: bool,
 }
 
+/// The node with a tooltip the pointer is over, which gets a hover
+/// intent event once the pointer has rested on it for `HOVER_DELAY`.
+#[derive(Copy, Clone, Debug)]
+struct HoverIntent {
+    id: u64,
+    /// When the pointer last moved.
+    since: Instant,
+    /// Where the pointer last moved to, which is where the tooltip shows.
+    position: LogicalPoint,
+    shown: bool,
+}
+
 pub(crate) struct DocumentState {
     states: HashMap<u64, NodeState>,
     pub window: Node<Window>,
     pub content_size: LogicalSize,
     hovered_node: Option<u64>,
-    pressed_node: Option<u64>,
+    /// The node that was pressed, which gets pointer events until the
+    /// button is released, even when the pointer leaves it.
+    captured_node: Option<u64>,
+    focused_node: Option<u64>,
+    /// The node whose popup is open. Its children are laid out in a layer
+    /// above the rest of the window.
+    popup_node: Option<u64>,
+    hover_intent: Option<HoverIntent>,
+    /// The node the shown tooltip is drawn in, which is styled as a child
+    /// of the window but isn't part of the tree.
+    tooltip_node: Option<Node<Tooltip>>,
+    /// The nodes in the top layer, in tree order, which is the order
+    /// they're drawn in.
+    top_layer: Vec<u64>,
+    /// The modal node focus is kept inside, and the node that had focus
+    /// before it was shown.
+    modal_focus: Option<(u64, Option<u64>)>,
+    /// The state of editing the text of editable nodes.
+    editors: HashMap<u64, TextEditor>,
+    /// States that devtools style nodes as being in, on top of their own.
+    forced_states: HashMap<u64, ElementStates>,
+    /// Nodes restyled and events dispatched since devtools were last told.
+    restyled: Vec<u64>,
+    dispatched: Vec<(u64, InputEvent)>,
 }
 
 impl DocumentState {
-    pub fn walk_children(&mut self, node: NodeRef) {
+    pub fn new(window: Node<Window>, content_size: LogicalSize) -> DocumentState {
+        let mut state = DocumentState {
+            window,
+            content_size,
+            states: HashMap::new(),
+            hovered_node: None,
+            captured_node: None,
+            focused_node: None,
+            popup_node: None,
+            hover_intent: None,
+            tooltip_node: None,
+            top_layer: vec![],
+            modal_focus: None,
+            editors: HashMap::new(),
+            forced_states: HashMap::new(),
+            restyled: vec![],
+            dispatched: vec![],
+        };
+        state.walk_children((&state.window.clone()).into(), None);
+        state
+    }
+
+    pub fn walk_children(&mut self, node: NodeRef, parent: Option<u64>) {
         let entry = self.states.entry(node.id()).or_insert_with(|| NodeState {
             node: node.to_owned(),
             states: ElementStates::default(),
             computed_values: None,
+            style_inputs: None,
+            variables: Variables::default(),
+            parent: None,
             live: false,
         });
+        entry.parent = parent;
         entry.live = true;
+        if node.top_layer() {
+            self.top_layer.push(node.id());
+        }
 
         for child in node.children() {
-            if let DynamicNode::Node(node) = child {
-                self.walk_children(node)
+            if let DynamicNode::Node(child) = child {
+                self.walk_children(child, Some(node.id()))
             }
         }
     }
 
+    /// Whether the node with `id` is `ancestor` or inside it.
+    pub fn is_inside(&self, id: u64, ancestor: u64) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.states.get(&id).and_then(|state| state.parent);
+        }
+        false
+    }
+
     pub fn computed_values(&self, id: u64) -> &ComputedValues {
         self.states
             .get(&id)
@@ -49,97 +139,638 @@ impl DocumentState {
             .unwrap()
     }
 
+    /// The states the node is styled as being in.
     pub fn node_states(&self, id: u64) -> ElementStates {
-        self.states.get(&id).unwrap().states
+        let forced = self.forced_states.get(&id).copied().unwrap_or_default();
+        let state = self.states.get(&id).unwrap();
+        state.states | state.node.states() | forced
     }
 
     fn set_root(&mut self, window: Node<Window>) {
         self.window = window.clone();
 
-        self.walk_children((&window).into());
+        self.top_layer.clear();
+        self.walk_children((&window).into(), None);
         self.states
-            .retain(|_id, state| std::mem::replace(&mut state.live, false));
+            .retain(|id, state| *id == TOOLTIP_ID || std::mem::replace(&mut state.live, false));
+        if let Some(focused) = self.focused_node {
+            if !self.states.contains_key(&focused) {
+                self.focused_node = None;
+            }
+        }
+        if let Some(popup) = self.popup_node {
+            if !self.states.contains_key(&popup) {
+                self.popup_node = None;
+            }
+        }
+        if let Some(intent) = self.hover_intent {
+            if !self.states.contains_key(&intent.id) {
+                self.hover_intent = None;
+            }
+        }
+        let states = &self.states;
+        self.editors.retain(|id, _| states.contains_key(id));
+        self.trap_focus();
+    }
+
+    /// The nodes in the top layer, in the order they're drawn in.
+    pub fn top_layer(&self) -> Vec<AnyNode> {
+        self.top_layer
+            .iter()
+            .map(|id| self.states[id].node.clone())
+            .collect()
+    }
+
+    /// The topmost modal node, which blocks the rest of the window.
+    pub fn modal(&self) -> Option<u64> {
+        self.top_layer
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| self.states[id].node.modal())
+    }
+
+    /// Moves focus into the modal node when one is shown, and gives it
+    /// back to the node that had it before when it's gone.
+    fn trap_focus(&mut self) {
+        let modal = self.modal();
+        if self.modal_focus.map(|(id, _)| id) == modal {
+            return;
+        }
+        let before = match self.modal_focus.take() {
+            Some((_, before)) => before,
+            None => self.focused_node,
+        };
+        match modal {
+            Some(modal) => {
+                self.modal_focus = Some((modal, before));
+                let mut focusable = vec![];
+                collect_focusable((&self.states[&modal].node).into(), &mut focusable);
+                self.focus(focusable.first().map(|node| node.id));
+            }
+            None => {
+                let before = before.filter(|id| self.states.contains_key(id));
+                self.focus(before);
+            }
+        }
+    }
+
+    /// The editor for the editable `node`, which is synced with its value.
+    pub(crate) fn editor(&mut self, node: NodeRef) -> &mut TextEditor {
+        let value = node.value().unwrap_or_default();
+        let editor = self
+            .editors
+            .entry(node.id())
+            .or_insert_with(|| TextEditor::new(value));
+        editor.sync(value);
+        editor
+    }
+
+    /// Edits the text of the node with `id` if it's editable, telling it
+    /// when the text changed. Returns whether it's editable.
+    fn edit(&mut self, id: u64, edit: impl FnOnce(&mut TextEditor, f32) -> bool) -> bool {
+        let state = match self.states.get(&id) {
+            Some(state) if state.node.editable() => state,
+            _ => return false,
+        };
+        let node = state.node.clone();
+        let size = self.computed_values(id).text_size.get();
+        let editor = self.editor((&node).into());
+        if edit(editor, size) {
+            let value = editor.text().to_owned();
+            self.dispatch(id, InputEvent::Edited { value });
+        }
+        true
+    }
+
+    /// Types `text` into the focused node, if it's editable.
+    pub fn text_input(&mut self, text: &str) -> bool {
+        match self.focused_node {
+            Some(focused) => self.edit(focused, |editor, _| editor.insert(text)),
+            None => false,
+        }
     }
 
     pub fn set_size(&mut self, size: LogicalSize) {
         self.content_size = size;
     }
 
-    pub fn mouse_move(&mut self, hovered: Option<u64>) -> bool {
-        if self.pressed_node.is_some() {
-            return false;
+    /// Passes the event to the node with `id`, returning whether it was
+    /// found. Only one popup is open at a time, so one opening closes
+    /// the other.
+    fn dispatch(&mut self, id: u64, event: InputEvent) -> bool {
+        let open = match self.states.get_mut(&id) {
+            Some(state) => {
+                state.states = state.node.process(state.states, &event);
+                self.dispatched.push((id, event));
+                state.states.contains(ElementState::Open)
+            }
+            None => return false,
+        };
+        if open && self.popup_node != Some(id) {
+            if let Some(popup) = self.popup_node.replace(id) {
+                self.dispatch(popup, InputEvent::Dismissed);
+            }
+        } else if !open && self.popup_node == Some(id) {
+            self.popup_node = None;
+        }
+        true
+    }
+
+    /// The node whose popup is open.
+    pub fn popup(&self) -> Option<AnyNode> {
+        self.popup_node.map(|id| self.states[&id].node.clone())
+    }
+
+    /// Chooses the option with `id` for the node it's in, which closes
+    /// that node's popup. Returns whether `id` is an option.
+    fn choose(&mut self, id: u64) -> bool {
+        let state = match self.states.get(&id) {
+            Some(state) => state,
+            None => return false,
+        };
+        let (owner, value) = match (state.parent, state.node.value()) {
+            (Some(owner), Some(value)) if self.states[&owner].node.has_popup() => {
+                (owner, value.to_owned())
+            }
+            _ => return false,
+        };
+        self.dispatch(owner, InputEvent::Chosen { value });
+        self.dispatch(owner, InputEvent::Dismissed);
+        true
+    }
+
+    /// Chooses the option after the selected one in the node with `id`,
+    /// or the one before it if `reverse`.
+    fn choose_next(&mut self, id: u64, reverse: bool) -> bool {
+        let node = NodeRef::from(&self.states[&id].node);
+        let options = node
+            .children()
+            .filter_map(|child| child.node())
+            .filter_map(|child| child.value().map(ToOwned::to_owned))
+            .collect::<Vec<_>>();
+        let selected = node.selected_child().and_then(|child| {
+            let value = child.value()?;
+            options.iter().position(|option| option == value)
+        });
+        let next = match (selected, reverse) {
+            (Some(index), false) => options.get(index + 1),
+            (Some(index), true) => index.checked_sub(1).and_then(|index| options.get(index)),
+            (None, _) => options.first(),
+        };
+        match next.cloned() {
+            Some(value) => self.dispatch(id, InputEvent::Chosen { value }),
+            None => false,
+        }
+    }
+
+    /// The closest interactive node that's `id` or contains it, so that
+    /// hovering the parts of an element hovers the element.
+    fn interactive_ancestor(&self, id: u64) -> Option<u64> {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let state = self.states.get(&id)?;
+            if state.node.interactive() {
+                return Some(id);
+            }
+            current = state.parent;
+        }
+        None
+    }
+
+    /// The closest node with a tooltip that's `id` or contains it.
+    fn tooltip_ancestor(&self, id: u64) -> Option<u64> {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let state = self.states.get(&id)?;
+            if state.node.tooltip().is_some() {
+                return Some(id);
+            }
+            current = state.parent;
+        }
+        None
+    }
+
+    /// Restarts the hover delay as the pointer moves to `position` over
+    /// the node with `hovered` at `now`. Returns whether a tooltip was
+    /// hidden.
+    fn track_hover(&mut self, hovered: Option<u64>, position: LogicalPoint, now: Instant) -> bool {
+        let target = hovered.and_then(|id| self.tooltip_ancestor(id));
+        match self.hover_intent {
+            // A shown tooltip stays where it is while the pointer is over
+            // its node.
+            Some(ref intent) if Some(intent.id) == target && intent.shown => false,
+            Some(ref mut intent) if Some(intent.id) == target => {
+                intent.since = now;
+                intent.position = position;
+                false
+            }
+            _ => {
+                let shown = self.hover_intent.map_or(false, |intent| intent.shown);
+                self.hover_intent = target.map(|id| HoverIntent {
+                    id,
+                    since: now,
+                    position,
+                    shown: false,
+                });
+                shown
+            }
+        }
+    }
+
+    /// When the pointer will have rested long enough for a tooltip to
+    /// show, if one is waiting to.
+    pub fn hover_deadline(&self) -> Option<Instant> {
+        self.hover_intent
+            .filter(|intent| !intent.shown)
+            .map(|intent| intent.since + HOVER_DELAY)
+    }
+
+    /// Shows the tooltip the pointer is resting over once `now` is past
+    /// the hover delay, telling its node. Returns whether it was shown.
+    pub fn update_hover(&mut self, now: Instant) -> bool {
+        let intent = match self.hover_intent {
+            Some(ref mut intent) if !intent.shown && intent.since + HOVER_DELAY <= now => intent,
+            _ => return false,
+        };
+        intent.shown = true;
+        let id = intent.id;
+        self.dispatch(id, InputEvent::HoverIntent)
+    }
+
+    /// The text of the shown tooltip and where the pointer was when it
+    /// showed.
+    pub fn tooltip(&self) -> Option<(&str, LogicalPoint)> {
+        let intent = self.hover_intent.filter(|intent| intent.shown)?;
+        let text = self.states.get(&intent.id)?.node.tooltip()?;
+        Some((text, intent.position))
+    }
+
+    /// The node the shown tooltip is drawn in, as of the last restyle.
+    pub fn tooltip_node(&self) -> Option<Node<Tooltip>> {
+        self.tooltip_node.clone()
+    }
+
+    /// Rebuilds the node the shown tooltip is drawn in when its text or
+    /// the window's `tooltip_style` changed, or removes it when hidden.
+    fn update_tooltip(&mut self) {
+        let text = self.tooltip().map(|(text, _)| text.to_owned());
+        let element = Tooltip::new(self.window.element().tooltip_style());
+        let unchanged = match (&self.tooltip_node, &text) {
+            (Some(node), Some(text)) => {
+                *node.element() == element && node.children() == std::slice::from_ref(text)
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return;
         }
+        self.states.remove(&TOOLTIP_ID);
+        self.tooltip_node = text.map(|text| Node::new(TOOLTIP_ID, element, vec![text]));
+        if let Some(node) = self.tooltip_node.clone() {
+            self.walk_children((&node).into(), Some(self.window.id()));
+        }
+    }
+
+    /// Moves the pointer over the node with `hovered`, at `position`.
+    /// Returns whether anything changed that needs rendering.
+    pub fn mouse_move(&mut self, hovered: Option<u64>, position: LogicalPoint) -> bool {
+        self.mouse_move_at(hovered, position, Instant::now())
+    }
+
+    /// Moves the pointer like `mouse_move`, with the hover delay starting
+    /// at `now`.
+    pub fn mouse_move_at(
+        &mut self,
+        hovered: Option<u64>,
+        position: LogicalPoint,
+        now: Instant,
+    ) -> bool {
+        // The tooltip belongs to the node it's shown for.
+        let hovered = match hovered {
+            Some(TOOLTIP_ID) => self.hover_intent.map(|intent| intent.id),
+            hovered => hovered,
+        };
+        let hid_tooltip = self.track_hover(hovered, position, now);
+        if self.captured_node.is_some() {
+            return hid_tooltip;
+        }
+        let hovered = hovered.map(|id| self.interactive_ancestor(id).unwrap_or(id));
 
         if hovered != self.hovered_node {
             if let Some(hovered) = self.hovered_node {
-                if let Some(state) = self.states.get_mut(&hovered) {
-                    state.states = state
-                        .node
-                        .process(state.states, &InputEvent::Hovered { state: State::End });
-                }
+                self.dispatch(hovered, InputEvent::Hovered { state: State::End });
             }
 
             self.hovered_node = hovered;
 
             if let Some(hovered) = self.hovered_node {
-                if let Some(state) = self.states.get_mut(&hovered) {
-                    state.states = state.node.process(
-                        state.states,
-                        &InputEvent::Hovered {
-                            state: State::Begin,
-                        },
-                    );
-                }
+                self.dispatch(
+                    hovered,
+                    InputEvent::Hovered {
+                        state: State::Begin,
+                    },
+                );
             }
 
             true
         } else {
-            false
+            hid_tooltip
         }
     }
 
     pub fn mouse_button1(&mut self, pressed: bool) -> bool {
-        if let Some(node) = self.pressed_node {
-            if let Some(state) = self.states.get_mut(&node) {
+        let event = InputEvent::MouseLeft {
+            state: if pressed { State::Begin } else { State::End },
+        };
+        // Pressing hides the tooltip, until the pointer rests again.
+        if pressed {
+            self.hover_intent = None;
+        }
+
+        if let Some(node) = self.captured_node {
+            if self.states.contains_key(&node) {
                 if !pressed {
-                    self.pressed_node = None;
+                    self.captured_node = None;
+                    self.choose(node);
                 }
-                state.states = state.node.process(
-                    state.states,
-                    &InputEvent::MouseLeft {
-                        state: if pressed { State::Begin } else { State::End },
-                    },
-                );
-                return true;
+                return self.dispatch(node, event);
+            }
+        }
+
+        // Clicking outside of the open popup only closes it.
+        if let Some(popup) = self.popup_node {
+            let inside = self
+                .hovered_node
+                .map_or(false, |hovered| self.is_inside(hovered, popup));
+            if pressed && !inside {
+                return self.dispatch(popup, InputEvent::Dismissed);
             }
         }
 
         if let Some(hovered) = self.hovered_node {
-            if let Some(state) = self.states.get_mut(&hovered) {
+            if self.states.contains_key(&hovered) {
                 if pressed {
-                    self.pressed_node = Some(hovered);
+                    self.captured_node = Some(hovered);
+                    if self.states[&hovered].node.focusable() {
+                        self.focus(Some(hovered));
+                    }
+                    if let Some(editor) = self.editors.get_mut(&hovered) {
+                        editor.pressed = true;
+                    }
                 }
-                state.states = state.node.process(
-                    state.states,
-                    &InputEvent::MouseLeft {
-                        state: if pressed { State::Begin } else { State::End },
-                    },
-                );
-                return true;
+                return self.dispatch(hovered, event);
             }
         }
 
         false
     }
 
-    pub fn close_requested(&mut self) -> bool {
-        if let Some(state) = self.states.get_mut(&self.window.id()) {
-            state.states = state
-                .node
-                .process(state.states, &InputEvent::CloseRequested);
+    /// The node that has the pointer captured.
+    pub fn captured(&self) -> Option<u64> {
+        self.captured_node.filter(|id| self.states.contains_key(id))
+    }
+
+    /// Tells the node that has the pointer captured where the pointer is.
+    /// Editable nodes have the caret put there instead, selecting from
+    /// where they were pressed. `measure` gives the width of text at a
+    /// size.
+    pub fn pointer_moved(
+        &mut self,
+        position: PointerPosition,
+        measure: &dyn Fn(&str, f32) -> f32,
+    ) -> bool {
+        let id = match self.captured() {
+            Some(id) => id,
+            None => return false,
+        };
+        let values = self.computed_values(id);
+        let padding = values
+            .display
+            .block()
+            .map_or(0.0, |block| block.padding.left);
+        let x = position.x - values.border.left.width.get() - padding;
+        let padding = values
+            .display
+            .block()
+            .map_or(0.0, |block| block.padding.top);
+        let y = position.y - values.border.top.width.get() - padding;
+        let editable = self.edit(id, |editor, size| {
+            let offset = editor.offset_at(x, y, &|text| measure(text, size));
+            editor.move_to(offset, !editor.pressed);
+            editor.pressed = false;
+            false
+        });
+        editable || self.dispatch(id, InputEvent::PointerMoved { position })
+    }
+
+    /// Passes the key to the focused node, selecting as the caret moves
+    /// in editable ones if `shift`. Arrow keys move between the nodes of
+    /// a group instead when the focused node is in one, and between the
+    /// options of nodes with popups. Escape closes the open popup.
+    /// `measure` gives the width of text at a size.
+    pub fn key_pressed(
+        &mut self,
+        key: Key,
+        shift: bool,
+        measure: &dyn Fn(&str, f32) -> f32,
+    ) -> bool {
+        if let (Key::Escape, Some(popup)) = (key, self.popup_node) {
+            return self.dispatch(popup, InputEvent::Dismissed);
+        }
+        let focused = match self.focused_node {
+            Some(focused) => focused,
+            None => return false,
+        };
+        let edited = self.edit(focused, |editor, size| match key {
+            Key::Backspace => editor.delete(false),
+            Key::Delete => editor.delete(true),
+            Key::Undo => editor.undo(),
+            Key::Redo => editor.redo(),
+            Key::SelectAll => {
+                editor.select_all();
+                false
+            }
+            key => {
+                editor.move_caret(key, shift, &|text| measure(text, size));
+                false
+            }
+        });
+        if edited {
             return true;
         }
-        false
+        let reverse = matches!(key, Key::Up | Key::Left);
+        let node = &self.states[&focused].node;
+        if key.is_arrow() && node.group().is_some() {
+            return self.focus_next_in_group(reverse);
+        }
+        if key.is_arrow() && node.has_popup() {
+            return self.choose_next(focused, reverse);
+        }
+        self.dispatch(focused, InputEvent::KeyPressed { key, shift })
+    }
+
+    pub fn close_requested(&mut self) -> bool {
+        self.dispatch(self.window.id(), InputEvent::CloseRequested)
+    }
+
+    /// Tells the window node that the OS window changed, such as by being
+    /// resized or moved.
+    pub fn window_changed(&mut self, event: InputEvent) -> bool {
+        self.dispatch(self.window.id(), event)
+    }
+
+    /// Moves focus to the node with `id`, or clears it. Returns whether
+    /// focus changed, which it can't to outside of the modal node.
+    pub fn focus(&mut self, id: Option<u64>) -> bool {
+        if id == self.focused_node {
+            return false;
+        }
+        if let (Some(id), Some(modal)) = (id, self.modal()) {
+            if !self.is_inside(id, modal) {
+                return false;
+            }
+        }
+        if let Some(focused) = self.focused_node {
+            self.dispatch(focused, InputEvent::Focused { state: State::End });
+        }
+        self.focused_node = id.filter(|id| self.states.contains_key(id));
+        if let Some(focused) = self.focused_node {
+            self.dispatch(
+                focused,
+                InputEvent::Focused {
+                    state: State::Begin,
+                },
+            );
+        }
+        true
+    }
+
+    /// Moves focus to the next focusable node in tree order, or the
+    /// previous one if `reverse`, wrapping around at the ends. Of the
+    /// nodes in a group, only the checked one can be moved to, or the
+    /// first if none are checked. While a modal node is shown, only the
+    /// nodes inside it can be moved to.
+    pub fn focus_next(&mut self, reverse: bool) -> bool {
+        let mut focusable = vec![];
+        collect_focusable((&self.focus_root()).into(), &mut focusable);
+        if reverse {
+            focusable.reverse();
+        }
+        let reachable = |node: &Focusable| match node.group {
+            Some(ref group) => {
+                let mut members = focusable
+                    .iter()
+                    .filter(|other| other.group.as_ref() == Some(group));
+                let first = if reverse {
+                    members.clone().last()
+                } else {
+                    members.clone().next()
+                };
+                node.checked
+                    || (!members.any(|other| other.checked)
+                        && first.map(|first| first.id) == Some(node.id))
+            }
+            None => true,
+        };
+        let start = self
+            .focused_node
+            .and_then(|focused| focusable.iter().position(|node| node.id == focused))
+            .map_or(0, |index| index + 1);
+        let (before, after) = focusable.split_at(start);
+        let next = after.iter().chain(before).find(|node| reachable(node));
+        self.focus(next.map(|node| node.id))
+    }
+
+    /// Moves focus to the next node in the focused node's group, or the
+    /// previous one if `reverse`, and activates it, which selects it for
+    /// radios.
+    pub fn focus_next_in_group(&mut self, reverse: bool) -> bool {
+        let mut focusable = vec![];
+        collect_focusable((&self.focus_root()).into(), &mut focusable);
+        let focused = match self
+            .focused_node
+            .and_then(|focused| focusable.iter().find(|node| node.id == focused))
+        {
+            Some(focused) => focused,
+            None => return false,
+        };
+        let mut members = focusable
+            .iter()
+            .filter(|node| node.group.is_some() && node.group == focused.group)
+            .map(|node| node.id)
+            .collect::<Vec<_>>();
+        if reverse {
+            members.reverse();
+        }
+        let index = match members.iter().position(|&id| id == focused.id) {
+            Some(index) => index,
+            None => return false,
+        };
+        let next = members[(index + 1) % members.len()];
+        if !self.focus(Some(next)) {
+            return false;
+        }
+        self.activate(next);
+        true
+    }
+
+    /// The node focus moves within, which is the modal node while one is
+    /// shown.
+    fn focus_root(&self) -> AnyNode {
+        match self.modal() {
+            Some(modal) => self.states[&modal].node.clone(),
+            None => self.window.clone().into(),
+        }
+    }
+
+    /// Does what clicking the node would, such as pressing a button or
+    /// choosing an option.
+    pub fn activate(&mut self, id: u64) -> bool {
+        self.choose(id) || self.dispatch(id, InputEvent::Activated)
+    }
+
+    pub fn perform_action(&mut self, id: u64, action: Action) -> bool {
+        match action {
+            Action::Focus => self.focus(Some(id)),
+            Action::Activate => self.activate(id),
+            Action::Increment => self.dispatch(
+                id,
+                InputEvent::KeyPressed {
+                    key: Key::Right,
+                    shift: false,
+                },
+            ),
+            Action::Decrement => self.dispatch(
+                id,
+                InputEvent::KeyPressed {
+                    key: Key::Left,
+                    shift: false,
+                },
+            ),
+        }
+    }
+}
+
+struct Focusable {
+    id: u64,
+    group: Option<String>,
+    checked: bool,
+}
+
+fn collect_focusable(node: NodeRef, focusable: &mut Vec<Focusable>) {
+    if node.focusable() {
+        focusable.push(Focusable {
+            id: node.id(),
+            group: node.group().map(ToOwned::to_owned),
+            checked: node.states().contains(ElementState::Checked),
+        });
+    }
+    for child in node.children() {
+        if let DynamicNode::Node(child) = child {
+            collect_focusable(child, focusable);
+        }
     }
 }
 
@@ -151,25 +782,41 @@ pub struct Document {
 
 impl Document {
     pub fn new(window: Node<Window>, content_size: LogicalSize) -> Document {
-        let mut doc = Document {
-            state: DocumentState {
-                window,
-                content_size,
-                states: HashMap::new(),
-                hovered_node: None,
-                pressed_node: None,
-            },
+        Document {
+            state: DocumentState::new(window, content_size),
             style_runtime: Runtime::new(),
             layout_engine: LayoutEngine::new(),
-        };
-        doc.state.walk_children((&doc.state.window.clone()).into());
-        doc
+        }
     }
 
     pub fn computed_values(&self, id: u64) -> &ComputedValues {
         self.state.computed_values(id)
     }
 
+    pub fn node_style(&self, id: u64) -> Option<NodeStyle> {
+        self.state.node_style(id)
+    }
+
+    /// Builds the accessibility tree from a layout tree returned by
+    /// `get_layout`, leaving out nodes that weren't laid out.
+    pub fn accessibility_tree(&self, layout: &LayoutTreeNode) -> AccessibilityTree {
+        let mut bounds = HashMap::new();
+        layout.collect_bounds(LogicalPoint::zero(), &mut bounds);
+        AccessibilityTree {
+            root: accessibility::build_node((&self.state.window).into(), Some(&bounds)),
+            focus: self.state.focused_node,
+        }
+    }
+
+    /// Copies a layout tree returned by `get_layout` for devtools.
+    pub fn layout_snapshot(&self, layout: &LayoutTreeNode) -> LayoutSnapshot {
+        layout.snapshot(&self.state, LogicalPoint::zero())
+    }
+
+    pub fn window(&self) -> &Node<Window> {
+        &self.state.window
+    }
+
     pub fn set_root(&mut self, window: Node<Window>) {
         self.state.set_root(window);
     }
@@ -178,29 +825,576 @@ impl Document {
         self.state.set_size(size);
     }
 
-    pub fn get_layout(&mut self) -> EqualRc<LayoutTreeNode> {
+    pub fn set_forced_states(&mut self, states: HashMap<u64, ElementStates>) {
+        self.state.forced_states = states;
+    }
+
+    pub fn window_id(&self) -> u64 {
+        self.state.window.id()
+    }
+
+    pub fn is_inside(&self, id: u64, ancestor: u64) -> bool {
+        self.state.is_inside(id, ancestor)
+    }
+
+    /// Returns the nodes restyled since this was last called.
+    pub fn take_restyled(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.state.restyled)
+    }
+
+    /// Returns the events dispatched since this was last called, along
+    /// with the nodes they were dispatched to.
+    pub fn take_dispatched(&mut self) -> Vec<(u64, InputEvent)> {
+        std::mem::take(&mut self.state.dispatched)
+    }
+
+    /// Styles and lays out the document, recording how long each took in
+    /// `timings`.
+    pub fn get_layout(&mut self, timings: &mut FrameTimings) -> EqualRc<LayoutTreeNode> {
+        let span = Phase::Style.enter();
         let state = &mut self.state;
         let window = state.window.clone();
         let size = state.content_size;
         self.style_runtime.run_once(move || {
             illicit::Layer::new().offer(size).enter(move || {
-                state.update_style((&window).into(), None);
+                state.update_style((&window).into());
             })
         });
-        self.layout_engine.layout(&mut self.state)
+        timings.style = span.exit();
+        let span = Phase::Layout.enter();
+        let layout = self.layout_engine.layout(&mut self.state);
+        timings.layout = span.exit();
+        layout
+    }
+
+    /// The id of the node whose popup is open.
+    pub fn popup(&self) -> Option<u64> {
+        self.state.popup_node
     }
 
-    pub fn mouse_move(&mut self, hovered: Option<u64>) -> bool {
-        self.state.mouse_move(hovered)
+    /// Lays out the open popup, at least `min_width` wide, to be drawn
+    /// above the layout from `get_layout`.
+    pub fn get_popup_layout(&mut self, min_width: f32) -> Option<EqualRc<LayoutTreeNode>> {
+        self.layout_engine.layout_popup(&mut self.state, min_width)
+    }
+
+    /// Lays out a line of text that isn't part of the document.
+    pub fn layout_line(&self, text: &str, size: f32) -> Option<LayoutText> {
+        self.layout_engine.layout_line(text, size)
+    }
+
+    pub fn mouse_move(&mut self, hovered: Option<u64>, position: LogicalPoint) -> bool {
+        self.state.mouse_move(hovered, position)
+    }
+
+    pub fn hover_deadline(&self) -> Option<Instant> {
+        self.state.hover_deadline()
+    }
+
+    pub fn update_hover(&mut self, now: Instant) -> bool {
+        self.state.update_hover(now)
+    }
+
+    pub fn tooltip(&self) -> Option<(&str, LogicalPoint)> {
+        self.state.tooltip()
+    }
+
+    /// Lays out the shown tooltip, to be drawn above the layout from
+    /// `get_layout`.
+    pub fn get_tooltip_layout(&mut self) -> Option<EqualRc<LayoutTreeNode>> {
+        self.layout_engine.layout_tooltip(&mut self.state)
     }
 
     pub fn mouse_button1(&mut self, pressed: bool) -> bool {
         self.state.mouse_button1(pressed)
     }
 
+    pub fn captured(&self) -> Option<u64> {
+        self.state.captured()
+    }
+
+    pub fn pointer_moved(&mut self, position: PointerPosition) -> bool {
+        let engine = &self.layout_engine;
+        self.state
+            .pointer_moved(position, &|text, size| engine.measure(text, size))
+    }
+
+    pub fn key_pressed(&mut self, key: Key, shift: bool) -> bool {
+        let engine = &self.layout_engine;
+        self.state
+            .key_pressed(key, shift, &|text, size| engine.measure(text, size))
+    }
+
+    pub fn text_input(&mut self, text: &str) -> bool {
+        self.state.text_input(text)
+    }
+
     pub fn close_requested(&mut self) -> bool {
         self.state.close_requested()
     }
+
+    pub fn window_changed(&mut self, event: InputEvent) -> bool {
+        self.state.window_changed(event)
+    }
+
+    pub fn focused(&self) -> Option<u64> {
+        self.state.focused_node
+    }
+
+    pub fn focus(&mut self, id: Option<u64>) -> bool {
+        self.state.focus(id)
+    }
+
+    pub fn focus_next(&mut self, reverse: bool) -> bool {
+        self.state.focus_next(reverse)
+    }
+
+    pub fn activate(&mut self, id: u64) -> bool {
+        self.state.activate(id)
+    }
+
+    pub fn perform_action(&mut self, id: u64, action: Action) -> bool {
+        self.state.perform_action(id, action)
+    }
 }
 
+pub(crate) mod editing;
 mod styling;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::element::{Element, HasAttribute, HasEvent};
+    use crate::dom::elements::view::ViewChild;
+    use crate::dom::{
+        test_window, AttrChecked, AttrGroup, AttrModal, AttrStyle, AttrTitle, AttrTooltipStyle,
+        AttrValue, Button, ChangeEvent, Checkbox, Overlay, Radio, Select, SelectOption, View,
+    };
+    use crate::layout::RenderData;
+    use crate::style::{Attributes, Style, StyleData};
+    use crate::style_impl::{apply, attribute, keyword, pixels};
+    use crate::util::event_handler::EventHandler;
+    use crate::Color;
+    use euclid::{point2, size2};
+    use std::sync::{Arc, Mutex};
+
+    static HIDDEN: StyleData = StyleData {
+        attributes: Attributes {
+            variables: |_variables| {},
+            apply: |values| apply(values, attribute::display(), keyword::none()),
+            get_attributes: Vec::new,
+        },
+        sub_styles: &[],
+        name: "HIDDEN",
+        file: file!(),
+        line: line!(),
+    };
+
+    static LARGE_TEXT: StyleData = StyleData {
+        attributes: Attributes {
+            variables: |_variables| {},
+            apply: |values| apply(values, attribute::text_size(), pixels(20.0)),
+            get_attributes: Vec::new,
+        },
+        sub_styles: &[],
+        name: "LARGE_TEXT",
+        file: file!(),
+        line: line!(),
+    };
+
+    /// The ids of the nodes laid out as children of `layout`.
+    fn child_ids(layout: &LayoutTreeNode) -> Vec<u64> {
+        layout
+            .children
+            .iter()
+            .filter_map(|child| match child.layout.render {
+                RenderData::Node(ref node) => Some(node.id()),
+                RenderData::Text { .. } | RenderData::Fill { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Nodes are painted and hit tested as their layout is rendered, so
+    /// nodes that aren't laid out can't be seen or clicked. Nor are they
+    /// exposed to assistive technology.
+    #[test]
+    fn leaves_display_none_nodes_out_of_the_layout() {
+        let mut hidden = View::default();
+        HasAttribute::<AttrStyle>::set_attribute(&mut hidden, Style::new(&HIDDEN).into());
+        let window = Node::new(
+            0,
+            Window::default(),
+            vec![Node::new(
+                1,
+                View::default(),
+                vec![
+                    ViewChild::View(Node::new(2, hidden, vec![])),
+                    ViewChild::View(Node::new(3, View::default(), vec![])),
+                ],
+            )],
+        );
+
+        let mut document = Document::new(window, size2(800.0, 600.0));
+        let layout = document.get_layout(&mut FrameTimings::default());
+        assert_eq!(child_ids(&layout), vec![1]);
+        assert_eq!(child_ids(&layout.children[0].layout), vec![3]);
+
+        let tree = document.accessibility_tree(&layout);
+        let ids: Vec<u64> = tree.root.iter().map(|node| node.id).collect();
+        assert_eq!(ids, vec![0, 1, 3]);
+    }
+
+    fn document(window: Node<Window>) -> DocumentState {
+        DocumentState::new(window, size2(800.0, 600.0))
+    }
+
+    /// Every character is 10 pixels wide.
+    fn measure(text: &str, _size: f32) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    fn radio(id: u64, group: &str, checked: bool) -> Node<Radio> {
+        let mut radio = Radio::default();
+        HasAttribute::<AttrGroup>::set_attribute(&mut radio, group.to_owned());
+        HasAttribute::<AttrChecked>::set_attribute(&mut radio, checked);
+        Node::new(id, radio, vec![])
+    }
+
+    /// A select with the value `value` and an option for each of
+    /// `options`, with the ids after its own.
+    fn select(id: u64, value: &str, options: &[&str]) -> Node<Select> {
+        let mut select = Select::default();
+        HasAttribute::<AttrValue<String>>::set_attribute(&mut select, value.to_owned());
+        let options = (1..).zip(options).map(|(offset, value)| {
+            let mut option = SelectOption::default();
+            HasAttribute::<AttrValue<String>>::set_attribute(&mut option, (*value).to_owned());
+            Node::new(id + offset, option, vec![(*value).to_owned()])
+        });
+        Node::new(id, select, options.collect())
+    }
+
+    /// Records the values the node's `on_change` is called with.
+    fn changes<Elt, T>(node: &Node<Elt>) -> Arc<Mutex<Vec<T>>>
+    where
+        Elt: Element + HasEvent<ChangeEvent<T>>,
+        T: Clone + Send + 'static,
+    {
+        let changes = Arc::new(Mutex::new(vec![]));
+        let sink = changes.clone();
+        Elt::set_handler(
+            &mut node.handlers().lock().unwrap(),
+            EventHandler::with_func(move |event: &ChangeEvent<T>| {
+                sink.lock().unwrap().push(event.value.clone())
+            }),
+        );
+        changes
+    }
+
+    /// A window with a button titled "Save" and one without a title.
+    fn titled_buttons() -> Node<Window> {
+        let mut titled = Button::default();
+        HasAttribute::<AttrTitle>::set_attribute(&mut titled, "Save".to_owned());
+        test_window(vec![
+            Node::new(2, titled, vec![]).into(),
+            Node::new(3, Button::default(), vec![]).into(),
+        ])
+    }
+
+    fn click(state: &mut DocumentState, id: u64) {
+        state.mouse_move(Some(id), point2(0.0, 0.0));
+        state.mouse_button1(true);
+        state.mouse_button1(false);
+    }
+
+    fn activated(state: &DocumentState) -> Vec<u64> {
+        state
+            .dispatched
+            .iter()
+            .filter(|(_, event)| matches!(event, InputEvent::Activated))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    #[test]
+    fn tabs_only_to_the_checked_radio_of_a_group() {
+        let mut state = document(test_window(vec![
+            Node::new(2, Button::default(), vec![]).into(),
+            radio(3, "size", false).into(),
+            radio(4, "size", true).into(),
+            radio(5, "size", false).into(),
+            radio(6, "color", false).into(),
+            radio(7, "color", false).into(),
+        ]));
+
+        let mut order = vec![];
+        for _ in 0..4 {
+            state.focus_next(false);
+            order.push(state.focused_node.unwrap());
+        }
+        assert_eq!(order, vec![2, 4, 6, 2]);
+
+        // Going backwards still reaches the first radio of a group with
+        // none checked.
+        state.focus_next(true);
+        assert_eq!(state.focused_node, Some(6));
+        state.focus_next(true);
+        assert_eq!(state.focused_node, Some(4));
+    }
+
+    #[test]
+    fn arrows_move_within_the_group_and_select() {
+        let first = radio(3, "size", false);
+        let second = radio(4, "size", true);
+        let third = radio(5, "size", false);
+        let changes = changes(&third);
+        let mut state = document(test_window(vec![
+            Node::new(2, Button::default(), vec![]).into(),
+            first.into(),
+            second.into(),
+            third.into(),
+            radio(6, "color", false).into(),
+        ]));
+        state.focus(Some(4));
+
+        assert!(state.key_pressed(Key::Down, false, &measure));
+        assert_eq!(state.focused_node, Some(5));
+        assert!(state.key_pressed(Key::Right, false, &measure));
+        assert_eq!(state.focused_node, Some(3));
+        assert!(state.key_pressed(Key::Up, false, &measure));
+        assert_eq!(state.focused_node, Some(5));
+        assert_eq!(activated(&state), vec![5, 3, 5]);
+        assert_eq!(*changes.lock().unwrap(), vec![true, true]);
+    }
+
+    #[test]
+    fn changes_checkboxes_to_the_opposite_of_their_state() {
+        let unchecked = Node::new(2, Checkbox::default(), vec![]);
+        let mut checked = Checkbox::default();
+        HasAttribute::<AttrChecked>::set_attribute(&mut checked, true);
+        let checked = Node::new(3, checked, vec![]);
+        let checked_radio = radio(4, "size", true);
+        let turned_on = changes(&unchecked);
+        let turned_off = changes(&checked);
+        let reselected = changes(&checked_radio);
+        let mut state = document(test_window(vec![
+            unchecked.into(),
+            checked.into(),
+            checked_radio.into(),
+        ]));
+
+        assert!(state.activate(2));
+        assert!(state.activate(3));
+        assert!(state.activate(4));
+        assert_eq!(*turned_on.lock().unwrap(), vec![true]);
+        assert_eq!(*turned_off.lock().unwrap(), vec![false]);
+        assert!(reselected.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn opens_the_popup_on_click_and_closes_it_on_escape() {
+        let mut state = document(test_window(vec![select(2, "en", &["en", "fr"]).into()]));
+
+        click(&mut state, 2);
+        assert_eq!(state.popup_node, Some(2));
+        assert_eq!(state.popup().unwrap().id(), 2);
+
+        assert!(state.key_pressed(Key::Escape, false, &measure));
+        assert_eq!(state.popup_node, None);
+    }
+
+    #[test]
+    fn closes_the_popup_on_a_click_outside_it() {
+        let mut state = document(test_window(vec![
+            select(2, "en", &["en", "fr"]).into(),
+            Node::new(5, Button::default(), vec![]).into(),
+        ]));
+        click(&mut state, 2);
+
+        state.mouse_move(Some(5), point2(0.0, 0.0));
+        assert!(state.mouse_button1(true));
+        assert_eq!(state.popup_node, None);
+        // The click only closes the popup.
+        assert_eq!(state.captured(), None);
+        assert!(activated(&state).is_empty());
+    }
+
+    #[test]
+    fn chooses_the_clicked_option() {
+        let select = select(2, "en", &["en", "fr"]);
+        let changes = changes(&select);
+        let mut state = document(test_window(vec![select.into()]));
+        click(&mut state, 2);
+
+        click(&mut state, 4);
+        assert_eq!(*changes.lock().unwrap(), vec!["fr".to_owned()]);
+        assert_eq!(state.popup_node, None);
+    }
+
+    #[test]
+    fn chooses_options_with_the_arrow_keys() {
+        let chosen = select(2, "fr", &["en", "fr", "de"]);
+        let unset = select(6, "", &["en", "fr"]);
+        let chosen_changes = changes(&chosen);
+        let unset_changes = changes(&unset);
+        let mut state = document(test_window(vec![chosen.into(), unset.into()]));
+
+        state.focus(Some(2));
+        assert!(state.key_pressed(Key::Down, false, &measure));
+        assert!(state.key_pressed(Key::Left, false, &measure));
+        assert_eq!(
+            *chosen_changes.lock().unwrap(),
+            vec!["de".to_owned(), "en".to_owned()]
+        );
+
+        // Without a value, the first option is the selected one.
+        state.focus(Some(6));
+        assert!(state.key_pressed(Key::Right, false, &measure));
+        assert_eq!(*unset_changes.lock().unwrap(), vec!["fr".to_owned()]);
+    }
+
+    #[test]
+    fn stops_choosing_at_the_last_option() {
+        let select = select(2, "fr", &["en", "fr"]);
+        let changes = changes(&select);
+        let mut state = document(test_window(vec![select.into()]));
+
+        state.focus(Some(2));
+        assert!(!state.key_pressed(Key::Down, false, &measure));
+        assert!(changes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_focus_inside_a_modal_overlay() {
+        let page = || -> Vec<ViewChild> {
+            vec![
+                Node::new(2, Button::default(), vec![]).into(),
+                radio(3, "size", false).into(),
+            ]
+        };
+        let mut state = document(test_window(page()));
+        state.focus(Some(2));
+
+        let mut dialog = Overlay::default();
+        HasAttribute::<AttrModal>::set_attribute(&mut dialog, true);
+        let dialog = Node::new(
+            4,
+            dialog,
+            vec![
+                Node::new(5, Button::default(), vec![]).into(),
+                radio(6, "size", true).into(),
+            ],
+        );
+        let mut with_dialog = page();
+        with_dialog.push(dialog.into());
+        state.set_root(test_window(with_dialog));
+        assert_eq!(state.focused_node, Some(5));
+
+        state.focus_next(false);
+        assert_eq!(state.focused_node, Some(6));
+        state.focus_next(false);
+        assert_eq!(state.focused_node, Some(5));
+        assert!(!state.focus(Some(2)));
+
+        // The radio behind the backdrop can't be moved to or selected.
+        state.focus(Some(6));
+        assert!(!state.key_pressed(Key::Down, false, &measure));
+        assert_eq!(state.focused_node, Some(6));
+        assert!(activated(&state).is_empty());
+
+        state.set_root(test_window(page()));
+        assert_eq!(state.focused_node, Some(2));
+    }
+
+    #[test]
+    fn shows_the_tooltip_after_the_hover_delay() {
+        let mut state = document(titled_buttons());
+        let now = Instant::now();
+        state.mouse_move_at(Some(2), point2(5.0, 5.0), now);
+        assert_eq!(state.hover_deadline(), Some(now + HOVER_DELAY));
+
+        assert!(!state.update_hover(now + HOVER_DELAY - Duration::from_millis(1)));
+        assert_eq!(state.tooltip(), None);
+
+        assert!(state.update_hover(now + HOVER_DELAY));
+        assert_eq!(state.tooltip(), Some(("Save", point2(5.0, 5.0))));
+        assert_eq!(state.hover_deadline(), None);
+        assert!(state
+            .dispatched
+            .iter()
+            .any(|(id, event)| *id == 2 && matches!(event, InputEvent::HoverIntent)));
+
+        // It stays where it showed while the pointer moves over the node.
+        assert!(!state.mouse_move_at(Some(2), point2(8.0, 5.0), now + HOVER_DELAY));
+        assert_eq!(state.tooltip(), Some(("Save", point2(5.0, 5.0))));
+    }
+
+    #[test]
+    fn hides_the_tooltip_when_moving_off_or_pressing() {
+        let mut state = document(titled_buttons());
+        let now = Instant::now();
+        state.mouse_move_at(Some(2), point2(5.0, 5.0), now);
+        state.update_hover(now + HOVER_DELAY);
+
+        assert!(state.mouse_move_at(Some(3), point2(50.0, 5.0), now + HOVER_DELAY));
+        assert_eq!(state.tooltip(), None);
+        assert_eq!(state.hover_deadline(), None);
+
+        state.mouse_move_at(Some(2), point2(5.0, 5.0), now);
+        state.update_hover(now + HOVER_DELAY);
+        state.mouse_button1(true);
+        assert_eq!(state.tooltip(), None);
+        assert_eq!(state.hover_deadline(), None);
+    }
+
+    #[test]
+    fn restarts_the_hover_delay_when_moving_over_the_node() {
+        let mut state = document(titled_buttons());
+        let now = Instant::now();
+        let later = now + Duration::from_millis(200);
+        state.mouse_move_at(Some(2), point2(5.0, 5.0), now);
+        state.mouse_move_at(Some(2), point2(8.0, 5.0), later);
+        assert_eq!(state.hover_deadline(), Some(later + HOVER_DELAY));
+
+        assert!(!state.update_hover(now + HOVER_DELAY));
+        assert_eq!(state.tooltip(), None);
+
+        assert!(state.update_hover(later + HOVER_DELAY));
+        assert_eq!(state.tooltip(), Some(("Save", point2(8.0, 5.0))));
+    }
+
+    /// The tooltip is drawn in a node of its own, styled by the window's
+    /// `tooltip_style` on top of a default style.
+    #[test]
+    fn styles_the_tooltip_with_the_window_tooltip_style() {
+        let mut element = Window::default();
+        HasAttribute::<AttrTooltipStyle>::set_attribute(
+            &mut element,
+            Style::new(&LARGE_TEXT).into(),
+        );
+        let window = Node::new(0, element, titled_buttons().children().to_vec());
+        let mut document = Document::new(window, size2(800.0, 600.0));
+        let now = Instant::now();
+        document.state.mouse_move_at(Some(2), point2(5.0, 5.0), now);
+        document.update_hover(now + HOVER_DELAY);
+
+        document.get_layout(&mut FrameTimings::default());
+        let tooltip = document.get_tooltip_layout().unwrap();
+        assert!(matches!(tooltip.render, RenderData::Node(ref node) if node.id() == TOOLTIP_ID));
+        let values = document.computed_values(TOOLTIP_ID);
+        assert_eq!(values.text_size.get(), 20.0);
+        assert_eq!(values.text_color, Color::white());
+
+        // Moving over the tooltip itself keeps it shown.
+        assert!(!document.state.mouse_move_at(
+            Some(TOOLTIP_ID),
+            point2(30.0, 30.0),
+            now + HOVER_DELAY
+        ));
+        assert_eq!(document.tooltip(), Some(("Save", point2(5.0, 5.0))));
+
+        document.mouse_button1(true);
+        document.get_layout(&mut FrameTimings::default());
+        assert!(document.get_tooltip_layout().is_none());
+        assert!(!document.state.states.contains_key(&TOOLTIP_ID));
+    }
+}
@@ -1,12 +1,18 @@
 use super::DocumentState;
-use crate::dom::element::{DynamicNode, ElementState};
+use crate::dom::devtools::NodeStyle;
+use crate::dom::element::{DynamicNode, ElementState, ElementStates};
 use crate::dom::node::NodeRef;
-use crate::style::{ComputedValues, NodeSelect, Style};
+use crate::layout::LogicalSize;
+use crate::style::{ComputedValues, InlineStyle, NodeSelect, Style, Variables};
+use crate::style_impl::attribute;
 use std::any::TypeId;
 
 struct NodeProxy<'a> {
     node: NodeRef<'a>,
-    state: &'a DocumentState,
+    states: ElementStates,
+    index: usize,
+    count: usize,
+    parent: Option<&'a NodeProxy<'a>>,
 }
 
 impl<'a> NodeSelect for NodeProxy<'a> {
@@ -15,41 +21,282 @@ impl<'a> NodeSelect for NodeProxy<'a> {
     }
 
     fn has_state(&self, state: ElementState) -> bool {
-        self.state.node_states(self.node.id()).contains(state)
+        self.states.contains(state)
+    }
+
+    fn has_class(&self, name: &str) -> bool {
+        self.node.classes().iter().any(|class| class == name)
+    }
+
+    fn element_name(&self) -> &'static str {
+        self.node.name()
+    }
+
+    fn parent(&self) -> Option<&dyn NodeSelect> {
+        self.parent.map(|parent| parent as &dyn NodeSelect)
+    }
+
+    fn sibling_index(&self) -> usize {
+        self.index
+    }
+
+    fn sibling_count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Everything about a node, other than its parent's computed values,
+/// that can affect the result of styling it. Nodes whose inputs haven't
+/// changed since the last pass keep their computed values.
+pub(super) struct StyleInputs {
+    styles: Vec<Style>,
+    /// Stylesheets can be reloaded without the styles' identity changing.
+    generations: Vec<usize>,
+    classes: Vec<String>,
+    inline_style: Option<InlineStyle>,
+    variables: Option<Variables>,
+    states: ElementStates,
+    index: usize,
+    count: usize,
+    viewport: LogicalSize,
+}
+
+impl StyleInputs {
+    fn new(proxy: &NodeProxy, viewport: LogicalSize) -> StyleInputs {
+        StyleInputs {
+            styles: proxy.node.styles().to_vec(),
+            generations: proxy
+                .node
+                .styles()
+                .iter()
+                .map(|style| style.generation())
+                .collect(),
+            classes: proxy.node.classes().to_vec(),
+            inline_style: proxy.node.inline_style().cloned(),
+            variables: proxy.node.variables().cloned(),
+            states: proxy.states,
+            index: proxy.index,
+            count: proxy.count,
+            viewport,
+        }
+    }
+
+    fn matches(&self, proxy: &NodeProxy, viewport: LogicalSize) -> bool {
+        self.styles[..] == *proxy.node.styles()
+            && self.generations.iter().copied().eq(proxy
+                .node
+                .styles()
+                .iter()
+                .map(|style| style.generation()))
+            && self.classes[..] == *proxy.node.classes()
+            && self.inline_style.as_ref() == proxy.node.inline_style()
+            && self.variables.as_ref() == proxy.node.variables()
+            && self.states == proxy.states
+            && self.index == proxy.index
+            && self.count == proxy.count
+            && self.viewport == viewport
     }
 }
 
 impl DocumentState {
-    pub fn update_style(&mut self, node: NodeRef, parent: Option<&ComputedValues>) {
+    pub fn update_style(&mut self, node: NodeRef) {
+        let proxy = NodeProxy {
+            node,
+            states: self.node_states(node.id()),
+            index: 0,
+            count: 1,
+            parent: None,
+        };
+        let start = self.restyled.len();
+        self.update_node_style(&proxy, None, &Variables::default(), false);
+
+        // The tooltip is styled as if it were the window's only child,
+        // and restyled whenever the window is.
+        self.update_tooltip();
+        if let Some(tooltip) = self.tooltip_node() {
+            let force = self.restyled[start..].contains(&node.id());
+            let values = *self.computed_values(node.id());
+            let variables = self.states[&node.id()].variables.clone();
+            let tooltip_proxy = NodeProxy {
+                node: (&tooltip).into(),
+                states: self.node_states(tooltip.id()),
+                index: 0,
+                count: 1,
+                parent: Some(&proxy),
+            };
+            self.update_node_style(&tooltip_proxy, Some(&values), &variables, force);
+        }
+    }
+
+    /// Returns the rules that matched the node with `id` when it was last
+    /// styled, along with its computed values.
+    pub fn node_style(&self, id: u64) -> Option<NodeStyle> {
+        let root = NodeProxy {
+            node: (&self.window).into(),
+            states: self.node_states(self.window.id()),
+            index: 0,
+            count: 1,
+            parent: None,
+        };
+        self.find_proxy(&root, id, &mut |proxy| NodeStyle {
+            rules: proxy
+                .node
+                .styles()
+                .iter()
+                .flat_map(|style| style.matched_rules(proxy))
+                .collect(),
+            computed: *self.computed_values(id),
+        })
+    }
+
+    /// Calls `func` with the proxy for the node with `id`, which has to be
+    /// found from the root so that selectors can look at its parents.
+    fn find_proxy<R>(
+        &self,
+        proxy: &NodeProxy,
+        id: u64,
+        func: &mut dyn FnMut(&NodeProxy) -> R,
+    ) -> Option<R> {
+        if proxy.node.id() == id {
+            return Some(func(proxy));
+        }
+        let count = proxy
+            .node
+            .children()
+            .filter(|child| child.node().is_some())
+            .count();
+        let children = proxy.node.children().filter_map(|child| child.node());
+        for (index, child) in children.enumerate() {
+            let child_proxy = NodeProxy {
+                node: child,
+                states: self.node_states(child.id()),
+                index,
+                count,
+                parent: Some(proxy),
+            };
+            if let Some(result) = self.find_proxy(&child_proxy, id, func) {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    /// Works out the variables in scope for the node. Variables offered
+    /// through the environment are only merged in where they differ from
+    /// the parent's, so that a node's own declarations aren't overridden
+    /// by the same environment again further down.
+    fn compute_variables(proxy: &NodeProxy, parent: &Variables) -> Variables {
+        let mut variables = parent.clone();
+
+        if let Some(offered) = proxy.node.variables() {
+            let parent_offered = proxy.parent.and_then(|parent| parent.node.variables());
+            if parent_offered.map_or(true, |parent_offered| !offered.same(parent_offered)) {
+                variables.extend(offered);
+            }
+        }
+
+        // Var references within declarations see the inherited values.
+        let inherited = variables.clone();
+        illicit::Layer::new().offer(inherited).enter(|| {
+            for style in proxy.node.styles() {
+                style.declare_variables(proxy, &mut variables);
+            }
+        });
+
+        if let Some(inline_style) = proxy.node.inline_style() {
+            variables.extend(inline_style.variables());
+        }
+
+        variables
+    }
+
+    fn compute_style(
+        proxy: &NodeProxy,
+        parent: Option<&ComputedValues>,
+        variables: &Variables,
+    ) -> ComputedValues {
+        let node = proxy.node;
         let mut computed = node.create_computed_values();
 
         let default_values = ComputedValues::default();
         let parent = parent.unwrap_or(&default_values);
 
-        // Default-inherited attributes
-        computed.text_color = parent.text_color;
-        computed.text_size = parent.text_size;
+        attribute::inherit_defaults(&mut computed, parent);
 
-        illicit::Layer::new().offer(*parent).enter(|| {
-            let style = node.style();
-            if let Some(Style(style)) = style {
-                (style.attributes.apply)(&mut computed);
+        illicit::Layer::new()
+            .offer(*parent)
+            .offer(variables.clone())
+            .enter(|| {
+                for style in node.styles() {
+                    style.apply(proxy, &mut computed);
+                }
 
-                let node = NodeProxy { node, state: self };
-                for sub_style in style.sub_styles {
-                    if (sub_style.selector)(&node) {
-                        (sub_style.attributes.apply)(&mut computed);
-                    }
+                if let Some(inline_style) = node.inline_style() {
+                    inline_style.apply(&mut computed);
                 }
-            }
-        });
+            });
 
-        self.states.get_mut(&node.id()).unwrap().computed_values = Some(computed);
+        computed
+    }
+
+    /// Restyles the node if its inputs changed or if `force` is set, then
+    /// recurses. Descendants are forced whenever this node's inputs,
+    /// computed values or variables changed, since they can inherit from
+    /// it or select on it.
+    fn update_node_style(
+        &mut self,
+        proxy: &NodeProxy,
+        parent: Option<&ComputedValues>,
+        parent_variables: &Variables,
+        force: bool,
+    ) {
+        let node = proxy.node;
+        let viewport = self.content_size;
+        let state = self.states.get_mut(&node.id()).unwrap();
 
-        for child in node.children() {
-            if let DynamicNode::Node(node) = child {
-                self.update_style(node, Some(&computed));
+        let inputs_changed = match state.style_inputs {
+            Some(ref inputs) => !inputs.matches(proxy, viewport),
+            None => true,
+        };
+        let previous = state.computed_values;
+        let restyled = force || inputs_changed || previous.is_none();
+        let (computed, changed) = match previous {
+            Some(computed) if !restyled => (computed, false),
+            _ => {
+                let variables = DocumentState::compute_variables(proxy, parent_variables);
+                let computed = DocumentState::compute_style(proxy, parent, &variables);
+                if inputs_changed {
+                    state.style_inputs = Some(StyleInputs::new(proxy, viewport));
+                }
+                let variables_changed = variables != state.variables;
+                state.variables = variables;
+                state.computed_values = Some(computed);
+                (computed, variables_changed || previous != Some(computed))
             }
+        };
+        let force_children = force || inputs_changed || changed;
+        let variables = state.variables.clone();
+        if restyled {
+            self.restyled.push(node.id());
+        }
+
+        let count = node
+            .children()
+            .filter(|child| child.node().is_some())
+            .count();
+        let children = node.children().filter_map(|child| match child {
+            DynamicNode::Node(node) => Some(node),
+            DynamicNode::Text(_) => None,
+        });
+        for (index, child) in children.enumerate() {
+            let child_proxy = NodeProxy {
+                node: child,
+                states: self.node_states(child.id()),
+                index,
+                count,
+                parent: Some(proxy),
+            };
+            self.update_node_style(&child_proxy, Some(&computed), &variables, force_children);
         }
     }
 }
@@ -0,0 +1,418 @@
+//! Describes the DOM to assistive technology such as screen readers.
+//! Each element has a role, and can have a name and description, which
+//! together with the layout make up an `AccessibilityTree`.
+
+use crate::dom::element::{DynamicNode, ElementState, RangeValue};
+use crate::dom::node::NodeRef;
+use crate::dom::{Node, Window};
+use std::collections::HashMap;
+
+/// What an element is to assistive technology.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Role {
+    Window,
+    /// A container for other elements.
+    Group,
+    Button,
+    Checkbox,
+    /// One of a group of options, of which one can be checked.
+    RadioButton,
+    Switch,
+    /// Picks a number from a range.
+    Slider,
+    /// Shows the chosen option and opens a list of the others.
+    ComboBox,
+    /// One of the options of a combo box.
+    ListOption,
+    /// Multiple lines of editable text.
+    TextArea,
+    /// Content shown above the rest of the window, which is blocked
+    /// while it's shown.
+    Dialog,
+    /// A run of text.
+    Text,
+}
+
+impl Role {
+    /// Whether elements with this role are named after the text inside
+    /// them when they don't have a `label`.
+    pub fn name_from_contents(self) -> bool {
+        match self {
+            Role::Button | Role::ListOption | Role::Text => true,
+            Role::Window
+            | Role::Group
+            | Role::Checkbox
+            | Role::RadioButton
+            | Role::Switch
+            | Role::Slider
+            | Role::ComboBox
+            | Role::TextArea
+            | Role::Dialog => false,
+        }
+    }
+
+    /// Whether elements with this role are either checked or unchecked.
+    pub fn checkable(self) -> bool {
+        match self {
+            Role::Checkbox | Role::RadioButton | Role::Switch => true,
+            Role::Window
+            | Role::Group
+            | Role::Button
+            | Role::Slider
+            | Role::ComboBox
+            | Role::ListOption
+            | Role::TextArea
+            | Role::Dialog
+            | Role::Text => false,
+        }
+    }
+
+    /// Whether the children of elements with this role are only how they
+    /// look, and are left out of the tree.
+    pub fn presentational_children(self) -> bool {
+        match self {
+            Role::Slider => true,
+            Role::Window
+            | Role::Group
+            | Role::Button
+            | Role::Checkbox
+            | Role::RadioButton
+            | Role::Switch
+            | Role::ComboBox
+            | Role::ListOption
+            | Role::TextArea
+            | Role::Dialog
+            | Role::Text => false,
+        }
+    }
+}
+
+/// Something assistive technology can ask an element to do.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    Focus,
+    /// Does what clicking the element would, such as pressing a button.
+    Activate,
+    /// Moves the value of a range up by a step.
+    Increment,
+    /// Moves the value of a range down by a step.
+    Decrement,
+}
+
+/// Where a node was laid out, in logical pixels relative to the window.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Bounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Bounds {
+    /// The smallest bounds containing both, for nodes laid out as several
+    /// boxes, such as text spanning lines.
+    pub fn union(self, other: Bounds) -> Bounds {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Bounds {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AccessibilityNode {
+    /// The id of the DOM node.
+    pub id: u64,
+    pub role: Role,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// `None` when the tree was built without a layout.
+    pub bounds: Option<Bounds>,
+    pub focusable: bool,
+    /// `None` unless the role is checkable.
+    pub checked: Option<bool>,
+    /// The value of range elements like sliders.
+    pub range: Option<RangeValue>,
+    /// The text of editable elements like textareas.
+    pub value: Option<String>,
+    pub actions: Vec<Action>,
+    pub children: Vec<AccessibilityNode>,
+}
+
+impl AccessibilityNode {
+    /// Iterates over this node and everything inside it, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
+
+/// The accessibility tree of a window.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AccessibilityTree {
+    pub root: AccessibilityNode,
+    /// The id of the focused node.
+    pub focus: Option<u64>,
+}
+
+impl AccessibilityTree {
+    /// Builds the tree from the window's DOM alone, without bounds or
+    /// focus. This lets tests check what assistive technology would see
+    /// without opening the window.
+    pub fn from_dom(window: &Node<Window>) -> AccessibilityTree {
+        AccessibilityTree {
+            root: build_node(window.into(), None),
+            focus: None,
+        }
+    }
+
+    pub fn find(&self, id: u64) -> Option<&AccessibilityNode> {
+        self.root.iter().find(|node| node.id == id)
+    }
+
+    /// Finds the first node with the role and name, the way a screen
+    /// reader user would look for it.
+    pub fn find_named(&self, role: Role, name: &str) -> Option<&AccessibilityNode> {
+        self.root
+            .iter()
+            .find(|node| node.role == role && node.name.as_deref() == Some(name))
+    }
+}
+
+/// Joins the text inside the node with single spaces.
+fn text_contents(node: NodeRef, text: &mut String) {
+    for child in node.children() {
+        match child {
+            DynamicNode::Text(contents) => {
+                for word in contents.split_whitespace() {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(word);
+                }
+            }
+            DynamicNode::Node(child) => text_contents(child, text),
+        }
+    }
+}
+
+/// Builds the node and its children. With `bounds`, nodes that weren't
+/// laid out are left out, since they aren't displayed.
+pub(crate) fn build_node(
+    node: NodeRef,
+    bounds: Option<&HashMap<u64, Bounds>>,
+) -> AccessibilityNode {
+    let role = node.accessibility_role();
+    let name = node.accessible_name().or_else(|| {
+        if role.name_from_contents() {
+            let mut text = String::new();
+            text_contents(node, &mut text);
+            Some(text).filter(|text| !text.is_empty())
+        } else {
+            None
+        }
+    });
+    let focusable = node.focusable();
+    let mut actions = vec![];
+    if focusable {
+        actions.push(Action::Focus);
+    }
+    if node.interactive() {
+        actions.push(Action::Activate);
+    }
+    let range = node.range();
+    if range.is_some() {
+        actions.extend_from_slice(&[Action::Increment, Action::Decrement]);
+    }
+    let children = if role.presentational_children() {
+        vec![]
+    } else {
+        node.children()
+            .filter_map(|child| child.node())
+            .filter(|child| bounds.map_or(true, |bounds| bounds.contains_key(&child.id())))
+            .map(|child| build_node(child, bounds))
+            .collect()
+    };
+
+    AccessibilityNode {
+        id: node.id(),
+        role,
+        name,
+        description: node
+            .accessible_description()
+            .or_else(|| node.tooltip().map(ToOwned::to_owned)),
+        bounds: bounds.and_then(|bounds| bounds.get(&node.id()).copied()),
+        focusable,
+        checked: Some(node.states().contains(ElementState::Checked)).filter(|_| role.checkable()),
+        range,
+        value: node.value().filter(|_| node.editable()).map(Into::into),
+        actions,
+        children,
+    }
+}
+
+/// Lists `label` and `description` for devtools, when they're set.
+pub(crate) fn describe_attributes(
+    label: &Option<String>,
+    description: &Option<String>,
+) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![];
+    if let Some(label) = label {
+        attributes.push(("label", format!("{:?}", label)));
+    }
+    if let Some(description) = description {
+        attributes.push(("description", format!("{:?}", description)));
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::element::HasAttribute;
+    use crate::dom::elements::button::ButtonChild;
+    use crate::dom::elements::span::SpanChild;
+    use crate::dom::elements::view::ViewChild;
+    use crate::dom::{
+        test_window, AttrChecked, AttrDescription, AttrLabel, AttrMax, AttrModal, AttrStep,
+        AttrTitle, AttrValue, Button, Checkbox, Overlay, Select, SelectOption, Slider, SliderPart,
+        Span,
+    };
+
+    #[test]
+    fn names_nodes_from_text_and_labels() {
+        let mut labelled = Button::default();
+        HasAttribute::<AttrLabel>::set_attribute(&mut labelled, "Close".to_owned());
+        let mut titled = Window::default();
+        HasAttribute::<AttrTitle>::set_attribute(&mut titled, "Demo".to_owned());
+        let titled = AccessibilityTree::from_dom(&Node::new(0, titled, vec![]));
+        assert_eq!(titled.root.role, Role::Window);
+        assert_eq!(titled.root.name.as_deref(), Some("Demo"));
+
+        let text = Node::new(
+            3,
+            Span::default(),
+            vec![SpanChild::Text(" Save \n now".to_owned())],
+        );
+        let window = test_window(vec![
+            ViewChild::Button(Node::new(
+                2,
+                Button::default(),
+                vec![ButtonChild::Span(text)],
+            )),
+            ViewChild::Button(Node::new(4, labelled, vec![])),
+        ]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        assert_eq!(tree.find(1).unwrap().name, None);
+
+        let save = tree.find_named(Role::Button, "Save now").unwrap();
+        assert_eq!(save.id, 2);
+        assert_eq!(save.actions, vec![Action::Focus, Action::Activate]);
+        assert_eq!(save.children[0].role, Role::Text);
+        assert_eq!(tree.find_named(Role::Button, "Close").unwrap().id, 4);
+        assert_eq!(save.checked, None);
+    }
+
+    #[test]
+    fn reports_checked_state() {
+        let mut checked = Checkbox::default();
+        HasAttribute::<AttrChecked>::set_attribute(&mut checked, true);
+        let window = test_window(vec![
+            ViewChild::Checkbox(Node::new(2, checked, vec![])),
+            ViewChild::Checkbox(Node::new(3, Checkbox::default(), vec![])),
+        ]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        assert_eq!(tree.find(1).unwrap().checked, None);
+        assert_eq!(tree.find(2).unwrap().role, Role::Checkbox);
+        assert_eq!(tree.find(2).unwrap().checked, Some(true));
+        assert_eq!(tree.find(3).unwrap().checked, Some(false));
+    }
+
+    #[test]
+    fn reports_slider_range() {
+        let mut slider = Slider::default();
+        HasAttribute::<AttrValue<f64>>::set_attribute(&mut slider, 30.0);
+        HasAttribute::<AttrMax>::set_attribute(&mut slider, 50.0);
+        HasAttribute::<AttrStep>::set_attribute(&mut slider, 10.0);
+        let window = test_window(vec![ViewChild::Slider(Node::new(
+            2,
+            slider,
+            vec![Node::new(3, SliderPart::default(), vec![])],
+        ))]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        let slider = tree.find(2).unwrap();
+        assert_eq!(slider.role, Role::Slider);
+        assert!(slider.children.is_empty());
+        assert!(slider.actions.contains(&Action::Increment));
+        let range = slider.range.unwrap();
+        assert_eq!((range.value, range.min, range.max), (30.0, 0.0, 50.0));
+        assert_eq!(range.at_fraction(0.45), 20.0);
+        assert_eq!(range.snap(57.0), 50.0);
+    }
+
+    #[test]
+    fn names_options_from_their_text() {
+        let mut select = Select::default();
+        HasAttribute::<AttrLabel>::set_attribute(&mut select, "Language".to_owned());
+        let option = |id, value: &str, text: &str| {
+            let mut option = SelectOption::default();
+            HasAttribute::<AttrValue<String>>::set_attribute(&mut option, value.to_owned());
+            Node::new(id, option, vec![text.to_owned()])
+        };
+        let select = Node::new(
+            2,
+            select,
+            vec![option(3, "en", "English"), option(4, "fr", "French")],
+        );
+        let window = test_window(vec![ViewChild::Select(select)]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        assert_eq!(tree.find(2).unwrap().role, Role::ComboBox);
+        assert_eq!(tree.find_named(Role::ComboBox, "Language").unwrap().id, 2);
+        assert_eq!(tree.find_named(Role::ListOption, "French").unwrap().id, 4);
+    }
+
+    #[test]
+    fn reports_modal_overlays_as_dialogs() {
+        let mut modal = Overlay::default();
+        HasAttribute::<AttrModal>::set_attribute(&mut modal, true);
+        HasAttribute::<AttrLabel>::set_attribute(&mut modal, "Confirm".to_owned());
+        let window = test_window(vec![
+            ViewChild::Overlay(Node::new(2, Overlay::default(), vec![])),
+            ViewChild::Overlay(Node::new(3, modal, vec![])),
+        ]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        assert_eq!(tree.find(2).unwrap().role, Role::Group);
+        assert_eq!(tree.find_named(Role::Dialog, "Confirm").unwrap().id, 3);
+    }
+
+    #[test]
+    fn describes_nodes_by_their_title() {
+        let mut titled = Button::default();
+        HasAttribute::<AttrTitle>::set_attribute(&mut titled, "Restore defaults".to_owned());
+        let mut described = titled.clone();
+        HasAttribute::<AttrDescription>::set_attribute(&mut described, "Resets".to_owned());
+        let window = test_window(vec![
+            ViewChild::Button(Node::new(2, titled, vec![])),
+            ViewChild::Button(Node::new(3, described, vec![])),
+        ]);
+
+        let tree = AccessibilityTree::from_dom(&window);
+        let titled = tree.find(2).unwrap();
+        assert_eq!(titled.description.as_deref(), Some("Restore defaults"));
+        assert_eq!(titled.name, None);
+        assert_eq!(tree.find(3).unwrap().description.as_deref(), Some("Resets"));
+    }
+}
@@ -1,4 +1,8 @@
-use crate::style::Style;
+use crate::style::{InlineStyle, StyleList};
+use crate::Color;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
 macro_rules! attribute {
     ($name:ident, $value:ty) => {
@@ -11,5 +15,147 @@ macro_rules! attribute {
     };
 }
 
-attribute!(AttrStyle, Option<Style>);
+attribute!(AttrStyle, StyleList);
+attribute!(AttrClass, ClassList);
+attribute!(AttrInlineStyle, InlineStyle);
 attribute!(AttrTitle, String);
+attribute!(AttrLabel, String);
+attribute!(AttrDescription, String);
+attribute!(AttrChecked, bool);
+attribute!(AttrGroup, String);
+attribute!(AttrMin, f64);
+attribute!(AttrMax, f64);
+attribute!(AttrStep, f64);
+attribute!(AttrTrackStyle, StyleList);
+attribute!(AttrFillStyle, StyleList);
+attribute!(AttrThumbStyle, StyleList);
+attribute!(AttrTooltipStyle, StyleList);
+attribute!(AttrModal, bool);
+attribute!(AttrSize, (f32, f32));
+attribute!(AttrMinSize, (f32, f32));
+attribute!(AttrMaxSize, (f32, f32));
+attribute!(AttrPosition, (f32, f32));
+attribute!(AttrResizable, bool);
+attribute!(AttrMaximized, bool);
+attribute!(AttrFullscreen, bool);
+attribute!(AttrAlwaysOnTop, bool);
+attribute!(AttrDecorations, bool);
+attribute!(AttrIcon, Icon);
+attribute!(AttrBackground, Color);
+
+/// The value of an input element, whose type depends on the element,
+/// like `f64` for sliders.
+pub struct AttrValue<Value>(PhantomData<Value>);
+
+impl<Value> Default for AttrValue<Value> {
+    fn default() -> Self {
+        AttrValue(PhantomData)
+    }
+}
+
+impl<Value> crate::dom::element::Attribute for AttrValue<Value> {
+    type Value = Value;
+}
+
+/// The value of the `class` attribute. Strings are split on whitespace,
+/// so `class="button danger"` gives the element two classes.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ClassList(Vec<String>);
+
+impl ClassList {
+    pub fn new() -> ClassList {
+        ClassList(vec![])
+    }
+
+    pub fn as_slice(&self) -> &[String] {
+        &self.0[..]
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|class| class == name)
+    }
+}
+
+impl From<&str> for ClassList {
+    fn from(classes: &str) -> ClassList {
+        ClassList(classes.split_whitespace().map(str::to_owned).collect())
+    }
+}
+
+impl From<String> for ClassList {
+    fn from(classes: String) -> ClassList {
+        classes.as_str().into()
+    }
+}
+
+impl From<Option<&str>> for ClassList {
+    fn from(classes: Option<&str>) -> ClassList {
+        classes.map(ClassList::from).unwrap_or_default()
+    }
+}
+
+impl From<Vec<&str>> for ClassList {
+    fn from(classes: Vec<&str>) -> ClassList {
+        classes.as_slice().into()
+    }
+}
+
+impl From<&[&str]> for ClassList {
+    fn from(classes: &[&str]) -> ClassList {
+        ClassList(
+            classes
+                .iter()
+                .flat_map(|classes| classes.split_whitespace())
+                .map(str::to_owned)
+                .collect(),
+        )
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for ClassList {
+    fn from(classes: [&str; N]) -> ClassList {
+        classes[..].into()
+    }
+}
+
+/// The value of a window's `icon` attribute, as 8-bit RGBA pixels in
+/// rows from the top. The pixels are shared, so icons are cheap to clone
+/// and compare on every render.
+#[derive(Clone, PartialEq)]
+pub struct Icon {
+    rgba: Arc<[u8]>,
+    width: u32,
+    height: u32,
+}
+
+impl Icon {
+    /// Returns `None` unless there are `width * height` pixels.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Option<Icon> {
+        if rgba.len() != width as usize * height as usize * 4 {
+            return None;
+        }
+        Some(Icon {
+            rgba: rgba.into(),
+            width,
+            height,
+        })
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl fmt::Debug for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Icon({}x{})", self.width, self.height)
+    }
+}
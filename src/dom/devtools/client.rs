@@ -0,0 +1,51 @@
+use super::protocol::{self, Command, ProtocolError, Update};
+use std::io::{BufReader, Write};
+use std::net::{Ipv4Addr, TcpStream};
+
+/// A connection to the devtools server of an app running on the same
+/// machine, which is started with `Runtime::serve_devtools`.
+#[derive(Debug)]
+pub struct DevToolsClient {
+    reader: BufReader<TcpStream>,
+}
+
+/// Sends commands over a `DevToolsClient`'s connection. It can be moved
+/// to another thread, so that updates can be received in the meantime.
+#[derive(Debug)]
+pub struct CommandSender {
+    stream: TcpStream,
+}
+
+impl DevToolsClient {
+    /// Connects to the server listening on `port`, with the token returned
+    /// by `Runtime::serve_devtools`.
+    pub fn connect(
+        port: u16,
+        token: &str,
+    ) -> Result<(DevToolsClient, CommandSender), ProtocolError> {
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+        stream.set_nodelay(true)?;
+        let mut sender = CommandSender {
+            stream: stream.try_clone()?,
+        };
+        let mut reader = BufReader::new(stream);
+        protocol::write_handshake(&mut sender.stream, token)?;
+        protocol::read_handshake_reply(&mut reader)?;
+        Ok((DevToolsClient { reader }, sender))
+    }
+
+    /// Waits for the next update from the app. The latest update of
+    /// each kind is sent straight after connecting.
+    pub fn receive(&mut self) -> Result<Update, ProtocolError> {
+        let line = protocol::read_line(&mut self.reader)?;
+        protocol::decode(&line)
+    }
+}
+
+impl CommandSender {
+    pub fn send(&mut self, command: &Command) -> Result<(), ProtocolError> {
+        let line = protocol::encode(command);
+        self.stream.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use crate::dom::element::ElementStates;
+use crate::dom::input::InputEvent;
+use crate::dom::node::AnyNode;
+use crate::style::{ComputedValues, StyleRule};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+mod client;
+mod profile;
+mod protocol;
+mod server;
+mod snapshot;
+
+pub use client::{CommandSender, DevToolsClient};
+pub use profile::{FrameTimings, Phase};
+pub use protocol::{Command, ProtocolError, Update};
+pub(crate) use server::{new_token, DevToolsServer};
+pub use snapshot::{ChildSnapshot, LayoutSnapshot, NodeSnapshot, Sides, StyleSnapshot};
+
+/// How a node was styled: the rules that matched it and the values they
+/// computed to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeStyle {
+    /// The rules from the node's styles that matched, in the order they
+    /// were applied. Inline styles are applied after these.
+    pub rules: Vec<StyleRule>,
+    /// See `ComputedValues::describe`.
+    pub computed: ComputedValues,
+}
+
+/// State shared between the devtools, which run along with the app's
+/// components, and the windows, which style the document on the main
+/// thread.
+#[derive(Debug, Default)]
+pub(crate) struct DevToolsShared {
+    inspected: Mutex<Option<u64>>,
+    style: Mutex<Option<(u64, NodeStyle)>>,
+    picking: AtomicBool,
+    picked: Mutex<Option<u64>>,
+    layouts: Mutex<HashMap<u64, LayoutSnapshot>>,
+    forced: Mutex<HashMap<u64, ElementStates>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_subscriber: AtomicU64,
+}
+
+#[derive(Clone, Debug)]
+struct Subscriber {
+    id: u64,
+    /// The root of the tool's own UI, see `DevToolsHandle::set_root`.
+    root: Option<u64>,
+    tools: Arc<Mutex<Box<dyn DevTools>>>,
+}
+
+thread_local! {
+    /// The subscribers whose hooks are running on this thread.
+    static DISPATCHING: RefCell<Vec<u64>> = RefCell::new(vec![]);
+}
+
+impl DevToolsShared {
+    pub(crate) fn inspected(&self) -> Option<u64> {
+        *self.inspected.lock().unwrap()
+    }
+
+    /// Returns the style of the inspected node, as of the last time it
+    /// was styled.
+    pub(crate) fn inspected_style(&self) -> Option<(u64, NodeStyle)> {
+        let inspected = self.inspected()?;
+        match *self.style.lock().unwrap() {
+            Some((id, ref style)) if id == inspected => Some((id, style.clone())),
+            _ => None,
+        }
+    }
+
+    /// Whether clicking a window should pick a node instead of
+    /// interacting with it.
+    pub(crate) fn picking(&self) -> bool {
+        self.picking.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn take_picked(&self) -> Option<u64> {
+        self.picked.lock().unwrap().take()
+    }
+
+    /// The states each node is styled as being in on top of its own.
+    pub(crate) fn forced_states(&self) -> HashMap<u64, ElementStates> {
+        self.forced.lock().unwrap().clone()
+    }
+
+    pub(crate) fn apply(&self, command: &Command) {
+        match *command {
+            Command::Inspect(id) => *self.inspected.lock().unwrap() = id,
+            Command::SetPicking(picking) => self.picking.store(picking, Ordering::Relaxed),
+            Command::ForceStates(id, states) => {
+                let mut forced = self.forced.lock().unwrap();
+                if states.is_empty() {
+                    forced.remove(&id);
+                } else {
+                    forced.insert(id, states);
+                }
+            }
+        }
+    }
+
+    /// Streams updates to devtools in other processes from now on.
+    pub(crate) fn serve(&self, server: DevToolsServer) {
+        self.add(Box::new(server));
+    }
+
+    fn add(&self, tools: Box<dyn DevTools>) -> u64 {
+        let id = self.next_subscriber.fetch_add(1, Ordering::Relaxed);
+        self.subscribers.lock().unwrap().push(Subscriber {
+            id,
+            root: None,
+            tools: Arc::new(Mutex::new(tools)),
+        });
+        id
+    }
+
+    fn subscribe(self: &Arc<Self>, tools: Box<dyn DevTools>) -> DevToolsHandle {
+        DevToolsHandle(Arc::new(HandleState {
+            id: self.add(tools),
+            shared: Arc::downgrade(self),
+        }))
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.id != id);
+    }
+
+    fn set_root(&self, id: u64, root: Option<u64>) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if let Some(subscriber) = subscribers
+            .iter_mut()
+            .find(|subscriber| subscriber.id == id)
+        {
+            subscriber.root = root;
+        }
+    }
+
+    /// Calls `func` with every registered tool and the root of its own
+    /// UI. Tools whose hooks are already running on this thread are
+    /// skipped, so that hooks never run re-entrantly.
+    pub(crate) fn each(&self, mut func: impl FnMut(&mut dyn DevTools, Option<u64>)) {
+        // Hooks can register and unregister tools, so the list isn't
+        // kept locked while they run.
+        let subscribers = self.subscribers.lock().unwrap().clone();
+        for subscriber in subscribers {
+            let running = DISPATCHING.with(|running| {
+                let mut running = running.borrow_mut();
+                if running.contains(&subscriber.id) {
+                    true
+                } else {
+                    running.push(subscriber.id);
+                    false
+                }
+            });
+            if running {
+                continue;
+            }
+            func(&mut **subscriber.tools.lock().unwrap(), subscriber.root);
+            DISPATCHING.with(|running| running.borrow_mut().retain(|&id| id != subscriber.id));
+        }
+    }
+
+    /// Records the node clicked while picking, which ends picking.
+    pub(crate) fn pick(&self, id: u64) {
+        self.stop_picking();
+        *self.picked.lock().unwrap() = Some(id);
+    }
+
+    pub(crate) fn stop_picking(&self) {
+        self.picking.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether devtools have been registered, so windows need to pass
+    /// their layouts on.
+    pub(crate) fn registered(&self) -> bool {
+        !self.subscribers.lock().unwrap().is_empty()
+    }
+
+    /// Stores the layout of the window with the given id, which is passed
+    /// to the devtools the next time the app updates.
+    pub(crate) fn publish_layout(&self, window: u64, layout: Option<LayoutSnapshot>) {
+        let mut layouts = self.layouts.lock().unwrap();
+        match layout {
+            Some(layout) => layouts.insert(window, layout),
+            None => layouts.remove(&window),
+        };
+    }
+
+    /// Stores the style of the inspected node, returning whether it
+    /// changed since it was last published.
+    pub(crate) fn publish_style(&self, id: u64, style: NodeStyle) -> bool {
+        let mut current = self.style.lock().unwrap();
+        let style = Some((id, style));
+        if *current == style {
+            false
+        } else {
+            *current = style;
+            true
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DevToolsRegistry {
+    shared: Arc<DevToolsShared>,
+}
+
+impl DevToolsRegistry {
+    pub(crate) fn new(shared: Arc<DevToolsShared>) -> DevToolsRegistry {
+        DevToolsRegistry { shared }
+    }
+
+    pub(crate) fn update(&self, node: AnyNode) {
+        let layouts = {
+            let layouts = self.shared.layouts.lock().unwrap();
+            let mut layouts = layouts.values().cloned().collect::<Vec<_>>();
+            layouts.sort_by_key(|layout| layout.node);
+            layouts
+        };
+        let snapshot = NodeSnapshot::new((&node).into());
+        self.shared.each(|tools, root| {
+            tools.on_update(match root {
+                Some(root) => snapshot.without(root),
+                None => snapshot.clone(),
+            });
+            tools.on_layout(match root {
+                Some(root) => layouts.iter().map(|layout| layout.without(root)).collect(),
+                None => layouts.clone(),
+            });
+        });
+    }
+}
+
+/// Hooks for inspecting the app, registered with `register_devtools`.
+/// `on_update` and `on_layout` run along with the app's components,
+/// while the rest are called from the main thread as windows render and
+/// handle input.
+///
+/// None of the hooks are told about the tool's own UI once it has been
+/// given to `DevToolsHandle::set_root`, so that showing what happened
+/// doesn't cause more to show.
+pub trait DevTools: Debug + Send + 'static {
+    /// Receives a snapshot of the DOM each time it's updated, without the
+    /// tool's own UI.
+    fn on_update(&mut self, node: NodeSnapshot);
+
+    /// Receives the layout tree of every window as of their last render,
+    /// after `on_update`. Layout changes alone don't cause an update, so
+    /// these can be a step behind the DOM.
+    fn on_layout(&mut self, _layouts: Vec<LayoutSnapshot>) {}
+
+    /// The nodes in `window` whose computed values were recalculated.
+    fn on_style_recalculated(&mut self, _window: u64, _nodes: &[u64]) {}
+
+    /// `window` was laid out again.
+    fn on_layout_done(&mut self, _window: u64, _layout: &LayoutSnapshot) {}
+
+    /// `window` finished rendering a frame, which took `timings`.
+    fn on_frame_rendered(&mut self, _window: u64, _timings: &FrameTimings) {}
+
+    /// An input event was dispatched to the node with id `target`.
+    fn on_event_dispatched(&mut self, _window: u64, _target: u64, _event: &InputEvent) {}
+}
+
+#[derive(Debug)]
+struct HandleState {
+    id: u64,
+    shared: Weak<DevToolsShared>,
+}
+
+impl Drop for HandleState {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.unsubscribe(self.id);
+        }
+    }
+}
+
+/// Keeps devtools registered until it and all of its clones are dropped,
+/// or until `unregister` is called.
+#[derive(Clone, Debug)]
+pub struct DevToolsHandle(Arc<HandleState>);
+
+impl DevToolsHandle {
+    /// Marks the node with `id` and its children as the tool's own UI,
+    /// which its hooks are no longer told about.
+    pub fn set_root(&self, id: Option<u64>) {
+        if let Some(shared) = self.0.shared.upgrade() {
+            shared.set_root(self.0.id, id);
+        }
+    }
+
+    /// Stops calling the tool's hooks, even if clones of the handle are
+    /// still around.
+    pub fn unregister(&self) {
+        if let Some(shared) = self.0.shared.upgrade() {
+            shared.unsubscribe(self.0.id);
+        }
+    }
+}
+
+/// Registers devtools, which are called until the returned handle is
+/// dropped. Any number can be registered at once. Components should only
+/// register once, such as with `moxie::once`, which also drops the handle
+/// when the component goes away.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn register_devtools(tools: impl DevTools) -> DevToolsHandle {
+    tools_registry.shared.subscribe(Box::new(tools))
+}
+
+/// Sets the node whose style the windows report, by its id. Once the
+/// document has been styled, the app is updated again so that
+/// `inspected_style` can return it.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn inspect_node(id: Option<u64>) {
+    tools_registry.shared.apply(&Command::Inspect(id));
+}
+
+/// Returns the style of the node set with `inspect_node`, as of the
+/// last time it was styled.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn inspected_style() -> Option<NodeStyle> {
+    tools_registry
+        .shared
+        .inspected_style()
+        .map(|(_id, style)| style)
+}
+
+/// Starts or stops picking a node. While picking, hovering over a window
+/// highlights the node under the mouse instead of interacting with it,
+/// and clicking picks it, to be returned by `take_picked`.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn set_picking(enabled: bool) {
+    tools_registry.shared.apply(&Command::SetPicking(enabled));
+}
+
+/// Whether a node is being picked, which stops once one is clicked.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn picking() -> bool {
+    tools_registry.shared.picking()
+}
+
+/// Returns the id of the node picked since this was last called.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn take_picked() -> Option<u64> {
+    tools_registry.shared.take_picked()
+}
+
+/// Styles the node with `id` as if it were in `states`, as well as the
+/// states it's really in, until this is called again. Passing no states
+/// stops forcing them.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn force_states(id: u64, states: ElementStates) {
+    tools_registry
+        .shared
+        .apply(&Command::ForceStates(id, states));
+}
+
+/// Applies a command the same way devtools in another process would.
+#[illicit::from_env(tools_registry: &DevToolsRegistry)]
+pub fn apply_command(command: &Command) {
+    tools_registry.shared.apply(command);
+}
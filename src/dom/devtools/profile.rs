@@ -0,0 +1,92 @@
+use log::trace;
+use std::time::{Duration, Instant};
+
+/// One of the steps of getting a frame on screen.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Phase {
+    /// Running the app's components.
+    App,
+    /// Computing the style of each node.
+    Style,
+    /// Building the layout tree.
+    Layout,
+    /// Building the display list from the layout tree.
+    DisplayList,
+    /// Submitting the display list to webrender and rendering it.
+    Render,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::App,
+        Phase::Style,
+        Phase::Layout,
+        Phase::DisplayList,
+        Phase::Render,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::App => "app",
+            Phase::Style => "style",
+            Phase::Layout => "layout",
+            Phase::DisplayList => "display list",
+            Phase::Render => "render",
+        }
+    }
+
+    /// Starts timing the phase, logging that it was entered at the trace
+    /// level.
+    pub(crate) fn enter(self) -> PhaseSpan {
+        trace!("Entering {} phase", self.name());
+        PhaseSpan {
+            phase: self,
+            start: Instant::now(),
+        }
+    }
+}
+
+/// A phase that is running, see `Phase::enter`.
+#[derive(Debug)]
+#[must_use]
+pub(crate) struct PhaseSpan {
+    phase: Phase,
+    start: Instant,
+}
+
+impl PhaseSpan {
+    /// Logs that the phase was left and returns how long it took.
+    pub(crate) fn exit(self) -> Duration {
+        let elapsed = self.start.elapsed();
+        trace!("Leaving {} phase after {:?}", self.phase.name(), elapsed);
+        elapsed
+    }
+}
+
+/// How long each phase of rendering a frame took.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct FrameTimings {
+    /// Zero for frames that weren't caused by the app updating, such as
+    /// when a window is resized.
+    pub app: Duration,
+    pub style: Duration,
+    pub layout: Duration,
+    pub display_list: Duration,
+    pub render: Duration,
+}
+
+impl FrameTimings {
+    pub fn get(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::App => self.app,
+            Phase::Style => self.style,
+            Phase::Layout => self.layout,
+            Phase::DisplayList => self.display_list,
+            Phase::Render => self.render,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        Phase::ALL.iter().map(|&phase| self.get(phase)).sum()
+    }
+}
@@ -0,0 +1,636 @@
+//! The format used between the app and devtools running in another
+//! process. Each message is one line of space separated values, starting
+//! with the message's name. Strings are quoted, options and enums start
+//! with a tag, and lists start with their length.
+
+use super::snapshot::{ChildSnapshot, LayoutSnapshot, NodeSnapshot, Sides, StyleSnapshot};
+use crate::dom::element::{ElementState, ElementStates};
+use crate::style::StyleRule;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+/// Sent by both sides when connecting, followed by the session's token
+/// from devtools. Connections between different versions, or with the
+/// wrong token, are refused.
+const HANDSHAKE: &str = "moxie-native-devtools 1";
+
+/// Sent from the app to devtools.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Update {
+    /// The app's DOM, sent whenever it changes.
+    Dom(NodeSnapshot),
+    /// The layout tree of every window.
+    Layouts(Vec<LayoutSnapshot>),
+    /// The style of the inspected node, or `None` if there isn't one or
+    /// it isn't in a window.
+    Style(Option<StyleSnapshot>),
+    /// Whether clicking a window picks a node.
+    Picking(bool),
+    /// A node was picked by clicking on it.
+    Picked(u64),
+}
+
+/// Sent from devtools to the app.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// Sets the node whose style is sent, see `inspect_node`.
+    Inspect(Option<u64>),
+    /// See `set_picking`.
+    SetPicking(bool),
+    /// Styles the node as if it's in the given states, as well as the
+    /// ones it's really in. See `force_states`.
+    ForceStates(u64, ElementStates),
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    /// The other side closed the connection.
+    Closed,
+    /// The other side speaks a different version of the protocol.
+    Version(String),
+    /// Devtools sent a token other than the one the app was started with.
+    Token,
+    /// A message couldn't be decoded.
+    Malformed(String),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::Io(err) => write!(f, "{}", err),
+            ProtocolError::Closed => write!(f, "Connection closed"),
+            ProtocolError::Version(handshake) => {
+                write!(f, "Expected {:?}, got {:?}", HANDSHAKE, handshake)
+            }
+            ProtocolError::Token => write!(f, "Wrong devtools token"),
+            ProtocolError::Malformed(message) => write!(f, "Malformed message: {}", message),
+        }
+    }
+}
+
+impl Error for ProtocolError {}
+
+impl From<io::Error> for ProtocolError {
+    fn from(err: io::Error) -> ProtocolError {
+        ProtocolError::Io(err)
+    }
+}
+
+fn malformed<T>(message: impl Into<String>) -> Result<T, ProtocolError> {
+    Err(ProtocolError::Malformed(message.into()))
+}
+
+pub(crate) struct Writer {
+    line: String,
+}
+
+impl Writer {
+    fn new() -> Writer {
+        Writer {
+            line: String::new(),
+        }
+    }
+
+    fn token(&mut self, token: impl fmt::Display) {
+        if !self.line.is_empty() {
+            self.line.push(' ');
+        }
+        self.line.push_str(&token.to_string());
+    }
+
+    fn string(&mut self, value: &str) {
+        let mut quoted = String::with_capacity(value.len() + 2);
+        quoted.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        self.token(quoted);
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(line: &'a str) -> Reader<'a> {
+        Reader {
+            chars: line.chars().peekable(),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.peek() == Some(&' ') {
+            self.chars.next();
+        }
+    }
+
+    fn word(&mut self) -> Result<String, ProtocolError> {
+        self.skip_spaces();
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == ' ' {
+                break;
+            }
+            word.push(c);
+            self.chars.next();
+        }
+        if word.is_empty() {
+            malformed("Unexpected end of message")
+        } else {
+            Ok(word)
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self) -> Result<T, ProtocolError> {
+        let word = self.word()?;
+        match word.parse() {
+            Ok(value) => Ok(value),
+            Err(_) => malformed(format!("Unexpected {:?}", word)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ProtocolError> {
+        self.skip_spaces();
+        if self.chars.next() != Some('"') {
+            return malformed("Expected a string");
+        }
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.chars.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    _ => return malformed("Invalid escape in string"),
+                },
+                Some(c) => value.push(c),
+                None => return malformed("Unterminated string"),
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), ProtocolError> {
+        self.skip_spaces();
+        match self.chars.peek() {
+            Some(_) => malformed("Unexpected data after message"),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(crate) trait Encode {
+    fn encode(&self, writer: &mut Writer);
+}
+
+pub(crate) trait Decode: Sized {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError>;
+}
+
+macro_rules! encode_parsed {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.token(self);
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+                    reader.parse()
+                }
+            }
+        )*
+    };
+}
+
+encode_parsed!(u64, f32, bool);
+
+impl Encode for String {
+    fn encode(&self, writer: &mut Writer) {
+        writer.string(self);
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        reader.string()
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Some(value) => {
+                writer.token("some");
+                value.encode(writer);
+            }
+            None => writer.token("none"),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        match &reader.word()?[..] {
+            "some" => Ok(Some(T::decode(reader)?)),
+            "none" => Ok(None),
+            tag => malformed(format!("Expected some or none, got {:?}", tag)),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.token(self.len());
+        for value in self {
+            value.encode(writer);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        let len: usize = reader.parse()?;
+        (0..len).map(|_| T::decode(reader)).collect()
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, writer: &mut Writer) {
+        self.0.encode(writer);
+        self.1.encode(writer);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        Ok((A::decode(reader)?, B::decode(reader)?))
+    }
+}
+
+impl Encode for ElementStates {
+    fn encode(&self, writer: &mut Writer) {
+        writer.token(self.len());
+        for state in self.iter() {
+            writer.token(match state {
+                ElementState::Hover => "hover",
+                ElementState::Press => "press",
+                ElementState::Focus => "focus",
+                ElementState::Checked => "checked",
+                ElementState::Open => "open",
+            });
+        }
+    }
+}
+
+impl Decode for ElementStates {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        let len: usize = reader.parse()?;
+        let mut states = ElementStates::empty();
+        for _ in 0..len {
+            states |= match &reader.word()?[..] {
+                "hover" => ElementState::Hover,
+                "press" => ElementState::Press,
+                "focus" => ElementState::Focus,
+                "checked" => ElementState::Checked,
+                "open" => ElementState::Open,
+                state => return malformed(format!("Unknown state {:?}", state)),
+            };
+        }
+        Ok(states)
+    }
+}
+
+/// Implements `Encode` and `Decode` for a struct by encoding its fields
+/// in order.
+macro_rules! encode_struct {
+    ($name:ident { $($field:ident),* }) => {
+        impl Encode for $name {
+            fn encode(&self, writer: &mut Writer) {
+                $(self.$field.encode(writer);)*
+            }
+        }
+
+        impl Decode for $name {
+            fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+                Ok($name {
+                    $($field: Decode::decode(reader)?,)*
+                })
+            }
+        }
+    };
+}
+
+encode_struct!(Sides {
+    top,
+    right,
+    bottom,
+    left
+});
+encode_struct!(LayoutSnapshot {
+    node,
+    name,
+    text,
+    x,
+    y,
+    width,
+    height,
+    margin,
+    border,
+    padding,
+    baseline,
+    children
+});
+encode_struct!(NodeSnapshot {
+    id,
+    name,
+    styles,
+    classes,
+    attributes,
+    children
+});
+encode_struct!(StyleRule {
+    style,
+    selector,
+    attributes
+});
+encode_struct!(StyleSnapshot {
+    node,
+    rules,
+    computed
+});
+
+impl Encode for ChildSnapshot {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            ChildSnapshot::Node(node) => {
+                writer.token("node");
+                node.encode(writer);
+            }
+            ChildSnapshot::Text(text) => {
+                writer.token("text");
+                text.encode(writer);
+            }
+        }
+    }
+}
+
+impl Decode for ChildSnapshot {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        match &reader.word()?[..] {
+            "node" => Ok(ChildSnapshot::Node(Decode::decode(reader)?)),
+            "text" => Ok(ChildSnapshot::Text(Decode::decode(reader)?)),
+            tag => malformed(format!("Unknown child {:?}", tag)),
+        }
+    }
+}
+
+impl Encode for Update {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Update::Dom(node) => {
+                writer.token("dom");
+                node.encode(writer);
+            }
+            Update::Layouts(layouts) => {
+                writer.token("layouts");
+                layouts.encode(writer);
+            }
+            Update::Style(style) => {
+                writer.token("style");
+                style.encode(writer);
+            }
+            Update::Picking(picking) => {
+                writer.token("picking");
+                picking.encode(writer);
+            }
+            Update::Picked(id) => {
+                writer.token("picked");
+                id.encode(writer);
+            }
+        }
+    }
+}
+
+impl Decode for Update {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        match &reader.word()?[..] {
+            "dom" => Ok(Update::Dom(Decode::decode(reader)?)),
+            "layouts" => Ok(Update::Layouts(Decode::decode(reader)?)),
+            "style" => Ok(Update::Style(Decode::decode(reader)?)),
+            "picking" => Ok(Update::Picking(Decode::decode(reader)?)),
+            "picked" => Ok(Update::Picked(Decode::decode(reader)?)),
+            name => malformed(format!("Unknown update {:?}", name)),
+        }
+    }
+}
+
+impl Encode for Command {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Command::Inspect(id) => {
+                writer.token("inspect");
+                id.encode(writer);
+            }
+            Command::SetPicking(picking) => {
+                writer.token("set_picking");
+                picking.encode(writer);
+            }
+            Command::ForceStates(id, states) => {
+                writer.token("force_states");
+                id.encode(writer);
+                states.encode(writer);
+            }
+        }
+    }
+}
+
+impl Decode for Command {
+    fn decode(reader: &mut Reader) -> Result<Self, ProtocolError> {
+        match &reader.word()?[..] {
+            "inspect" => Ok(Command::Inspect(Decode::decode(reader)?)),
+            "set_picking" => Ok(Command::SetPicking(Decode::decode(reader)?)),
+            "force_states" => Ok(Command::ForceStates(
+                Decode::decode(reader)?,
+                Decode::decode(reader)?,
+            )),
+            name => malformed(format!("Unknown command {:?}", name)),
+        }
+    }
+}
+
+/// Encodes a message as a line, including the newline.
+pub(crate) fn encode(message: &impl Encode) -> String {
+    let mut writer = Writer::new();
+    message.encode(&mut writer);
+    writer.line.push('\n');
+    writer.line
+}
+
+pub(crate) fn decode<T: Decode>(line: &str) -> Result<T, ProtocolError> {
+    let mut reader = Reader::new(line.trim_end_matches(&['\r', '\n'][..]));
+    let message = T::decode(&mut reader)?;
+    reader.finish()?;
+    Ok(message)
+}
+
+/// Sent by devtools when connecting, with the token the server expects.
+pub(crate) fn write_handshake(stream: &mut impl Write, token: &str) -> Result<(), ProtocolError> {
+    writeln!(stream, "{} {}", HANDSHAKE, token)?;
+    Ok(())
+}
+
+/// Sent by the server once it has accepted devtools' handshake.
+pub(crate) fn write_handshake_reply(stream: &mut impl Write) -> Result<(), ProtocolError> {
+    writeln!(stream, "{}", HANDSHAKE)?;
+    Ok(())
+}
+
+/// Reads one line, which `decode` can then turn into a message.
+pub(crate) fn read_line(reader: &mut impl BufRead) -> Result<String, ProtocolError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(ProtocolError::Closed);
+    }
+    Ok(line)
+}
+
+/// Reads the handshake from devtools, which must have the given token.
+pub(crate) fn read_handshake(reader: &mut impl BufRead, token: &str) -> Result<(), ProtocolError> {
+    let line = read_line(reader)?;
+    let line = line.trim_end();
+    let sent = match line
+        .strip_prefix(HANDSHAKE)
+        .and_then(|rest| rest.strip_prefix(' '))
+    {
+        Some(sent) => sent,
+        None => return Err(ProtocolError::Version(line.to_owned())),
+    };
+    // Compares every byte, so that how long this takes doesn't give away
+    // how much of the token was right.
+    let matches = sent.len() == token.len()
+        && sent
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(ProtocolError::Token)
+    }
+}
+
+pub(crate) fn read_handshake_reply(reader: &mut impl BufRead) -> Result<(), ProtocolError> {
+    let line = read_line(reader)?;
+    let line = line.trim_end();
+    if line == HANDSHAKE {
+        Ok(())
+    } else {
+        Err(ProtocolError::Version(line.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_messages() {
+        let layout = LayoutSnapshot {
+            node: 3,
+            name: "span".to_owned(),
+            text: Some("two \"quoted\"\nlines \\".to_owned()),
+            x: 0.5,
+            y: 1e-3,
+            width: 120.25,
+            height: -0.0,
+            margin: Sides::default(),
+            border: Sides {
+                top: 1.0,
+                right: 2.0,
+                bottom: 3.0,
+                left: 4.0,
+            },
+            padding: Sides::default(),
+            baseline: None,
+            children: vec![],
+        };
+        let updates = vec![
+            Update::Dom(NodeSnapshot {
+                id: 1,
+                name: "view".to_owned(),
+                styles: vec!["BUTTON".to_owned()],
+                classes: vec![],
+                attributes: vec![("title".to_owned(), "a b".to_owned())],
+                children: vec![ChildSnapshot::Text(" ".to_owned())],
+            }),
+            Update::Layouts(vec![layout]),
+            Update::Style(Some(StyleSnapshot {
+                node: 1,
+                rules: vec![StyleRule {
+                    style: "BUTTON".to_owned(),
+                    selector: Some("state: hover".to_owned()),
+                    attributes: vec![("width".to_owned(), "10 px".to_owned())],
+                }],
+                computed: vec![],
+            })),
+            Update::Style(None),
+            Update::Picked(7),
+        ];
+        for update in updates {
+            let line = encode(&update);
+            assert_eq!(line.matches('\n').count(), 1);
+            assert_eq!(decode::<Update>(&line).unwrap(), update);
+        }
+
+        let command = Command::ForceStates(2, ElementState::Hover | ElementState::Press);
+        assert_eq!(decode::<Command>(&encode(&command)).unwrap(), command);
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        assert!(decode::<Command>("inspect").is_err());
+        assert!(decode::<Command>("inspect some 1 2").is_err());
+        assert!(decode::<Command>("force_states 1 1 active").is_err());
+        assert!(decode::<Update>("dom 1 \"unterminated").is_err());
+    }
+
+    #[test]
+    fn only_accepts_the_session_token() {
+        let mut handshake = vec![];
+        write_handshake(&mut handshake, "secret").unwrap();
+        assert!(read_handshake(&mut &handshake[..], "secret").is_ok());
+        assert!(matches!(
+            read_handshake(&mut &handshake[..], "other"),
+            Err(ProtocolError::Token)
+        ));
+        assert!(matches!(
+            read_handshake(&mut &handshake[..], "secrets"),
+            Err(ProtocolError::Token)
+        ));
+        assert!(matches!(
+            read_handshake(&mut &b"moxie-native-devtools 0 secret\n"[..], "secret"),
+            Err(ProtocolError::Version(_))
+        ));
+
+        let mut reply = vec![];
+        write_handshake_reply(&mut reply).unwrap();
+        assert!(read_handshake_reply(&mut &reply[..]).is_ok());
+    }
+}
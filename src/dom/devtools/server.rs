@@ -0,0 +1,209 @@
+use super::protocol::{self, Command, ProtocolError, Update};
+use super::snapshot::{LayoutSnapshot, NodeSnapshot};
+use super::{DevTools, DevToolsShared};
+use crate::runtime::UpdateHandle;
+use log::{info, warn};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Makes a random token for devtools to connect with. The keys of each
+/// `RandomState` are random, so hashing with them gives numbers that
+/// other processes can't guess.
+pub(crate) fn new_token() -> String {
+    let half = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", half(), half())
+}
+
+/// How long devtools have to send the handshake after connecting.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long devtools can take to accept an update before they're
+/// disconnected.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The last update of each kind, which devtools are sent when they
+/// connect.
+#[derive(Debug, Default)]
+struct Latest {
+    dom: Option<String>,
+    layouts: Option<String>,
+    style: Option<String>,
+    picking: Option<String>,
+}
+
+impl Latest {
+    fn get_mut(&mut self, update: &Update) -> Option<&mut Option<String>> {
+        match update {
+            Update::Dom(_) => Some(&mut self.dom),
+            Update::Layouts(_) => Some(&mut self.layouts),
+            Update::Style(_) => Some(&mut self.style),
+            Update::Picking(_) => Some(&mut self.picking),
+            Update::Picked(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ServerState {
+    /// Devtools have to send this when connecting, so that other users
+    /// and processes on the machine can't.
+    token: String,
+    shared: Arc<DevToolsShared>,
+    updates: UpdateHandle,
+    /// Lines queued for each client, which its own thread writes so that
+    /// a slow client can't hold up the app.
+    clients: Mutex<Vec<Sender<String>>>,
+    latest: Mutex<Latest>,
+}
+
+/// Streams snapshots of the app to devtools in other processes, and
+/// applies the commands they send back. Only accepts connections from
+/// the same machine that know the session's token.
+#[derive(Clone, Debug)]
+pub(crate) struct DevToolsServer(Arc<ServerState>);
+
+impl DevToolsServer {
+    pub(crate) fn start(
+        port: u16,
+        token: String,
+        shared: Arc<DevToolsShared>,
+        updates: UpdateHandle,
+    ) -> io::Result<DevToolsServer> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        info!("Devtools server listening on {}", listener.local_addr()?);
+
+        let server = DevToolsServer(Arc::new(ServerState {
+            token,
+            shared,
+            updates,
+            clients: Mutex::new(vec![]),
+            latest: Mutex::new(Latest::default()),
+        }));
+        let accepting = server.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        warn!("Devtools failed to connect: {}", err);
+                        continue;
+                    }
+                };
+                let server = accepting.clone();
+                std::thread::spawn(move || match server.connect(stream) {
+                    Ok(reader) => server.receive(reader),
+                    Err(err) => warn!("Devtools failed to connect: {}", err),
+                });
+            }
+        });
+        Ok(server)
+    }
+
+    /// Exchanges handshakes with a new client and starts sending it
+    /// updates, beginning with the latest of each kind. Runs on the
+    /// client's own thread, which goes on to read its commands.
+    fn connect(&self, stream: TcpStream) -> Result<BufReader<TcpStream>, ProtocolError> {
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        protocol::read_handshake(&mut reader, &self.0.token)?;
+        protocol::write_handshake_reply(&mut writer)?;
+        writer.set_read_timeout(None)?;
+        info!("Devtools connected from {}", writer.peer_addr()?);
+
+        // Queuing the backlog and adding the client under the same lock
+        // makes sure it doesn't miss an update sent in between, without
+        // holding the lock while anything is written.
+        let (sender, lines) = mpsc::channel();
+        {
+            let latest = self.0.latest.lock().unwrap();
+            let backlog = [&latest.dom, &latest.layouts, &latest.style, &latest.picking];
+            for line in backlog.iter().filter_map(|line| line.as_ref()) {
+                let _ = sender.send(line.clone());
+            }
+            self.0.clients.lock().unwrap().push(sender);
+        }
+        std::thread::spawn(move || write_lines(writer, lines));
+        Ok(reader)
+    }
+
+    /// Applies commands from one client until it disconnects.
+    fn receive(&self, mut reader: BufReader<TcpStream>) {
+        loop {
+            let command = protocol::read_line(&mut reader)
+                .and_then(|line| protocol::decode::<Command>(&line));
+            match command {
+                Ok(command) => {
+                    self.0.shared.apply(&command);
+                    self.0.updates.request_update();
+                }
+                Err(ProtocolError::Malformed(message)) => {
+                    warn!("Ignoring devtools command: {}", message);
+                }
+                Err(ProtocolError::Closed) => {
+                    info!("Devtools disconnected");
+                    break;
+                }
+                Err(err) => {
+                    warn!("Devtools disconnected: {}", err);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Queues the update for every client, unless it's the same as the
+    /// last one of its kind. Clients that were disconnected are dropped.
+    fn send(&self, update: &Update) {
+        let line = protocol::encode(update);
+        let mut latest = self.0.latest.lock().unwrap();
+        if let Some(latest) = latest.get_mut(update) {
+            if latest.as_ref() == Some(&line) {
+                return;
+            }
+            *latest = Some(line.clone());
+        }
+        self.0
+            .clients
+            .lock()
+            .unwrap()
+            .retain(|client| client.send(line.clone()).is_ok());
+    }
+}
+
+/// Writes queued lines to a client until it disconnects or stops
+/// accepting them, which drops the queue.
+fn write_lines(mut writer: TcpStream, lines: Receiver<String>) {
+    for line in lines {
+        if let Err(err) = writer.write_all(line.as_bytes()) {
+            warn!("Devtools disconnected: {}", err);
+            break;
+        }
+    }
+}
+
+impl DevTools for DevToolsServer {
+    fn on_update(&mut self, node: NodeSnapshot) {
+        let shared = &self.0.shared;
+        self.send(&Update::Dom(node));
+        self.send(&Update::Picking(shared.picking()));
+        if let Some(id) = shared.take_picked() {
+            self.send(&Update::Picked(id));
+        }
+        let style = shared
+            .inspected_style()
+            .map(|(id, style)| style.snapshot(id));
+        self.send(&Update::Style(style));
+    }
+
+    fn on_layout(&mut self, layouts: Vec<LayoutSnapshot>) {
+        self.send(&Update::Layouts(layouts));
+    }
+}
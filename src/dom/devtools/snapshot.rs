@@ -0,0 +1,158 @@
+use super::NodeStyle;
+use crate::dom::element::DynamicNode;
+use crate::dom::node::NodeRef;
+use crate::style::StyleRule;
+
+/// The width of each side of one of a node's boxes.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Sides {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// A copy of a node from a window's layout tree. DOM nodes can be laid
+/// out as several of these, such as one for each line of text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LayoutSnapshot {
+    /// The id of the node, or for a line of text, of the node containing
+    /// it.
+    pub node: u64,
+    pub name: String,
+    /// The text on the line, if this is a line of text.
+    pub text: Option<String>,
+    /// Position of the border box relative to the window.
+    pub x: f32,
+    pub y: f32,
+    /// Size of the border box.
+    pub width: f32,
+    pub height: f32,
+    pub margin: Sides,
+    pub border: Sides,
+    pub padding: Sides,
+    /// Distance from the top of the border box to the baseline of the last
+    /// line of text inside.
+    pub baseline: Option<f32>,
+    pub children: Vec<LayoutSnapshot>,
+}
+
+impl LayoutSnapshot {
+    /// Finds the layout of the node with `id` within this one, skipping
+    /// lines of text.
+    pub fn find(&self, id: u64) -> Option<&LayoutSnapshot> {
+        if self.node == id && self.text.is_none() {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// Copies the layout, leaving out the node with `id` and everything
+    /// inside it.
+    pub fn without(&self, id: u64) -> LayoutSnapshot {
+        LayoutSnapshot {
+            name: self.name.clone(),
+            text: self.text.clone(),
+            children: self
+                .children
+                .iter()
+                .filter(|child| child.node != id)
+                .map(|child| child.without(id))
+                .collect(),
+            ..*self
+        }
+    }
+}
+
+/// A copy of a DOM node and its children, which unlike `AnyNode` can be
+/// sent to devtools running in another process.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeSnapshot {
+    pub id: u64,
+    pub name: String,
+    /// The names of the node's styles.
+    pub styles: Vec<String>,
+    pub classes: Vec<String>,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<ChildSnapshot>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ChildSnapshot {
+    Node(NodeSnapshot),
+    Text(String),
+}
+
+impl NodeSnapshot {
+    /// Copies the node and its children.
+    pub fn new(node: NodeRef) -> NodeSnapshot {
+        NodeSnapshot {
+            id: node.id(),
+            name: node.name().to_owned(),
+            styles: node
+                .styles()
+                .iter()
+                .map(|style| style.name().into_owned())
+                .collect(),
+            classes: node.classes().to_vec(),
+            attributes: node
+                .attributes()
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+            children: node
+                .children()
+                .map(|child| match child {
+                    DynamicNode::Node(node) => ChildSnapshot::Node(NodeSnapshot::new(node)),
+                    DynamicNode::Text(text) => ChildSnapshot::Text(text.to_owned()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Copies the snapshot, leaving out the node with `id` and everything
+    /// inside it.
+    pub fn without(&self, id: u64) -> NodeSnapshot {
+        NodeSnapshot {
+            id: self.id,
+            name: self.name.clone(),
+            styles: self.styles.clone(),
+            classes: self.classes.clone(),
+            attributes: self.attributes.clone(),
+            children: self
+                .children
+                .iter()
+                .filter(|child| !matches!(child, ChildSnapshot::Node(node) if node.id == id))
+                .map(|child| match child {
+                    ChildSnapshot::Node(node) => ChildSnapshot::Node(node.without(id)),
+                    ChildSnapshot::Text(text) => ChildSnapshot::Text(text.clone()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The style of the inspected node as sent to devtools, with the computed
+/// values described as text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StyleSnapshot {
+    pub node: u64,
+    pub rules: Vec<StyleRule>,
+    /// See `ComputedValues::describe`.
+    pub computed: Vec<(String, String)>,
+}
+
+impl NodeStyle {
+    pub fn snapshot(&self, node: u64) -> StyleSnapshot {
+        StyleSnapshot {
+            node,
+            rules: self.rules.clone(),
+            computed: self
+                .computed
+                .describe()
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+        }
+    }
+}
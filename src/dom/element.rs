@@ -1,6 +1,7 @@
+use crate::dom::accessibility::Role;
 use crate::dom::input::InputEvent;
 use crate::dom::node::{Node, NodeRef};
-use crate::style::{ComputedValues, Style};
+use crate::style::{ComputedValues, InlineStyle, Style};
 use crate::util::event_handler::EventHandler;
 use enumset::{EnumSet, EnumSetType};
 use std::fmt::Debug;
@@ -22,6 +23,90 @@ pub trait Element: Default + Clone + Debug + PartialEq + 'static {
         false
     }
 
+    /// Whether the element can be focused with the keyboard or by
+    /// assistive technology.
+    fn focusable(&self) -> bool {
+        self.interactive()
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    /// The name assistive technology gives the element. Without one, it's
+    /// named after the text inside it if its role allows that.
+    fn accessible_name(&self) -> Option<String> {
+        None
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        None
+    }
+
+    /// States the element is in because of its attributes, such as
+    /// `Checked`, on top of the ones input puts it in.
+    fn states(&self) -> ElementStates {
+        ElementStates::empty()
+    }
+
+    /// The group the element belongs to, if it's one of several that act
+    /// as a single control, like radio buttons.
+    fn group(&self) -> Option<&str> {
+        None
+    }
+
+    /// The value of range elements like `<slider>`. Their children are
+    /// laid out as a track, fill and thumb according to it.
+    fn range(&self) -> Option<RangeValue> {
+        None
+    }
+
+    /// Whether the element's children are options shown in a popup above
+    /// the rest of the window while it's `open`, like a select's. The
+    /// element itself only shows the selected one.
+    fn has_popup(&self) -> bool {
+        false
+    }
+
+    /// The value of an option, or of the element it's chosen for, which
+    /// selects the option with the same value.
+    fn value(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the element's `value` is text the document edits for it,
+    /// like a textarea's. Edits are passed to it as `Edited` events.
+    fn editable(&self) -> bool {
+        false
+    }
+
+    /// Text shown in a tooltip near the pointer when it rests on the
+    /// element, from its `title`.
+    fn tooltip(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the element is laid out in the window's top layer, above
+    /// the rest of the window and at its size, instead of where it is in
+    /// the tree.
+    fn top_layer(&self) -> bool {
+        false
+    }
+
+    /// Whether the element blocks the rest of the window while it's in
+    /// the top layer. Clicks below it are caught, and focus is kept
+    /// inside it.
+    fn modal(&self) -> bool {
+        false
+    }
+
+    /// Children to build the element with when it's given none, such as
+    /// the parts of a slider. Called while the element's node is built,
+    /// so nodes built here keep their ids between updates.
+    fn default_children(&self) -> Vec<Self::Child> {
+        vec![]
+    }
+
     fn process(
         &self,
         states: ElementStates,
@@ -31,8 +116,20 @@ pub trait Element: Default + Clone + Debug + PartialEq + 'static {
         (false, states)
     }
 
-    /// Returns the list of styles attached to this element.
-    fn style(&self) -> Option<Style>;
+    /// Returns the list of styles attached to this element, in the
+    /// order they should be applied.
+    fn styles(&self) -> &[Style];
+
+    /// Returns the class names that `class:` selectors match against.
+    fn classes(&self) -> &[String] {
+        &[]
+    }
+
+    /// Returns the inline style overrides, which are applied after
+    /// `styles()`.
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        None
+    }
 
     fn attributes(&self) -> Vec<(&'static str, String)> {
         vec![]
@@ -64,14 +161,57 @@ where
     fn set_attribute(&mut self, value: Attr::Value);
 }
 
-#[derive(EnumSetType)]
+#[derive(EnumSetType, Debug)]
 pub enum ElementState {
     Hover,
     Press,
+    Focus,
+    Checked,
+    /// The element's popup is shown, like a select's list of options.
+    Open,
 }
 
 pub type ElementStates = EnumSet<ElementState>;
 
+/// A number between a minimum and maximum, such as a slider's value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RangeValue {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    /// The values are multiples of `step` from `min`, or any value if
+    /// it's 0.
+    pub step: f64,
+}
+
+impl RangeValue {
+    /// How far the value is from `min` to `max`, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The value `fraction` of the way from `min` to `max`, rounded to
+    /// the nearest step.
+    pub fn at_fraction(&self, fraction: f64) -> f64 {
+        self.snap(self.min + fraction.clamp(0.0, 1.0) * (self.max - self.min))
+    }
+
+    /// Rounds `value` to the nearest step and keeps it within range. The
+    /// value is `min` if `max` is below it.
+    pub fn snap(&self, value: f64) -> f64 {
+        let value = if self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.min(self.max).max(self.min)
+    }
+}
+
 pub enum DynamicNode<'a> {
     Text(&'a str),
     Node(NodeRef<'a>),
@@ -128,6 +268,64 @@ impl NodeChild for String {
     }
 }
 
+/// The child type of elements that can't have children.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NoChildren {}
+
+impl NodeChild for NoChildren {
+    fn get_node(&self) -> DynamicNode {
+        match *self {}
+    }
+}
+
 pub trait HandlerList: Default + 'static {}
 
 impl HandlerList for () {}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeValue;
+
+    fn range(min: f64, max: f64, step: f64) -> RangeValue {
+        RangeValue {
+            value: min,
+            min,
+            max,
+            step,
+        }
+    }
+
+    #[test]
+    fn snaps_to_steps_from_the_minimum() {
+        let range = range(1.0, 11.0, 2.0);
+        assert_eq!(range.snap(4.2), 5.0);
+        assert_eq!(range.snap(3.8), 3.0);
+        assert_eq!(range.snap(-4.0), 1.0);
+        assert_eq!(range.snap(20.0), 11.0);
+    }
+
+    #[test]
+    fn keeps_values_in_range_without_a_step() {
+        let range = range(0.0, 1.0, 0.0);
+        assert_eq!(range.snap(0.33), 0.33);
+        assert_eq!(range.snap(-0.5), 0.0);
+        assert_eq!(range.snap(1.5), 1.0);
+    }
+
+    #[test]
+    fn snaps_to_the_ends_when_the_step_is_larger_than_the_range() {
+        let range = range(0.0, 1.0, 5.0);
+        assert_eq!(range.snap(0.8), 0.0);
+        assert_eq!(range.snap(3.0), 1.0);
+        assert_eq!(range.at_fraction(1.0), 0.0);
+    }
+
+    #[test]
+    fn stays_at_the_minimum_when_it_is_above_the_maximum() {
+        let range = range(10.0, 0.0, 1.0);
+        assert_eq!(range.snap(5.0), 10.0);
+        assert_eq!(range.snap(20.0), 10.0);
+        assert_eq!(range.at_fraction(0.5), 10.0);
+        assert_eq!(range.fraction(), 0.0);
+    }
+}
@@ -19,7 +19,7 @@ impl Element for App {
 
     const ELEMENT_NAME: &'static str = "app";
 
-    fn style(&self) -> Option<Style> {
-        None
+    fn styles(&self) -> &[Style] {
+        &[]
     }
 }
@@ -1,14 +1,24 @@
-use crate::dom::element::{Element, ElementState, ElementStates, HasEvent};
-use crate::dom::input::{InputEvent, State};
-use crate::dom::{AttrStyle, ClickEvent, Node, Span, View};
-use crate::style::Style;
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementStates, HasEvent};
+use crate::dom::elements::control::process_pressable;
+use crate::dom::input::InputEvent;
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, AttrTitle, ClassList,
+    ClickEvent, Node, Span, View,
+};
+use crate::style::{InlineStyle, Style, StyleList};
 use crate::util::event_handler::EventHandler;
 
 /// Corresponds to <button>. This element can be hovered and pressed,
 /// resulting in corresponding events.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct Button {
-    style: Option<Style>,
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    title: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
 }
 
 multiple_children! {
@@ -22,6 +32,11 @@ multiple_children! {
 element_attributes! {
     Button {
         style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        title: AttrTitle,
+        label: AttrLabel,
+        description: AttrDescription,
     }
 }
 
@@ -41,34 +56,48 @@ impl Element for Button {
         true
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = describe_attributes(&self.label, &self.description);
+        if let Some(ref title) = self.title {
+            attributes.push(("title", format!("{:?}", title)));
+        }
+        attributes
+    }
+
     fn process(
         &self,
         states: ElementStates,
         handlers: &mut Self::Handlers,
         event: &InputEvent,
     ) -> (bool, ElementStates) {
-        match event {
-            InputEvent::Hovered {
-                state: State::Begin,
-            } => (true, states | ElementState::Hover),
-            InputEvent::Hovered { state: State::End } => {
-                (true, states.difference(ElementState::Hover.into()))
-            }
-            InputEvent::MouseLeft {
-                state: State::Begin,
-                ..
-            } => (true, states | ElementState::Press),
-            InputEvent::MouseLeft {
-                state: State::End, ..
-            } if states.contains(ElementState::Press) => {
-                handlers.on_click.invoke(&ClickEvent);
-                (true, states.difference(ElementState::Press.into()))
-            }
-            _ => (false, states),
-        }
+        process_pressable(states, event, || handlers.on_click.invoke(&ClickEvent))
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
     }
 
-    fn style(&self) -> Option<Style> {
-        self.style
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
     }
 }
@@ -0,0 +1,112 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementState, ElementStates, HasEvent, NoChildren};
+use crate::dom::elements::control::{accent_color, control_values, process_pressable};
+use crate::dom::input::InputEvent;
+use crate::dom::{
+    AttrChecked, AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, ChangeEvent,
+    ClassList,
+};
+use crate::style::{ComputedValues, Edges, InlineStyle, Style, StyleList};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// Corresponds to <checkbox>. Clicking it fires `on_change` with the
+/// opposite of `checked`. It's in the `checked` state while checked.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Checkbox {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    checked: bool,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    Checkbox {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        checked: AttrChecked,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    CheckboxHandlers for Checkbox {
+        on_change: ChangeEvent<bool>,
+    }
+}
+
+impl Element for Checkbox {
+    type Child = NoChildren;
+    type Handlers = CheckboxHandlers;
+
+    const ELEMENT_NAME: &'static str = "checkbox";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        let border = Edges::new_all_same(1.0);
+        if self.checked {
+            control_values(16.0, 16.0, 3.0, border, accent_color(), accent_color())
+        } else {
+            let gray = Color::new(120, 120, 120, 255);
+            control_values(16.0, 16.0, 3.0, border, gray, Color::white())
+        }
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Checkbox
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn states(&self) -> ElementStates {
+        if self.checked {
+            ElementState::Checked.into()
+        } else {
+            ElementStates::empty()
+        }
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("checked", self.checked.to_string())];
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        process_pressable(states, event, || {
+            handlers.on_change.invoke(&ChangeEvent {
+                value: !self.checked,
+            })
+        })
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
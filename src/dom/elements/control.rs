@@ -0,0 +1,79 @@
+//! Behavior and default looks shared by the interactive elements.
+
+use crate::dom::element::{ElementState, ElementStates};
+use crate::dom::input::{InputEvent, State};
+use crate::layout::{LogicalLength, LogicalSideOffsets};
+use crate::style::{BlockValues, Border, BorderStyle, ComputedValues, Corners, DisplayType, Edges};
+use crate::Color;
+
+/// Handles hover, press and focus the way buttons do, calling
+/// `activate` when the element is clicked or activated from the
+/// keyboard or by assistive technology.
+pub(crate) fn process_pressable(
+    states: ElementStates,
+    event: &InputEvent,
+    activate: impl FnOnce(),
+) -> (bool, ElementStates) {
+    match event {
+        InputEvent::Hovered {
+            state: State::Begin,
+        } => (true, states | ElementState::Hover),
+        InputEvent::Hovered { state: State::End } => {
+            (true, states.difference(ElementState::Hover.into()))
+        }
+        InputEvent::MouseLeft {
+            state: State::Begin,
+            ..
+        } => (true, states | ElementState::Press),
+        InputEvent::MouseLeft {
+            state: State::End, ..
+        } if states.contains(ElementState::Press) => {
+            activate();
+            (true, states.difference(ElementState::Press.into()))
+        }
+        InputEvent::Focused {
+            state: State::Begin,
+        } => (true, states | ElementState::Focus),
+        InputEvent::Focused { state: State::End } => {
+            (true, states.difference(ElementState::Focus.into()))
+        }
+        InputEvent::Activated => {
+            activate();
+            (true, states)
+        }
+        _ => (false, states),
+    }
+}
+
+/// The color checked controls are filled with unless styled otherwise.
+pub(crate) fn accent_color() -> Color {
+    Color::new(40, 110, 220, 255)
+}
+
+/// A fixed size inline box with a solid border, which the checkbox,
+/// radio and switch elements start from.
+pub(crate) fn control_values(
+    width: f32,
+    height: f32,
+    radius: f32,
+    border: Edges<f32>,
+    border_color: Color,
+    background_color: Color,
+) -> ComputedValues {
+    ComputedValues {
+        display: DisplayType::InlineBlock(BlockValues {
+            width: Some(LogicalLength::new(width)),
+            height: Some(LogicalLength::new(height)),
+            margin: LogicalSideOffsets::new(2.0, 4.0, 2.0, 0.0),
+            ..Default::default()
+        }),
+        background_color,
+        border: border.map(|width| Border {
+            width: LogicalLength::new(width),
+            style: BorderStyle::Solid,
+            color: border_color,
+        }),
+        corner_radius: Corners::new_all_same(LogicalLength::new(radius)),
+        ..Default::default()
+    }
+}
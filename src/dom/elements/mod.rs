@@ -69,6 +69,15 @@ macro_rules! element_handlers {
 
 pub mod app;
 pub mod button;
+pub mod checkbox;
+pub(crate) mod control;
+pub mod overlay;
+pub mod radio;
+pub mod select;
+pub mod slider;
 pub mod span;
+pub mod switch;
+pub mod text_area;
+pub mod tooltip;
 pub mod view;
 pub mod window;
@@ -0,0 +1,104 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::Element;
+use crate::dom::elements::view::ViewChild;
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrModal, AttrStyle, ClassList,
+};
+use crate::style::{
+    Alignment, BlockValues, ComputedValues, DisplayType, InlineStyle, Style, StyleList,
+};
+use crate::Color;
+
+/// Corresponds to <overlay>. Its children are shown in the window's top
+/// layer, above everything else and laid out at the window's size, from
+/// wherever it is in the tree. This is how dialogs and toasts are shown
+/// without restructuring the window.
+///
+/// A `modal` overlay is a dialog. Its background is a backdrop that
+/// catches clicks meant for the rest of the window, and focus is kept
+/// inside it while it's shown. Focus moves into it when it's added, and
+/// back to where it was when it's removed.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Overlay {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    modal: bool,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    Overlay {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        modal: AttrModal,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+impl Element for Overlay {
+    type Child = ViewChild;
+    type Handlers = ();
+
+    const ELEMENT_NAME: &'static str = "overlay";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        ComputedValues {
+            display: DisplayType::Block(BlockValues {
+                align_items: Alignment::Center,
+                ..Default::default()
+            }),
+            background_color: if self.modal {
+                Color::new(0, 0, 0, 96)
+            } else {
+                Color::new(0, 0, 0, 0)
+            },
+            ..Default::default()
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        if self.modal {
+            Role::Dialog
+        } else {
+            Role::Group
+        }
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn top_layer(&self) -> bool {
+        true
+    }
+
+    fn modal(&self) -> bool {
+        self.modal
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("modal", self.modal.to_string())];
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
@@ -0,0 +1,125 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementState, ElementStates, HasEvent, NoChildren};
+use crate::dom::elements::control::{accent_color, control_values, process_pressable};
+use crate::dom::input::InputEvent;
+use crate::dom::{
+    AttrChecked, AttrClass, AttrDescription, AttrGroup, AttrInlineStyle, AttrLabel, AttrStyle,
+    ChangeEvent, ClassList,
+};
+use crate::style::{ComputedValues, Edges, InlineStyle, Style, StyleList};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// Corresponds to <radio>. Radios with the same `group` in a window act
+/// as one control: Tab focuses only the checked one, and the arrow keys
+/// move between them, selecting each in turn. Selecting an unchecked
+/// radio fires `on_change` with `true`, and the app is expected to
+/// uncheck the others.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Radio {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    checked: bool,
+    group: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    Radio {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        checked: AttrChecked,
+        group: AttrGroup,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    RadioHandlers for Radio {
+        on_change: ChangeEvent<bool>,
+    }
+}
+
+impl Element for Radio {
+    type Child = NoChildren;
+    type Handlers = RadioHandlers;
+
+    const ELEMENT_NAME: &'static str = "radio";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        if self.checked {
+            let border = Edges::new_all_same(5.0);
+            control_values(16.0, 16.0, 8.0, border, accent_color(), Color::white())
+        } else {
+            let border = Edges::new_all_same(1.0);
+            let gray = Color::new(120, 120, 120, 255);
+            control_values(16.0, 16.0, 8.0, border, gray, Color::white())
+        }
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::RadioButton
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn states(&self) -> ElementStates {
+        if self.checked {
+            ElementState::Checked.into()
+        } else {
+            ElementStates::empty()
+        }
+    }
+
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("checked", self.checked.to_string())];
+        if let Some(ref group) = self.group {
+            attributes.push(("group", format!("{:?}", group)));
+        }
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        process_pressable(states, event, || {
+            if !self.checked {
+                handlers.on_change.invoke(&ChangeEvent { value: true })
+            }
+        })
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementState, ElementStates, HasEvent};
+use crate::dom::elements::control::process_pressable;
+use crate::dom::input::{InputEvent, State};
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, AttrValue, ChangeEvent,
+    ClassList, Node,
+};
+use crate::layout::{LogicalLength, LogicalSideOffsets};
+use crate::style::{
+    BlockValues, Border, BorderStyle, ComputedValues, Corners, DisplayType, Edges, InlineStyle,
+    Style, StyleList,
+};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// Corresponds to <select>. Shows the `<option>` whose value is `value`,
+/// or the first one, and clicking it opens a popup listing all of them
+/// above the rest of the window. Choosing one, by clicking it or with
+/// the arrow keys, fires `on_change` with its value. It's in the `open`
+/// state while the popup is shown.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Select {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    value: String,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    Select {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        value: AttrValue<String>,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    SelectHandlers for Select {
+        on_change: ChangeEvent<String>,
+    }
+}
+
+impl Element for Select {
+    type Child = Node<SelectOption>;
+    type Handlers = SelectHandlers;
+
+    const ELEMENT_NAME: &'static str = "select";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        ComputedValues {
+            display: DisplayType::InlineBlock(BlockValues {
+                margin: LogicalSideOffsets::new(2.0, 4.0, 2.0, 0.0),
+                ..Default::default()
+            }),
+            background_color: Color::white(),
+            border: Edges::new_all_same(Border {
+                width: LogicalLength::new(1.0),
+                style: BorderStyle::Solid,
+                color: Color::new(120, 120, 120, 255),
+            }),
+            corner_radius: Corners::new_all_same(LogicalLength::new(3.0)),
+            ..Default::default()
+        }
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn has_popup(&self) -> bool {
+        true
+    }
+
+    fn value(&self) -> Option<&str> {
+        Some(&self.value)
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("value", format!("{:?}", self.value))];
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        match event {
+            InputEvent::Chosen { value } => {
+                if *value != self.value {
+                    handlers.on_change.invoke(&ChangeEvent {
+                        value: value.clone(),
+                    });
+                }
+                (true, states)
+            }
+            InputEvent::Dismissed | InputEvent::Focused { state: State::End } => {
+                let (_, states) = process_pressable(states, event, || ());
+                (true, states.difference(ElementState::Open.into()))
+            }
+            _ => {
+                let mut toggled = false;
+                let (sink, states) = process_pressable(states, event, || toggled = true);
+                if !toggled {
+                    (sink, states)
+                } else if states.contains(ElementState::Open) {
+                    (sink, states.difference(ElementState::Open.into()))
+                } else {
+                    (sink, states | ElementState::Open)
+                }
+            }
+        }
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
+
+/// Corresponds to <option>, one of the choices of a `<select>`. It's
+/// shown with the text inside it, and chosen by its `value`.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct SelectOption {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    value: String,
+}
+
+element_attributes! {
+    SelectOption {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        value: AttrValue<String>,
+    }
+}
+
+impl Element for SelectOption {
+    type Child = String;
+    type Handlers = ();
+
+    const ELEMENT_NAME: &'static str = "option";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        ComputedValues {
+            display: DisplayType::Block(BlockValues {
+                padding: LogicalSideOffsets::new(4.0, 8.0, 4.0, 8.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ListOption
+    }
+
+    fn value(&self) -> Option<&str> {
+        Some(&self.value)
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("value", format!("{:?}", self.value))]
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        _handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        process_pressable(states, event, || ())
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
@@ -0,0 +1,374 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementStates, HasEvent, NoChildren, RangeValue};
+use crate::dom::elements::control::{accent_color, process_pressable};
+use crate::dom::input::{InputEvent, Key};
+use crate::dom::{
+    AttrClass, AttrDescription, AttrFillStyle, AttrInlineStyle, AttrLabel, AttrMax, AttrMin,
+    AttrStep, AttrStyle, AttrThumbStyle, AttrTrackStyle, AttrValue, ChangeEvent, ClassList, Node,
+};
+use crate::layout::{LogicalLength, LogicalSideOffsets};
+use crate::mox_impl::builder::Builder;
+use crate::style::{
+    BlockValues, Border, BorderStyle, ComputedValues, Corners, DisplayType, Edges, InlineStyle,
+    Style, StyleList,
+};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// How many keyboard steps it takes to go from `min` to `max` when the
+/// slider doesn't have a `step`.
+const DEFAULT_KEY_STEPS: f64 = 10.0;
+
+/// Corresponds to <slider>. Picks a number from `min` to `max`, which
+/// are 0 and 1 unless set, by dragging its thumb or with the arrow keys.
+/// Values are rounded to multiples of `step` when it's set. Changes fire
+/// `on_change` with the new value.
+///
+/// It's built with three `<slider_part>` children, with the classes
+/// `track`, `fill` and `thumb`, styled by `track_style`, `fill_style`
+/// and `thumb_style`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slider {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+    track_style: StyleList,
+    fill_style: StyleList,
+    thumb_style: StyleList,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+impl Default for Slider {
+    fn default() -> Self {
+        Slider {
+            style: StyleList::new(),
+            class: ClassList::new(),
+            inline_style: InlineStyle::new(),
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            step: 0.0,
+            track_style: StyleList::new(),
+            fill_style: StyleList::new(),
+            thumb_style: StyleList::new(),
+            label: None,
+            description: None,
+        }
+    }
+}
+
+element_attributes! {
+    Slider {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        value: AttrValue<f64>,
+        min: AttrMin,
+        max: AttrMax,
+        step: AttrStep,
+        track_style: AttrTrackStyle,
+        fill_style: AttrFillStyle,
+        thumb_style: AttrThumbStyle,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    SliderHandlers for Slider {
+        on_change: ChangeEvent<f64>,
+    }
+}
+
+impl Slider {
+    fn range_value(&self) -> RangeValue {
+        RangeValue {
+            value: self.value,
+            min: self.min,
+            max: self.max,
+            step: self.step,
+        }
+    }
+
+    /// The value `key` moves the slider to.
+    fn step_by_key(&self, key: Key) -> f64 {
+        let range = self.range_value();
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / DEFAULT_KEY_STEPS
+        };
+        match key {
+            Key::Left | Key::Down => range.snap(self.value - step),
+            Key::Right | Key::Up => range.snap(self.value + step),
+            Key::PageDown => range.snap(self.value - step * DEFAULT_KEY_STEPS),
+            Key::PageUp => range.snap(self.value + step * DEFAULT_KEY_STEPS),
+            Key::Home => self.min,
+            Key::End => self.max,
+            Key::Escape | Key::Backspace | Key::Delete | Key::Undo | Key::Redo | Key::SelectAll => {
+                self.value
+            }
+        }
+    }
+
+    fn change(&self, handlers: &SliderHandlers, value: f64) {
+        if value != self.value {
+            handlers.on_change.invoke(&ChangeEvent { value });
+        }
+    }
+}
+
+impl Element for Slider {
+    type Child = Node<SliderPart>;
+    type Handlers = SliderHandlers;
+
+    const ELEMENT_NAME: &'static str = "slider";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        ComputedValues {
+            display: DisplayType::InlineBlock(BlockValues {
+                width: Some(LogicalLength::new(160.0)),
+                height: Some(LogicalLength::new(20.0)),
+                margin: LogicalSideOffsets::new(2.0, 4.0, 2.0, 0.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn default_children(&self) -> Vec<Node<SliderPart>> {
+        vec![
+            Builder::with_element(SliderPart::new(Part::Track, &self.track_style)).build(),
+            Builder::with_element(SliderPart::new(Part::Fill, &self.fill_style)).build(),
+            Builder::with_element(SliderPart::new(Part::Thumb, &self.thumb_style)).build(),
+        ]
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn range(&self) -> Option<RangeValue> {
+        Some(self.range_value())
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![
+            ("value", self.value.to_string()),
+            ("min", self.min.to_string()),
+            ("max", self.max.to_string()),
+        ];
+        if self.step > 0.0 {
+            attributes.push(("step", self.step.to_string()));
+        }
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        match event {
+            InputEvent::PointerMoved { position } => {
+                if position.width > 0.0 {
+                    let fraction = (position.x / position.width) as f64;
+                    self.change(handlers, self.range_value().at_fraction(fraction));
+                }
+                (true, states)
+            }
+            InputEvent::KeyPressed { key, .. } => {
+                self.change(handlers, self.step_by_key(*key));
+                (true, states)
+            }
+            _ => process_pressable(states, event, || ()),
+        }
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Part {
+    /// Stretched across the slider.
+    Track,
+    /// Covers the track up to the value.
+    Fill,
+    /// Placed at the value.
+    Thumb,
+}
+
+/// Corresponds to <slider_part>, one of the parts a slider is built
+/// with. Its class is the name of the part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliderPart {
+    part: Part,
+    style: StyleList,
+    class: ClassList,
+}
+
+impl Default for SliderPart {
+    fn default() -> Self {
+        SliderPart::new(Part::Track, &StyleList::new())
+    }
+}
+
+impl SliderPart {
+    fn new(part: Part, style: &StyleList) -> SliderPart {
+        let class = match part {
+            Part::Track => "track",
+            Part::Fill => "fill",
+            Part::Thumb => "thumb",
+        };
+        SliderPart {
+            part,
+            style: style.clone(),
+            class: class.into(),
+        }
+    }
+
+    pub fn part(&self) -> Part {
+        self.part
+    }
+}
+
+impl Element for SliderPart {
+    type Child = NoChildren;
+    type Handlers = ();
+
+    const ELEMENT_NAME: &'static str = "slider_part";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        let (size, radius, background_color, border) = match self.part {
+            Part::Track => (4.0, 2.0, Color::new(200, 200, 200, 255), 0.0),
+            Part::Fill => (4.0, 2.0, accent_color(), 0.0),
+            Part::Thumb => (16.0, 8.0, Color::white(), 1.0),
+        };
+        ComputedValues {
+            display: DisplayType::Block(BlockValues {
+                width: Some(LogicalLength::new(size)),
+                height: Some(LogicalLength::new(size)),
+                ..Default::default()
+            }),
+            background_color,
+            border: Edges::new_all_same(Border {
+                width: LogicalLength::new(border),
+                style: BorderStyle::Solid,
+                color: Color::new(120, 120, 120, 255),
+            }),
+            corner_radius: Corners::new_all_same(LogicalLength::new(radius)),
+            ..Default::default()
+        }
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::input::PointerPosition;
+    use std::sync::{Arc, Mutex};
+
+    fn slider(value: f64, min: f64, max: f64, step: f64) -> Slider {
+        Slider {
+            value,
+            min,
+            max,
+            step,
+            ..Slider::default()
+        }
+    }
+
+    #[test]
+    fn steps_a_tenth_of_the_range_without_a_step() {
+        let slider = slider(2.0, 0.0, 10.0, 0.0);
+        assert_eq!(slider.step_by_key(Key::Right), 3.0);
+        assert_eq!(slider.step_by_key(Key::Up), 3.0);
+        assert_eq!(slider.step_by_key(Key::Left), 1.0);
+        assert_eq!(slider.step_by_key(Key::Down), 1.0);
+        assert_eq!(slider.step_by_key(Key::PageUp), 10.0);
+        assert_eq!(slider.step_by_key(Key::PageDown), 0.0);
+        assert_eq!(slider.step_by_key(Key::Escape), 2.0);
+    }
+
+    #[test]
+    fn steps_by_its_step() {
+        let slider = slider(50.0, 0.0, 200.0, 5.0);
+        assert_eq!(slider.step_by_key(Key::Right), 55.0);
+        assert_eq!(slider.step_by_key(Key::Down), 45.0);
+        assert_eq!(slider.step_by_key(Key::PageUp), 100.0);
+        assert_eq!(slider.step_by_key(Key::PageDown), 0.0);
+        assert_eq!(slider.step_by_key(Key::Home), 0.0);
+        assert_eq!(slider.step_by_key(Key::End), 200.0);
+    }
+
+    #[test]
+    fn stops_stepping_at_the_ends() {
+        let slider = slider(1.0, 0.0, 1.0, 0.25);
+        assert_eq!(slider.step_by_key(Key::Right), 1.0);
+        assert_eq!(slider.step_by_key(Key::PageDown), 0.0);
+    }
+
+    #[test]
+    fn drags_to_the_value_under_the_pointer() {
+        let slider = slider(0.0, 0.0, 100.0, 10.0);
+        let changes = Arc::new(Mutex::new(vec![]));
+        let sink = changes.clone();
+        let mut handlers = SliderHandlers::default();
+        Slider::set_handler(
+            &mut handlers,
+            EventHandler::with_func(move |event: &ChangeEvent<f64>| {
+                sink.lock().unwrap().push(event.value)
+            }),
+        );
+
+        for &(x, width) in &[(40.0, 120.0), (-10.0, 120.0), (200.0, 120.0), (10.0, 0.0)] {
+            let position = PointerPosition {
+                x,
+                y: 0.0,
+                width,
+                height: 20.0,
+            };
+            let event = InputEvent::PointerMoved { position };
+            let (handled, _) = slider.process(ElementStates::empty(), &mut handlers, &event);
+            assert!(handled);
+        }
+        assert_eq!(*changes.lock().unwrap(), vec![30.0, 100.0]);
+    }
+}
@@ -1,17 +1,31 @@
+use crate::dom::accessibility::{describe_attributes, Role};
 use crate::dom::element::Element;
-use crate::dom::{AttrStyle, Button, Node, View};
-use crate::style::{ComputedValues, DisplayType, InlineValues, Style};
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, AttrTitle, Button, Checkbox,
+    ClassList, Node, Radio, Select, Slider, Switch, TextArea, View,
+};
+use crate::style::{ComputedValues, DisplayType, InlineStyle, InlineValues, Style, StyleList};
 
 /// Corresponds to <span>. This element is typically used for inline
 /// layout of text.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct Span {
-    style: Option<Style>,
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    title: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
 }
 
 element_attributes! {
     Span {
         style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        title: AttrTitle,
+        label: AttrLabel,
+        description: AttrDescription,
     }
 }
 
@@ -21,6 +35,12 @@ multiple_children! {
         Button(Node<Button>),
         View(Node<View>),
         Span(Node<Span>),
+        Checkbox(Node<Checkbox>),
+        Radio(Node<Radio>),
+        Switch(Node<Switch>),
+        Slider(Node<Slider>),
+        Select(Node<Select>),
+        TextArea(Node<TextArea>),
     }
 }
 
@@ -37,7 +57,39 @@ impl Element for Span {
         }
     }
 
-    fn style(&self) -> Option<Style> {
-        self.style
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Text
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = describe_attributes(&self.label, &self.description);
+        if let Some(ref title) = self.title {
+            attributes.push(("title", format!("{:?}", title)));
+        }
+        attributes
     }
 }
@@ -0,0 +1,118 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementState, ElementStates, HasEvent, NoChildren};
+use crate::dom::elements::control::{accent_color, control_values, process_pressable};
+use crate::dom::input::InputEvent;
+use crate::dom::{
+    AttrChecked, AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, ChangeEvent,
+    ClassList,
+};
+use crate::style::{ComputedValues, Edges, InlineStyle, Style, StyleList};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// Corresponds to <switch>. Behaves like a checkbox, but looks like a
+/// toggle switch, with the thumb drawn as a wide border on the side it
+/// has been switched to.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Switch {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    checked: bool,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    Switch {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        checked: AttrChecked,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    SwitchHandlers for Switch {
+        on_change: ChangeEvent<bool>,
+    }
+}
+
+impl Element for Switch {
+    type Child = NoChildren;
+    type Handlers = SwitchHandlers;
+
+    const ELEMENT_NAME: &'static str = "switch";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        let (thumb, track) = if self.checked {
+            ((2.0, 16.0), accent_color())
+        } else {
+            ((16.0, 2.0), Color::new(190, 190, 190, 255))
+        };
+        let border = Edges {
+            left: thumb.0,
+            right: thumb.1,
+            top: 2.0,
+            bottom: 2.0,
+        };
+        control_values(34.0, 18.0, 9.0, border, Color::white(), track)
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Switch
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn states(&self) -> ElementStates {
+        if self.checked {
+            ElementState::Checked.into()
+        } else {
+            ElementStates::empty()
+        }
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("checked", self.checked.to_string())];
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        process_pressable(states, event, || {
+            handlers.on_change.invoke(&ChangeEvent {
+                value: !self.checked,
+            })
+        })
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
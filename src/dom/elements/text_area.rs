@@ -0,0 +1,133 @@
+use crate::dom::accessibility::{describe_attributes, Role};
+use crate::dom::element::{Element, ElementStates, HasEvent, NoChildren};
+use crate::dom::elements::control::process_pressable;
+use crate::dom::input::InputEvent;
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, AttrValue, ChangeEvent,
+    ClassList,
+};
+use crate::layout::{LogicalLength, LogicalSideOffsets};
+use crate::style::{
+    BlockValues, Border, BorderStyle, ComputedValues, Corners, DisplayType, Edges, InlineStyle,
+    Style, StyleList,
+};
+use crate::util::event_handler::EventHandler;
+use crate::Color;
+
+/// Corresponds to <textarea>. Edits multiple lines of text, which wrap
+/// to its width and scroll to keep the caret in view. The caret moves
+/// with the arrow keys by visual line, text is selected by dragging or
+/// with shift held, and edits can be undone. Edits fire `on_change` with
+/// the new text, which should be passed back as `value`.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct TextArea {
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    value: String,
+    label: Option<String>,
+    description: Option<String>,
+}
+
+element_attributes! {
+    TextArea {
+        style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        value: AttrValue<String>,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+element_handlers! {
+    TextAreaHandlers for TextArea {
+        on_change: ChangeEvent<String>,
+    }
+}
+
+impl Element for TextArea {
+    type Child = NoChildren;
+    type Handlers = TextAreaHandlers;
+
+    const ELEMENT_NAME: &'static str = "textarea";
+
+    fn create_computed_values(&self) -> ComputedValues {
+        ComputedValues {
+            display: DisplayType::InlineBlock(BlockValues {
+                width: Some(LogicalLength::new(240.0)),
+                height: Some(LogicalLength::new(80.0)),
+                padding: LogicalSideOffsets::new(4.0, 4.0, 4.0, 4.0),
+                margin: LogicalSideOffsets::new(2.0, 4.0, 2.0, 0.0),
+                ..Default::default()
+            }),
+            background_color: Color::white(),
+            border: Edges::new_all_same(Border {
+                width: LogicalLength::new(1.0),
+                style: BorderStyle::Solid,
+                color: Color::new(120, 120, 120, 255),
+            }),
+            corner_radius: Corners::new_all_same(LogicalLength::new(3.0)),
+            ..Default::default()
+        }
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TextArea
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn value(&self) -> Option<&str> {
+        Some(&self.value)
+    }
+
+    fn editable(&self) -> bool {
+        true
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = vec![("value", format!("{:?}", self.value))];
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
+    }
+
+    fn process(
+        &self,
+        states: ElementStates,
+        handlers: &mut Self::Handlers,
+        event: &InputEvent,
+    ) -> (bool, ElementStates) {
+        match event {
+            InputEvent::Edited { value } => {
+                handlers.on_change.invoke(&ChangeEvent {
+                    value: value.clone(),
+                });
+                (true, states)
+            }
+            _ => process_pressable(states, event, || ()),
+        }
+    }
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::dom::element::Element;
+use crate::dom::ClassList;
+use crate::style::{Attributes, Style, StyleData, StyleList};
+use crate::style_impl::{apply, attribute, func, pixels};
+
+/// How tooltips look unless the window's `tooltip_style` says otherwise.
+static TOOLTIP_STYLE: StyleData = StyleData {
+    attributes: Attributes {
+        variables: |_| {},
+        apply: |values| {
+            apply(
+                values,
+                attribute::background_color(),
+                func::rgba(51.0, 51.0, 51.0, 242.0),
+            );
+            apply(
+                values,
+                attribute::text_color(),
+                func::rgb(255.0, 255.0, 255.0),
+            );
+            apply(values, attribute::text_size(), pixels(13.0));
+            apply(values, attribute::padding(), pixels(5.0));
+            apply(values, attribute::corner_radius(), pixels(3.0));
+        },
+        get_attributes: || {
+            vec![
+                ("background_color", "rgba(51, 51, 51, 242)".to_owned()),
+                ("text_color", "rgb(255, 255, 255)".to_owned()),
+                ("text_size", "13 px".to_owned()),
+                ("padding", "5 px".to_owned()),
+                ("corner_radius", "3 px".to_owned()),
+            ]
+        },
+    },
+    sub_styles: &[],
+    name: "TOOLTIP_STYLE",
+    file: file!(),
+    line: line!(),
+};
+
+/// Corresponds to <tooltip>, the box the shown tooltip's text is drawn
+/// in. It isn't part of the tree, and is styled as a child of the window
+/// by the window's `tooltip_style`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tooltip {
+    style: StyleList,
+    class: ClassList,
+}
+
+impl Default for Tooltip {
+    fn default() -> Self {
+        Tooltip::new(&StyleList::new())
+    }
+}
+
+impl Tooltip {
+    pub(crate) fn new(style: &StyleList) -> Tooltip {
+        let mut styles = vec![Style::new(&TOOLTIP_STYLE)];
+        styles.extend_from_slice(style.as_slice());
+        Tooltip {
+            style: styles.into(),
+            class: ClassList::new(),
+        }
+    }
+}
+
+impl Element for Tooltip {
+    type Child = String;
+    type Handlers = ();
+
+    const ELEMENT_NAME: &'static str = "tooltip";
+
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+}
@@ -1,12 +1,21 @@
+use crate::dom::accessibility::describe_attributes;
 use crate::dom::element::Element;
-use crate::dom::{AttrStyle, Button, Node, Span};
-use crate::style::{ComputedValues, Style};
+use crate::dom::{
+    AttrClass, AttrDescription, AttrInlineStyle, AttrLabel, AttrStyle, AttrTitle, Button, Checkbox,
+    ClassList, Node, Overlay, Radio, Select, Slider, Span, Switch, TextArea,
+};
+use crate::style::{ComputedValues, InlineStyle, Style, StyleList};
 use crate::Color;
 
 /// Corresponds to <view>. Generic frame for layout purposes.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct View {
-    style: Option<Style>,
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
+    title: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
 }
 
 multiple_children! {
@@ -14,12 +23,24 @@ multiple_children! {
         Button(Node<Button>),
         View(Node<View>),
         Span(Node<Span>),
+        Checkbox(Node<Checkbox>),
+        Radio(Node<Radio>),
+        Switch(Node<Switch>),
+        Slider(Node<Slider>),
+        Select(Node<Select>),
+        TextArea(Node<TextArea>),
+        Overlay(Node<Overlay>),
     }
 }
 
 element_attributes! {
     View {
         style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
+        title: AttrTitle,
+        label: AttrLabel,
+        description: AttrDescription,
     }
 }
 
@@ -36,7 +57,35 @@ impl Element for View {
         }
     }
 
-    fn style(&self) -> Option<Style> {
-        self.style
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = describe_attributes(&self.label, &self.description);
+        if let Some(ref title) = self.title {
+            attributes.push(("title", format!("{:?}", title)));
+        }
+        attributes
     }
 }
@@ -1,24 +1,76 @@
+use crate::dom::accessibility::{describe_attributes, Role};
 use crate::dom::element::{Element, ElementStates, HasEvent};
-use crate::dom::events::CloseRequestedEvent;
+use crate::dom::events::{
+    CloseRequestedEvent, FocusChangeEvent, MoveEvent, ResizeEvent, ScaleFactorChangeEvent,
+    ThemeChangeEvent,
+};
 use crate::dom::input::InputEvent;
-use crate::dom::{AttrStyle, AttrTitle, Node, View};
-use crate::style::Style;
+use crate::dom::{
+    AttrAlwaysOnTop, AttrBackground, AttrClass, AttrDecorations, AttrDescription, AttrFullscreen,
+    AttrIcon, AttrInlineStyle, AttrLabel, AttrMaxSize, AttrMaximized, AttrMinSize, AttrPosition,
+    AttrResizable, AttrSize, AttrStyle, AttrTitle, AttrTooltipStyle, ClassList, Icon, Node, View,
+};
+use crate::style::{InlineStyle, Style, StyleList};
 use crate::util::event_handler::EventHandler;
-use crate::Runtime;
+use crate::{Color, Runtime};
 
 /// Corresponds to <window>. This is the top-level container for UI and
 /// corresponds to an OS window.
+///
+/// Tooltips shown in it are drawn in a `<tooltip>` that's styled as a
+/// child of the window, by `tooltip_style` on top of a default style.
+///
+/// The rest of its attributes are properties of the OS window. Sizes are
+/// `(width, height)` and the position is `(x, y)`, in logical pixels, and
+/// the window is placed by the OS without a `position`. These are applied
+/// when the window is created, and again whenever they change, so a
+/// window the user has resized keeps its size until `size` is changed.
+/// `background` is what's shown where nothing is painted, and a
+/// transparent one makes the window see-through on platforms that allow
+/// it.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Window {
-    style: Option<Style>,
+    style: StyleList,
+    class: ClassList,
+    inline_style: InlineStyle,
     pub title: String,
+    tooltip_style: StyleList,
+    pub size: Option<(f32, f32)>,
+    pub min_size: Option<(f32, f32)>,
+    pub max_size: Option<(f32, f32)>,
+    pub position: Option<(f32, f32)>,
+    pub resizable: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    pub always_on_top: bool,
+    pub decorations: bool,
+    pub icon: Option<Icon>,
+    pub background: Color,
+    label: Option<String>,
+    description: Option<String>,
 }
 
 impl Default for Window {
     fn default() -> Self {
         Window {
-            style: None,
+            style: StyleList::new(),
+            class: ClassList::new(),
+            inline_style: InlineStyle::new(),
             title: "Untitled Window".to_owned(),
+            tooltip_style: StyleList::new(),
+            size: None,
+            min_size: None,
+            max_size: None,
+            position: None,
+            resizable: true,
+            maximized: false,
+            fullscreen: false,
+            always_on_top: false,
+            decorations: true,
+            icon: None,
+            background: Color::white(),
+            label: None,
+            description: None,
         }
     }
 }
@@ -26,7 +78,29 @@ impl Default for Window {
 element_attributes! {
     Window {
         style: AttrStyle,
+        class: AttrClass,
+        inline_style: AttrInlineStyle,
         title: AttrTitle,
+        tooltip_style: AttrTooltipStyle,
+        size: AttrSize,
+        min_size: AttrMinSize,
+        max_size: AttrMaxSize,
+        position: AttrPosition,
+        resizable: AttrResizable,
+        maximized: AttrMaximized,
+        fullscreen: AttrFullscreen,
+        always_on_top: AttrAlwaysOnTop,
+        decorations: AttrDecorations,
+        icon: AttrIcon,
+        background: AttrBackground,
+        label: AttrLabel,
+        description: AttrDescription,
+    }
+}
+
+impl Window {
+    pub(crate) fn tooltip_style(&self) -> &StyleList {
+        &self.tooltip_style
     }
 }
 
@@ -37,6 +111,13 @@ element_handlers! {
         /// `Runtime::shutdown()` which will stop the event loop and
         /// cause the application to exit.
         on_close: CloseRequestedEvent,
+        /// Handle the window being resized, such as to remember its size
+        /// for the next time the app runs.
+        on_resize: ResizeEvent,
+        on_focus_change: FocusChangeEvent,
+        on_move: MoveEvent,
+        on_scale_factor_change: ScaleFactorChangeEvent,
+        on_theme_change: ThemeChangeEvent,
     }
 }
 
@@ -46,12 +127,60 @@ impl Element for Window {
 
     const ELEMENT_NAME: &'static str = "window";
 
-    fn style(&self) -> Option<Style> {
-        self.style
+    fn styles(&self) -> &[Style] {
+        self.style.as_slice()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.class.as_slice()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        Some(&self.inline_style)
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Window
+    }
+
+    /// Windows are named after their title unless they have a `label`.
+    fn accessible_name(&self) -> Option<String> {
+        Some(self.label.clone().unwrap_or_else(|| self.title.clone()))
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.description.clone()
     }
 
     fn attributes(&self) -> Vec<(&'static str, String)> {
-        vec![("title", format!("{:?}", self.title))]
+        let mut attributes = vec![("title", format!("{:?}", self.title))];
+        let sizes = [
+            ("size", self.size),
+            ("min_size", self.min_size),
+            ("max_size", self.max_size),
+        ];
+        for (name, size) in sizes {
+            if let Some((width, height)) = size {
+                attributes.push((name, format!("{}x{}", width, height)));
+            }
+        }
+        if let Some((x, y)) = self.position {
+            attributes.push(("position", format!("{},{}", x, y)));
+        }
+        let flags = [
+            ("resizable", self.resizable),
+            ("maximized", self.maximized),
+            ("fullscreen", self.fullscreen),
+            ("always_on_top", self.always_on_top),
+            ("decorations", self.decorations),
+        ];
+        attributes.extend(flags.iter().map(|&(name, flag)| (name, flag.to_string())));
+        if let Some(icon) = &self.icon {
+            attributes.push(("icon", format!("{:?}", icon)));
+        }
+        attributes.push(("background", self.background.to_string()));
+        attributes.extend(describe_attributes(&self.label, &self.description));
+        attributes
     }
 
     fn process(
@@ -69,6 +198,37 @@ impl Element for Window {
                 }
                 (true, states)
             }
+            InputEvent::Resized { width, height } => {
+                handlers.on_resize.invoke(&ResizeEvent {
+                    width: *width,
+                    height: *height,
+                });
+                (true, states)
+            }
+            InputEvent::WindowFocused { focused } => {
+                handlers
+                    .on_focus_change
+                    .invoke(&FocusChangeEvent { focused: *focused });
+                (true, states)
+            }
+            InputEvent::Moved { x, y } => {
+                handlers.on_move.invoke(&MoveEvent { x: *x, y: *y });
+                (true, states)
+            }
+            InputEvent::ScaleFactorChanged { scale_factor } => {
+                handlers
+                    .on_scale_factor_change
+                    .invoke(&ScaleFactorChangeEvent {
+                        scale_factor: *scale_factor,
+                    });
+                (true, states)
+            }
+            InputEvent::ThemeChanged { theme } => {
+                handlers
+                    .on_theme_change
+                    .invoke(&ThemeChangeEvent { theme: *theme });
+                (true, states)
+            }
             _ => (false, states),
         }
     }
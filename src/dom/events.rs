@@ -4,7 +4,58 @@ use super::element::Event;
 pub struct ClickEvent;
 impl Event for ClickEvent {}
 
+/// The user changed the value of the element, such as by checking a
+/// checkbox. `value` is the new value, which the app should pass back
+/// to the element for the change to show.
+pub struct ChangeEvent<Value> {
+    pub value: Value,
+}
+impl<Value: 'static> Event for ChangeEvent<Value> {}
+
 /// Fired when the user requests a window to be closed (such as by
 /// pressing the close button, or pressing alt+f4).
 pub struct CloseRequestedEvent {}
 impl Event for CloseRequestedEvent {}
+
+/// The window's content area was resized by the user or the OS. The size
+/// is in logical pixels. Some platforms resize minimized windows to zero.
+pub struct ResizeEvent {
+    pub width: f32,
+    pub height: f32,
+}
+impl Event for ResizeEvent {}
+
+/// The window gained or lost keyboard focus to another window or app.
+pub struct FocusChangeEvent {
+    pub focused: bool,
+}
+impl Event for FocusChangeEvent {}
+
+/// The window was moved. `x` and `y` are where its top left corner,
+/// including decorations, now is on the desktop, in logical pixels.
+pub struct MoveEvent {
+    pub x: f32,
+    pub y: f32,
+}
+impl Event for MoveEvent {}
+
+/// The number of physical pixels per logical pixel changed, such as when
+/// the window was moved to another monitor. Layout is in logical pixels,
+/// so this only matters for things like picking image resolutions.
+pub struct ScaleFactorChangeEvent {
+    pub scale_factor: f64,
+}
+impl Event for ScaleFactorChangeEvent {}
+
+/// Whether the OS prefers light or dark windows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// The OS switched between its light and dark themes.
+pub struct ThemeChangeEvent {
+    pub theme: Theme,
+}
+impl Event for ThemeChangeEvent {}
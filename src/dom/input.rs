@@ -1,10 +1,107 @@
+use super::events::Theme;
+
+#[derive(Clone, Debug)]
 pub enum InputEvent {
-    Hovered { state: State },
-    MouseLeft { state: State },
+    Hovered {
+        state: State,
+    },
+    /// The pointer has rested on the element long enough to show its
+    /// tooltip.
+    HoverIntent,
+    MouseLeft {
+        state: State,
+    },
+    /// Focus moved to or away from the element.
+    Focused {
+        state: State,
+    },
+    /// The element was activated from the keyboard or by assistive
+    /// technology, which should do what clicking it would.
+    Activated,
+    /// The pointer moved while the element has it captured, which it
+    /// does from when it's pressed until it's released, wherever the
+    /// pointer goes. Also sent right after the press.
+    PointerMoved {
+        position: PointerPosition,
+    },
+    /// A key was pressed while the element was focused, along with
+    /// whether shift was held.
+    KeyPressed {
+        key: Key,
+        shift: bool,
+    },
+    /// The element's text was edited, such as by typing into it.
+    Edited {
+        value: String,
+    },
+    /// An option was chosen for the element, such as by clicking it in
+    /// the element's popup.
+    Chosen {
+        value: String,
+    },
+    /// The element's popup should close, such as after a click outside
+    /// of it.
+    Dismissed,
     CloseRequested,
+    /// The window's content area was resized, in logical pixels.
+    Resized {
+        width: f32,
+        height: f32,
+    },
+    /// The window gained or lost keyboard focus.
+    WindowFocused {
+        focused: bool,
+    },
+    /// The window was moved, in logical pixels.
+    Moved {
+        x: f32,
+        y: f32,
+    },
+    ScaleFactorChanged {
+        scale_factor: f64,
+    },
+    ThemeChanged {
+        theme: Theme,
+    },
+}
+
+/// Where the pointer is relative to an element, along with the size of
+/// the element, in logical pixels. It can be outside of the element.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointerPosition {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Keys passed to the focused element. Shortcuts like the one for undo
+/// are passed as keys of their own.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Escape,
+    Backspace,
+    Delete,
+    Undo,
+    Redo,
+    SelectAll,
+}
+
+impl Key {
+    pub fn is_arrow(self) -> bool {
+        matches!(self, Key::Left | Key::Right | Key::Up | Key::Down)
+    }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum State {
     Begin,
     End,
@@ -14,6 +111,9 @@ pub enum State {
 
 impl InputEvent {
     pub fn get_position(&self) -> Option<(f32, f32)> {
-        None
+        match self {
+            InputEvent::PointerMoved { position } => Some((position.x, position.y)),
+            _ => None,
+        }
     }
 }
@@ -2,6 +2,7 @@
 //! moxie-native. It implements the DOM hierarchy which is used to
 //! represent the UI.
 
+pub mod accessibility;
 pub mod attributes;
 pub mod devtools;
 pub mod element;
@@ -11,6 +12,21 @@ pub mod input;
 pub mod node;
 
 pub use attributes::*;
-pub use elements::{app::App, button::Button, span::Span, view::View, window::Window};
+pub use elements::{
+    app::App, button::Button, checkbox::Checkbox, overlay::Overlay, radio::Radio, select::Select,
+    select::SelectOption, slider::Slider, slider::SliderPart, span::Span, switch::Switch,
+    text_area::TextArea, tooltip::Tooltip, view::View, window::Window,
+};
 pub use events::*;
 pub use node::Node;
+
+/// A window with ids 0 and 1 for itself and a view holding `children`,
+/// which tests build their DOMs in.
+#[cfg(test)]
+pub(crate) fn test_window(children: Vec<elements::view::ViewChild>) -> Node<Window> {
+    Node::new(
+        0,
+        Window::default(),
+        vec![Node::new(1, View::default(), children)],
+    )
+}
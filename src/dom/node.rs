@@ -1,6 +1,7 @@
-use crate::dom::element::{DynamicNode, Element, ElementStates, NodeChild};
+use crate::dom::accessibility::Role;
+use crate::dom::element::{DynamicNode, Element, ElementStates, NodeChild, RangeValue};
 use crate::dom::input::InputEvent;
-use crate::style::{ComputedValues, Style};
+use crate::style::{ComputedValues, InlineStyle, Style, StyleRule, Variables};
 use std::any::{type_name, TypeId};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::hash::{Hash, Hasher};
@@ -15,6 +16,7 @@ where
     element: Elt,
     handlers: Mutex<Elt::Handlers>,
     children: Vec<Elt::Child>,
+    variables: Option<Variables>,
 }
 
 impl<Elt> Debug for NodeData<Elt>
@@ -36,11 +38,17 @@ impl<Elt> NodeData<Elt>
 where
     Elt: Element,
 {
-    fn new(id: u64, element: Elt, children: Vec<Elt::Child>) -> NodeData<Elt> {
+    fn new(
+        id: u64,
+        element: Elt,
+        children: Vec<Elt::Child>,
+        variables: Option<Variables>,
+    ) -> NodeData<Elt> {
         NodeData {
             id,
             element,
             children,
+            variables,
             handlers: Mutex::new(Default::default()),
         }
     }
@@ -84,12 +92,36 @@ pub trait AnyNodeData: Debug {
     fn children(&self) -> NodeDataChildrenIter;
     fn process(&self, states: ElementStates, event: &InputEvent) -> ElementStates;
     fn create_computed_values(&self) -> ComputedValues;
-    fn style(&self) -> Option<Style>;
+    fn styles(&self) -> &[Style];
+    fn classes(&self) -> &[String];
+    fn inline_style(&self) -> Option<&InlineStyle>;
+    fn variables(&self) -> Option<&Variables>;
     fn type_id(&self) -> TypeId;
     fn attributes(&self) -> Vec<(&'static str, String)>;
+    /// Describes every rule of the node's styles, whether or not it
+    /// matches. `Document::node_style` gives only the ones that applied.
+    fn style_rules(&self) -> Vec<StyleRule> {
+        self.styles()
+            .iter()
+            .flat_map(|style| style.rules())
+            .collect()
+    }
     fn name(&self) -> &'static str;
     fn id(&self) -> u64;
     fn interactive(&self) -> bool;
+    fn focusable(&self) -> bool;
+    fn accessibility_role(&self) -> Role;
+    fn accessible_name(&self) -> Option<String>;
+    fn accessible_description(&self) -> Option<String>;
+    fn states(&self) -> ElementStates;
+    fn group(&self) -> Option<&str>;
+    fn range(&self) -> Option<RangeValue>;
+    fn has_popup(&self) -> bool;
+    fn value(&self) -> Option<&str>;
+    fn editable(&self) -> bool;
+    fn tooltip(&self) -> Option<&str>;
+    fn top_layer(&self) -> bool;
+    fn modal(&self) -> bool;
 }
 
 impl<Elt> AnyNodeData for NodeData<Elt>
@@ -117,8 +149,20 @@ where
         self.element.create_computed_values()
     }
 
-    fn style(&self) -> Option<Style> {
-        self.element.style()
+    fn styles(&self) -> &[Style] {
+        self.element.styles()
+    }
+
+    fn classes(&self) -> &[String] {
+        self.element.classes()
+    }
+
+    fn inline_style(&self) -> Option<&InlineStyle> {
+        self.element.inline_style()
+    }
+
+    fn variables(&self) -> Option<&Variables> {
+        self.variables.as_ref()
     }
 
     fn type_id(&self) -> TypeId {
@@ -140,6 +184,58 @@ where
     fn interactive(&self) -> bool {
         self.element.interactive()
     }
+
+    fn focusable(&self) -> bool {
+        self.element.focusable()
+    }
+
+    fn accessibility_role(&self) -> Role {
+        self.element.accessibility_role()
+    }
+
+    fn accessible_name(&self) -> Option<String> {
+        self.element.accessible_name()
+    }
+
+    fn accessible_description(&self) -> Option<String> {
+        self.element.accessible_description()
+    }
+
+    fn states(&self) -> ElementStates {
+        self.element.states()
+    }
+
+    fn group(&self) -> Option<&str> {
+        self.element.group()
+    }
+
+    fn range(&self) -> Option<RangeValue> {
+        self.element.range()
+    }
+
+    fn has_popup(&self) -> bool {
+        self.element.has_popup()
+    }
+
+    fn value(&self) -> Option<&str> {
+        self.element.value()
+    }
+
+    fn editable(&self) -> bool {
+        self.element.editable()
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.element.tooltip()
+    }
+
+    fn top_layer(&self) -> bool {
+        self.element.top_layer()
+    }
+
+    fn modal(&self) -> bool {
+        self.element.modal()
+    }
 }
 
 /// Typed handle to a DOM node.
@@ -152,7 +248,18 @@ where
 {
     /// Create a new DOM node from the given element and children vector.
     pub fn new(id: u64, element: Elt, children: Vec<Elt::Child>) -> Node<Elt> {
-        let data = Arc::new(NodeData::new(id, element, children));
+        Node::with_variables(id, element, children, None)
+    }
+
+    /// Create a new DOM node that also carries the style variables that
+    /// were offered in the environment it was built in.
+    pub fn with_variables(
+        id: u64,
+        element: Elt,
+        children: Vec<Elt::Child>,
+        variables: Option<Variables>,
+    ) -> Node<Elt> {
+        let data = Arc::new(NodeData::new(id, element, children, variables));
         Node(data)
     }
 }
@@ -254,6 +361,16 @@ impl<'a> NodeRef<'a> {
     pub fn to_owned(&self) -> AnyNode {
         self.0.to_owned()
     }
+
+    /// The child whose value is the node's, like a select's selected
+    /// option, or else the first child node.
+    pub fn selected_child(&self) -> Option<NodeRef<'a>> {
+        let node: &'a dyn AnyNodeData = self.0.node_data();
+        let children = || node.children().filter_map(|child| child.node());
+        children()
+            .find(|child| child.value().is_some() && child.value() == node.value())
+            .or_else(|| children().next())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1,7 +1,11 @@
-use super::{inline, LayoutChild, LayoutTreeNode, LogicalSize, RenderData};
+use super::{
+    inline, text_area, LayoutChild, LayoutTreeNode, LogicalLength, LogicalPoint,
+    LogicalSideOffsets, LogicalSize, RenderData,
+};
 use crate::document::DocumentState;
-use crate::dom::{element::DynamicNode, node::AnyNode, node::NodeRef};
-use crate::style::{BlockValues, ComputedValues, Direction, DisplayType};
+use crate::dom::element::{DynamicNode, RangeValue};
+use crate::dom::node::{AnyNode, NodeRef};
+use crate::style::{Alignment, BlockValues, ComputedValues, Direction, DisplayType};
 use crate::util::equal_rc::EqualRc;
 use euclid::{point2, size2, vec2};
 
@@ -16,46 +20,139 @@ fn calc_max_size(values: &BlockValues, parent_size: LogicalSize) -> LogicalSize
     outer - size2(values.padding.horizontal(), values.padding.vertical())
 }
 
+/// Offset of a child within the free space on the cross axis. Auto
+/// margins take priority over alignment.
+fn cross_offset(free: f32, start_auto: bool, end_auto: bool, alignment: Alignment) -> f32 {
+    match (start_auto, end_auto, alignment) {
+        (true, true, _) => free / 2.0,
+        (true, false, _) => free,
+        (false, true, _) => 0.0,
+        (false, false, Alignment::Start) => 0.0,
+        (false, false, Alignment::Center) => free / 2.0,
+        (false, false, Alignment::End) => free,
+    }
+}
+
+/// A copy of the layout with a different width.
+fn with_width(layout: &EqualRc<LayoutTreeNode>, width: f32) -> EqualRc<LayoutTreeNode> {
+    let node = match layout.render {
+        RenderData::Node(ref node) => node.clone(),
+        RenderData::Text { .. } | RenderData::Fill { .. } => return layout.clone(),
+    };
+    EqualRc::new(LayoutTreeNode {
+        size: size2(width, layout.size.height),
+        margin: layout.margin,
+        auto_margins: layout.auto_margins,
+        align_self: layout.align_self,
+        baseline: layout.baseline,
+        children: layout
+            .children
+            .iter()
+            .map(|child| LayoutChild {
+                position: child.position,
+                layout: child.layout.clone(),
+            })
+            .collect(),
+        render: RenderData::Node(node),
+    })
+}
+
+/// Lays out range elements like sliders, whose children are a track
+/// stretched across the element, a fill covering the track up to the
+/// value and a thumb placed at the value, all centered vertically.
+fn calc_range_layout(
+    input: &(ComputedValues, Vec<EqualRc<LayoutTreeNode>>, AnyNode),
+    range: RangeValue,
+) -> EqualRc<LayoutTreeNode> {
+    let (values, children, node) = input;
+
+    let block_values = *values.display.block().unwrap();
+    let inset = point2(
+        block_values.padding.left + values.border.left.width.get(),
+        block_values.padding.top + values.border.top.width.get(),
+    );
+    let padding = size2(
+        block_values.padding.horizontal(),
+        block_values.padding.vertical(),
+    );
+    let border = size2(
+        values.border.left.width.get() + values.border.right.width.get(),
+        values.border.top.width.get() + values.border.bottom.width.get(),
+    );
+
+    let content_height = children
+        .iter()
+        .map(|child| child.size.height)
+        .fold(0.0f32, f32::max);
+    let mut size = size2(0.0, content_height) + padding + border;
+    if let Some(width) = block_values.width {
+        size.width = width.get();
+    }
+    if let Some(height) = block_values.height {
+        size.height = height.get();
+    }
+    let inner = size - padding - border;
+
+    let fraction = range.fraction() as f32;
+    let child_positions = children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| {
+            let (x, layout) = match index {
+                0 => (0.0, with_width(child, inner.width)),
+                1 => (0.0, with_width(child, inner.width * fraction)),
+                _ => (
+                    (inner.width - child.size.width).max(0.0) * fraction,
+                    child.clone(),
+                ),
+            };
+            let y = (inner.height - layout.size.height) / 2.0;
+            LayoutChild {
+                position: inset + vec2(x, y),
+                layout,
+            }
+        })
+        .collect();
+
+    EqualRc::new(LayoutTreeNode {
+        size,
+        margin: block_values.margin,
+        auto_margins: block_values.margin_auto,
+        align_self: block_values.align_self,
+        baseline: None,
+        children: child_positions,
+        render: RenderData::Node(node.clone()),
+    })
+}
+
 fn calc_block_layout(
     input: &(ComputedValues, Vec<EqualRc<LayoutTreeNode>>, AnyNode),
 ) -> EqualRc<LayoutTreeNode> {
     let (values, children, node) = input;
+    if let Some(range) = node.range() {
+        return calc_range_layout(input, range);
+    }
 
-    let block_values = if let DisplayType::Block(block) = values.display {
-        block
-    } else {
-        panic!()
-    };
+    let block_values = *values.display.block().unwrap();
+    let vertical = block_values.direction == Direction::Vertical;
 
     let inset = point2(
         block_values.padding.left + values.border.left.width.get(),
         block_values.padding.top + values.border.top.width.get(),
     );
 
-    let mut width = 0.0f32;
-    let mut height = 0.0f32;
-    let mut child_positions = vec![];
+    let mut content = LogicalSize::zero();
     for child in children {
-        let child = child.clone();
         let size = child.size + size2(child.margin.horizontal(), child.margin.vertical());
-        if block_values.direction == Direction::Vertical {
-            width = width.max(size.width);
-            child_positions.push(LayoutChild {
-                position: inset + vec2(0.0, height),
-                layout: child,
-            });
-            height += size.height;
+        if vertical {
+            content.width = content.width.max(size.width);
+            content.height += size.height;
         } else {
-            height = height.max(size.height);
-            child_positions.push(LayoutChild {
-                position: inset + vec2(width, 0.0),
-                layout: child,
-            });
-            width += size.width;
+            content.height = content.height.max(size.height);
+            content.width += size.width;
         }
     }
 
-    let size = size2(width, height);
     let padding = size2(
         block_values.padding.horizontal(),
         block_values.padding.vertical(),
@@ -65,7 +162,7 @@ fn calc_block_layout(
         values.border.top.width.get() + values.border.bottom.width.get(),
     );
 
-    let mut size = size + padding + border;
+    let mut size = content + padding + border;
 
     if let Some(width) = block_values.width {
         size.width = width.get();
@@ -74,11 +171,82 @@ fn calc_block_layout(
         size.height = height.get();
     }
 
-    let margin = block_values.margin;
+    // The space available to children, which is larger than the
+    // content when the size is fixed.
+    let inner = size - padding - border;
+
+    // Free space on the main axis is shared equally between all of the
+    // auto margins along it.
+    let auto_count = children
+        .iter()
+        .map(|child| {
+            let auto = child.auto_margins;
+            if vertical {
+                auto.top as usize + auto.bottom as usize
+            } else {
+                auto.left as usize + auto.right as usize
+            }
+        })
+        .sum::<usize>();
+    let auto_space = if auto_count > 0 {
+        let free = if vertical {
+            inner.height - content.height
+        } else {
+            inner.width - content.width
+        };
+        free.max(0.0) / auto_count as f32
+    } else {
+        0.0
+    };
+
+    let mut offset = 0.0f32;
+    let mut baseline = None;
+    let mut child_positions = vec![];
+    for child in children {
+        let child = child.clone();
+        let auto = child.auto_margins;
+        let alignment = child.align_self.unwrap_or(block_values.align_items);
+        let mut margin = child.margin;
+        let position = if vertical {
+            if auto.top {
+                margin.top += auto_space;
+            }
+            if auto.bottom {
+                margin.bottom += auto_space;
+            }
+            let free = (inner.width - child.size.width - margin.horizontal()).max(0.0);
+            margin.left += cross_offset(free, auto.left, auto.right, alignment);
+            let position = inset + vec2(margin.left, offset + margin.top);
+            offset += margin.vertical() + child.size.height;
+            position
+        } else {
+            if auto.left {
+                margin.left += auto_space;
+            }
+            if auto.right {
+                margin.right += auto_space;
+            }
+            let free = (inner.height - child.size.height - margin.vertical()).max(0.0);
+            margin.top += cross_offset(free, auto.top, auto.bottom, alignment);
+            let position = inset + vec2(offset + margin.left, margin.top);
+            offset += margin.horizontal() + child.size.width;
+            position
+        };
+        if let Some(child_baseline) = child.baseline {
+            baseline = Some(position.y + child_baseline);
+        }
+        child_positions.push(LayoutChild {
+            position,
+            layout: child,
+        });
+    }
 
     EqualRc::new(LayoutTreeNode {
         size,
-        margin,
+        margin: block_values.margin,
+        auto_margins: block_values.margin_auto,
+        align_self: block_values.align_self,
+        baseline,
         children: child_positions,
         render: RenderData::Node(node.clone()),
     })
@@ -91,20 +259,35 @@ pub(crate) fn layout_block(
     block_values: &BlockValues,
     parent_max_size: LogicalSize,
 ) -> EqualRc<LayoutTreeNode> {
+    if node.editable() {
+        return text_area::layout_text_area(state, node, values, block_values, parent_max_size);
+    }
     let max_size = calc_max_size(block_values, parent_max_size);
 
+    // Nodes with popups only show their selected child, and the rest in
+    // the popup.
+    let selected = if node.has_popup() {
+        node.selected_child().map(|child| child.id())
+    } else {
+        None
+    };
+
     let mut children = vec![];
     for child in node.children() {
         topo::call(|| match child {
+            DynamicNode::Node(node) if selected.map_or(false, |id| id != node.id()) => (),
+            // Nodes in the top layer are laid out on their own.
+            DynamicNode::Node(node) if node.top_layer() => (),
             DynamicNode::Node(node) => {
                 let values = *state.computed_values(node.id());
                 match values.display {
-                    DisplayType::Block(ref block) => {
+                    DisplayType::Block(ref block) | DisplayType::InlineBlock(ref block) => {
                         children.push(layout_block(state, node, &values, block, max_size));
                     }
                     DisplayType::Inline(_) => {
                         children.push(inline::layout_inline(state, node, &values, max_size));
                     }
+                    DisplayType::None => (),
                 }
             }
             DynamicNode::Text(text) => {
@@ -120,3 +303,170 @@ pub(crate) fn layout_block(
 
     moxie::cache(&(*values, children, node.to_owned()), calc_block_layout)
 }
+
+fn calc_popup_layout(
+    input: &(ComputedValues, Vec<EqualRc<LayoutTreeNode>>, AnyNode, f32),
+) -> EqualRc<LayoutTreeNode> {
+    let (values, children, node, min_width) = input;
+
+    let block_values = *values.display.block().unwrap();
+    let inset = point2(
+        block_values.padding.left + values.border.left.width.get(),
+        block_values.padding.top + values.border.top.width.get(),
+    );
+    let padding = size2(
+        block_values.padding.horizontal(),
+        block_values.padding.vertical(),
+    );
+    let border = size2(
+        values.border.left.width.get() + values.border.right.width.get(),
+        values.border.top.width.get() + values.border.bottom.width.get(),
+    );
+
+    let content_width = children
+        .iter()
+        .map(|child| child.size.width + child.margin.horizontal())
+        .fold(0.0f32, f32::max);
+    let width = (content_width + padding.width + border.width).max(*min_width);
+    let inner_width = width - padding.width - border.width;
+
+    // Options are stacked and stretched to the width of the popup.
+    let mut offset = 0.0f32;
+    let mut child_positions = vec![];
+    for child in children {
+        let position = inset + vec2(child.margin.left, offset + child.margin.top);
+        offset += child.margin.vertical() + child.size.height;
+        child_positions.push(LayoutChild {
+            position,
+            layout: with_width(child, inner_width - child.margin.horizontal()),
+        });
+    }
+
+    EqualRc::new(LayoutTreeNode {
+        size: size2(width, offset) + padding + border,
+        margin: LogicalSideOffsets::default(),
+        auto_margins: Default::default(),
+        align_self: None,
+        baseline: None,
+        children: child_positions,
+        render: RenderData::Node(node.clone()),
+    })
+}
+
+/// Lays out the children of `node` as the list shown in its popup, at
+/// least `min_width` wide. The popup is boxed like the node itself.
+pub(crate) fn layout_popup(
+    state: &mut DocumentState,
+    node: NodeRef,
+    min_width: f32,
+) -> EqualRc<LayoutTreeNode> {
+    let values = *state.computed_values(node.id());
+    let max_size = state.content_size;
+
+    let mut children = vec![];
+    for child in node.children() {
+        topo::call(|| {
+            if let DynamicNode::Node(child) = child {
+                let values = *state.computed_values(child.id());
+                if let DisplayType::Block(ref block) | DisplayType::InlineBlock(ref block) =
+                    values.display
+                {
+                    children.push(layout_block(state, child, &values, block, max_size));
+                }
+            }
+        })
+    }
+
+    moxie::cache(
+        &(values, children, node.to_owned(), min_width),
+        calc_popup_layout,
+    )
+}
+
+fn calc_top_layer_layout(
+    input: &(EqualRc<LayoutTreeNode>, Vec<EqualRc<LayoutTreeNode>>),
+) -> EqualRc<LayoutTreeNode> {
+    let (root, layers) = input;
+    let node = match root.render {
+        RenderData::Node(ref node) => node.clone(),
+        RenderData::Text { .. } | RenderData::Fill { .. } => return root.clone(),
+    };
+    let children = root
+        .children
+        .iter()
+        .map(|child| LayoutChild {
+            position: child.position,
+            layout: child.layout.clone(),
+        })
+        .chain(layers.iter().map(|layer| LayoutChild {
+            position: LogicalPoint::zero(),
+            layout: layer.clone(),
+        }))
+        .collect();
+    EqualRc::new(LayoutTreeNode {
+        size: root.size,
+        margin: root.margin,
+        auto_margins: root.auto_margins,
+        align_self: root.align_self,
+        baseline: root.baseline,
+        children,
+        render: RenderData::Node(node),
+    })
+}
+
+/// Adds the nodes in the top layer to the window's `root` layout, each
+/// laid out at the size of the window and placed over it, so that
+/// they're drawn above the rest of it.
+pub(crate) fn add_top_layer(
+    state: &mut DocumentState,
+    root: EqualRc<LayoutTreeNode>,
+) -> EqualRc<LayoutTreeNode> {
+    let size = state.content_size;
+    let mut layers = vec![];
+    for node in state.top_layer() {
+        topo::call(|| {
+            let mut values = *state.computed_values(node.id());
+            let mut block_values = match values.display.block() {
+                Some(block_values) => *block_values,
+                None => return,
+            };
+            block_values.width = Some(LogicalLength::new(size.width));
+            block_values.height = Some(LogicalLength::new(size.height));
+            block_values.margin = LogicalSideOffsets::zero();
+            values.display = DisplayType::Block(block_values);
+            layers.push(layout_block(
+                state,
+                (&node).into(),
+                &values,
+                &block_values,
+                size,
+            ));
+        })
+    }
+    if layers.is_empty() {
+        return root;
+    }
+    moxie::cache(&(root, layers), calc_top_layer_layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_within_the_free_space() {
+        assert_eq!(cross_offset(40.0, false, false, Alignment::Start), 0.0);
+        assert_eq!(cross_offset(40.0, false, false, Alignment::Center), 20.0);
+        assert_eq!(cross_offset(40.0, false, false, Alignment::End), 40.0);
+    }
+
+    #[test]
+    fn auto_margins_take_priority_over_alignment() {
+        // A single auto margin takes all of the free space.
+        assert_eq!(cross_offset(40.0, true, false, Alignment::Start), 40.0);
+        assert_eq!(cross_offset(40.0, false, true, Alignment::End), 0.0);
+        // Two auto margins share it, which centers the child.
+        assert_eq!(cross_offset(40.0, true, true, Alignment::Start), 20.0);
+        assert_eq!(cross_offset(40.0, true, true, Alignment::End), 20.0);
+    }
+}
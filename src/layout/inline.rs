@@ -5,13 +5,18 @@ use super::{
 };
 use crate::document::DocumentState;
 use crate::dom::{element::DynamicNode, node::AnyNode, node::NodeRef};
-use crate::style::{ComputedValues, DisplayType};
+use crate::style::{ComputedValues, DisplayType, Edges};
 use crate::util::equal_rc::EqualRc;
 use euclid::{point2, size2};
 
 #[derive(PartialEq, Clone)]
 enum InlineLayoutItem {
-    Block(EqualRc<LayoutTreeNode>),
+    Block {
+        layout: EqualRc<LayoutTreeNode>,
+        /// Inline-blocks are aligned using the baseline of their
+        /// contents, other blocks sit on the baseline.
+        inline_block: bool,
+    },
     Text {
         text: EqualRc<TextLayoutInfo>,
         parent: AnyNode,
@@ -22,10 +27,15 @@ struct LayoutState {
     children: Vec<LayoutChild>,
     longest_line: f32,
     height: f32,
+    baseline: Option<f32>,
 }
 
 impl LayoutState {
     fn add_line(&mut self, line: LineState) {
+        if !line.line_items.is_empty() {
+            self.baseline = Some(self.height + line.ascender);
+        }
+
         for item in line.line_items {
             let LineItem {
                 ascender,
@@ -38,13 +48,14 @@ impl LayoutState {
             });
         }
 
-        self.height += line.height;
+        self.height += line.ascender + line.descender;
         self.longest_line = self.longest_line.max(line.x);
     }
 }
 
 // Turns into LayoutChild
 struct LineItem {
+    /// Distance from the top of the item to its baseline.
     ascender: f32,
     x: f32,
     layout: EqualRc<LayoutTreeNode>,
@@ -54,8 +65,8 @@ struct LineState {
     line_items: Vec<LineItem>,
     max_width: f32,
     x: f32,
-    height: f32,
     ascender: f32,
+    descender: f32,
 }
 
 impl LineState {
@@ -63,24 +74,33 @@ impl LineState {
         LineState {
             max_width,
             x: 0.0f32,
-            height: 0.0f32,
             ascender: 0.0f32,
+            descender: 0.0f32,
             line_items: vec![],
         }
     }
 
-    fn insert_block_item(&mut self, layout: EqualRc<LayoutTreeNode>) -> bool {
+    fn insert_block_item(&mut self, layout: EqualRc<LayoutTreeNode>, inline_block: bool) -> bool {
         let size = layout.size;
-        if self.x + size.width > self.max_width {
+        let margin = layout.margin;
+        let outer_width = size.width + margin.horizontal();
+        if self.x + outer_width > self.max_width && !self.line_items.is_empty() {
             return false;
         }
+        // Without any text inside, the bottom margin edge is used as the
+        // baseline.
+        let baseline = match layout.baseline {
+            Some(baseline) if inline_block => baseline,
+            _ => size.height + margin.bottom,
+        };
         self.line_items.push(LineItem {
-            x: self.x,
-            ascender: size.height,
+            x: self.x + margin.left,
+            ascender: baseline,
             layout,
         });
-        self.x += size.width;
-        self.height = self.height.max(size.height);
+        self.x += outer_width;
+        self.ascender = self.ascender.max(margin.top + baseline);
+        self.descender = self.descender.max(size.height + margin.bottom - baseline);
         true
     }
 
@@ -94,18 +114,22 @@ impl LineState {
                         text: LayoutText {
                             fragments: line.fragments,
                             size: line.text_size,
+                            text: line.text,
                         },
                         parent,
                     },
                     size: size2(line.width, line.height),
                     margin: LogicalSideOffsets::default(),
+                    auto_margins: Edges::default(),
+                    align_self: None,
+                    baseline: Some(line.ascender),
                     children: vec![],
                 }),
             });
 
             self.x += line.width;
-            self.height = self.height.max(line.height);
             self.ascender = self.ascender.max(line.ascender);
+            self.descender = self.descender.max(line.height - line.ascender);
 
             true
         } else {
@@ -128,11 +152,22 @@ fn collect_inline_items(
                 match values.display {
                     DisplayType::Block(ref block) => {
                         let layout = block::layout_block(state, node, &values, block, max_size);
-                        items.push(InlineLayoutItem::Block(layout));
+                        items.push(InlineLayoutItem::Block {
+                            layout,
+                            inline_block: false,
+                        });
+                    }
+                    DisplayType::InlineBlock(ref block) => {
+                        let layout = block::layout_block(state, node, &values, block, max_size);
+                        items.push(InlineLayoutItem::Block {
+                            layout,
+                            inline_block: true,
+                        });
                     }
                     DisplayType::Inline(_) => {
                         collect_inline_items(state, node, &values, max_size, items);
                     }
+                    DisplayType::None => (),
                 }
             }
             DynamicNode::Text(text) => items.push(InlineLayoutItem::Text {
@@ -156,6 +191,7 @@ fn calc_inline_layout(
     let mut state = LayoutState {
         height: 0.0f32,
         longest_line: 0.0f32,
+        baseline: None,
         children: vec![],
     };
 
@@ -163,11 +199,14 @@ fn calc_inline_layout(
 
     for item in items {
         match item {
-            InlineLayoutItem::Block(layout) => {
-                if !line.insert_block_item(layout.clone()) {
+            InlineLayoutItem::Block {
+                layout,
+                inline_block,
+            } => {
+                if !line.insert_block_item(layout.clone(), *inline_block) {
                     let old_line = std::mem::replace(&mut line, LineState::new(max_width));
                     state.add_line(old_line);
-                    line.insert_block_item(layout.clone());
+                    line.insert_block_item(layout.clone(), *inline_block);
                 }
             }
             InlineLayoutItem::Text { text, parent } => {
@@ -190,6 +229,9 @@ fn calc_inline_layout(
     EqualRc::new(LayoutTreeNode {
         render: RenderData::Node(node),
         margin: LogicalSideOffsets::default(),
+        auto_margins: Edges::default(),
+        align_self: None,
+        baseline: state.baseline,
         size,
         children,
     })
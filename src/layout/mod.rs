@@ -2,19 +2,24 @@
 //! arranging elements and performing text layout.
 
 use crate::document::DocumentState;
+use crate::dom::accessibility::Bounds;
+use crate::dom::devtools::{LayoutSnapshot, Sides};
 use crate::dom::node::AnyNode;
-use crate::style::DisplayType;
+use crate::style::{Alignment, DisplayType, Edges};
 use crate::util::equal_rc::EqualRc;
+use crate::Color;
 use euclid::{Length, Point2D, SideOffsets2D, Size2D};
 use font_kit::family_name::FamilyName;
 use font_kit::properties::Properties;
 use font_kit::source::SystemSource;
 use moxie::runtime::Runtime;
 use skribo::{FontCollection, FontFamily, FontRef};
+use std::collections::HashMap;
 
 mod block;
 mod inline;
 mod text;
+mod text_area;
 
 pub struct LogicalPixel;
 pub type LogicalPoint = Point2D<f32, LogicalPixel>;
@@ -45,10 +50,21 @@ pub struct LayoutText {
     pub fragments: Vec<TextFragment>,
     /// The text size of the text.
     pub size: f32,
+    /// The text on the line, for devtools.
+    pub text: String,
 }
 
 pub enum RenderData {
-    Text { text: LayoutText, parent: AnyNode },
+    Text {
+        text: LayoutText,
+        parent: AnyNode,
+    },
+    /// A rectangle drawn for `parent` inside it, like the caret and
+    /// selection of a textarea.
+    Fill {
+        color: Color,
+        parent: AnyNode,
+    },
     Node(AnyNode),
 }
 
@@ -57,14 +73,124 @@ pub struct LayoutTreeNode {
     /// The computed size of the node.
     pub size: LogicalSize,
     pub margin: LogicalSideOffsets,
+    /// Which sides of the margin are `auto`.
+    pub(crate) auto_margins: Edges<bool>,
+    /// Overrides the parent's `align_items` for this node.
+    pub(crate) align_self: Option<Alignment>,
+    /// Distance from the top of the node to the baseline of its last
+    /// line of text, used to align inline-blocks on a line. `None` if
+    /// the node doesn't contain any text.
+    pub baseline: Option<f32>,
     pub render: RenderData,
     pub children: Vec<LayoutChild>,
 }
 
+fn sides(offsets: LogicalSideOffsets) -> Sides {
+    Sides {
+        top: offsets.top,
+        right: offsets.right,
+        bottom: offsets.bottom,
+        left: offsets.left,
+    }
+}
+
+impl LayoutTreeNode {
+    /// Copies the layout for devtools, where `position` is where the node
+    /// was placed in the window.
+    pub(crate) fn snapshot(&self, state: &DocumentState, position: LogicalPoint) -> LayoutSnapshot {
+        let (node, text, border, padding) = match self.render {
+            RenderData::Node(ref node) => {
+                let values = state.computed_values(node.id());
+                let border = values.border.map(|side| side.width.get());
+                let padding = values
+                    .display
+                    .block()
+                    .map_or(Sides::default(), |block| sides(block.padding));
+                (
+                    node,
+                    None,
+                    Sides {
+                        top: border.top,
+                        right: border.right,
+                        bottom: border.bottom,
+                        left: border.left,
+                    },
+                    padding,
+                )
+            }
+            RenderData::Text {
+                ref text,
+                ref parent,
+            } => (
+                parent,
+                Some(text.text.clone()),
+                Sides::default(),
+                Sides::default(),
+            ),
+            RenderData::Fill { ref parent, .. } => {
+                (parent, None, Sides::default(), Sides::default())
+            }
+        };
+        LayoutSnapshot {
+            node: node.id(),
+            name: node.name().to_owned(),
+            text,
+            x: position.x,
+            y: position.y,
+            width: self.size.width,
+            height: self.size.height,
+            margin: sides(self.margin),
+            border,
+            padding,
+            baseline: self.baseline,
+            children: self
+                .children
+                .iter()
+                .map(|child| {
+                    child
+                        .layout
+                        .snapshot(state, position + child.position.to_vector())
+                })
+                .collect(),
+        }
+    }
+
+    /// Records where each node was placed in the window, as the union of
+    /// its boxes, where `position` is where this node was placed.
+    pub(crate) fn collect_bounds(&self, position: LogicalPoint, bounds: &mut HashMap<u64, Bounds>) {
+        let id = match self.render {
+            RenderData::Node(ref node) => node.id(),
+            RenderData::Text { ref parent, .. } | RenderData::Fill { ref parent, .. } => {
+                parent.id()
+            }
+        };
+        let rect = Bounds {
+            x: position.x,
+            y: position.y,
+            width: self.size.width,
+            height: self.size.height,
+        };
+        bounds
+            .entry(id)
+            .and_modify(|bounds| *bounds = bounds.union(rect))
+            .or_insert(rect);
+        for child in &self.children {
+            child
+                .layout
+                .collect_bounds(position + child.position.to_vector(), bounds);
+        }
+    }
+}
+
 /// Used to build the layout tree, with internal caching for
 /// performance.
 pub struct LayoutEngine {
     runtime: Runtime,
+    /// Popups are laid out separately, so that laying them out only while
+    /// they're open doesn't throw away the rest of the cached layout.
+    popup_runtime: Runtime,
+    /// The same goes for the tooltip.
+    tooltip_runtime: Runtime,
     collection: EqualRc<FontCollection>,
 }
 
@@ -81,12 +207,42 @@ impl LayoutEngine {
 
         LayoutEngine {
             runtime: Runtime::new(),
+            popup_runtime: Runtime::new(),
+            tooltip_runtime: Runtime::new(),
             collection: EqualRc::new(collection),
         }
     }
 
+    /// Lays out `text` on a single line, or returns `None` if it's empty.
+    pub fn layout_line(&self, text: &str, size: f32) -> Option<LayoutText> {
+        illicit::Layer::new()
+            .offer(self.collection.clone())
+            .enter(|| {
+                let info = text::TextLayoutInfo::new(text.to_owned(), size);
+                let line = text::TextState::new(&info).fill_line(f32::INFINITY, true)?;
+                Some(LayoutText {
+                    fragments: line.fragments,
+                    size: line.text_size,
+                    text: line.text,
+                })
+            })
+    }
+
+    /// The width of `text` laid out on a single line.
+    pub fn measure(&self, text: &str, size: f32) -> f32 {
+        illicit::Layer::new()
+            .offer(self.collection.clone())
+            .enter(|| {
+                let info = text::TextLayoutInfo::new(text.to_owned(), size);
+                text::TextState::new(&info)
+                    .fill_line(f32::INFINITY, false)
+                    .map_or(0.0, |line| line.width)
+            })
+    }
+
     /// Perform a layout step based on the new DOM and content size, and
-    /// return a fresh layout tree.
+    /// return a fresh layout tree. Nodes in the top layer are placed last
+    /// among the children of the root.
     pub(crate) fn layout(&mut self, state: &mut DocumentState) -> EqualRc<LayoutTreeNode> {
         illicit::Layer::new()
             .offer(self.collection.clone())
@@ -94,20 +250,83 @@ impl LayoutEngine {
                 self.runtime.run_once(move || {
                     let node = state.window.clone();
                     let values = *state.computed_values(node.id());
-                    match values.display {
-                        DisplayType::Block(ref block) => block::layout_block(
+                    let root = match values.display {
+                        DisplayType::Block(ref block) | DisplayType::InlineBlock(ref block) => {
+                            block::layout_block(
+                                state,
+                                (&node).into(),
+                                &values,
+                                block,
+                                state.content_size,
+                            )
+                        }
+                        DisplayType::Inline(_) => inline::layout_inline(
                             state,
                             (&node).into(),
                             &values,
-                            block,
                             state.content_size,
                         ),
-                        DisplayType::Inline(_) => inline::layout_inline(
+                        DisplayType::None => EqualRc::new(LayoutTreeNode {
+                            size: LogicalSize::zero(),
+                            margin: LogicalSideOffsets::default(),
+                            auto_margins: Edges::default(),
+                            align_self: None,
+                            baseline: None,
+                            render: RenderData::Node(node.into()),
+                            children: vec![],
+                        }),
+                    };
+                    block::add_top_layer(state, root)
+                })
+            })
+    }
+
+    /// Lays out the children of the node whose popup is open, as the list
+    /// shown in the popup.
+    pub(crate) fn layout_popup(
+        &mut self,
+        state: &mut DocumentState,
+        min_width: f32,
+    ) -> Option<EqualRc<LayoutTreeNode>> {
+        let node = state.popup()?;
+        let layout = illicit::Layer::new()
+            .offer(self.collection.clone())
+            .enter(move || {
+                self.popup_runtime
+                    .run_once(move || block::layout_popup(state, (&node).into(), min_width))
+            });
+        Some(layout)
+    }
+
+    /// Lays out the node the shown tooltip is drawn in, unless its style
+    /// hides it.
+    pub(crate) fn layout_tooltip(
+        &mut self,
+        state: &mut DocumentState,
+    ) -> Option<EqualRc<LayoutTreeNode>> {
+        let node = state.tooltip_node()?;
+        illicit::Layer::new()
+            .offer(self.collection.clone())
+            .enter(move || {
+                self.tooltip_runtime.run_once(move || {
+                    let values = *state.computed_values(node.id());
+                    match values.display {
+                        DisplayType::Block(ref block) | DisplayType::InlineBlock(ref block) => {
+                            Some(block::layout_block(
+                                state,
+                                (&node).into(),
+                                &values,
+                                block,
+                                state.content_size,
+                            ))
+                        }
+                        DisplayType::Inline(_) => Some(inline::layout_inline(
                             state,
                             (&node).into(),
                             &values,
                             state.content_size,
-                        ),
+                        )),
+                        DisplayType::None => None,
                     }
                 })
             })
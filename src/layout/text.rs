@@ -15,6 +15,7 @@ pub struct FilledLine {
     pub ascender: f32,
     pub fragments: Vec<TextFragment>,
     pub text_size: f32,
+    pub text: String,
 }
 
 pub struct TextState<'a> {
@@ -63,6 +64,11 @@ impl<'a> TextState<'a> {
         fragments
     }
 
+    /// How far into the text the lines filled so far reach.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
     pub fn finished(&self) -> bool {
         self.offset == self.layout.session.borrow().text().len()
     }
@@ -111,6 +117,7 @@ impl<'a> TextState<'a> {
                                 height: last_word_height,
                                 ascender: last_word_ascender,
                                 text_size: size,
+                                text: text[start..self.offset].to_owned(),
                             });
                         } else {
                             // todo: force progress by hard breaking if is_new_line is true
@@ -137,6 +144,7 @@ impl<'a> TextState<'a> {
                 height: last_word_height,
                 ascender: last_word_ascender,
                 text_size: size,
+                text: text[start..self.offset].to_owned(),
             })
         } else {
             None
@@ -0,0 +1,243 @@
+//! Lays out elements whose text is edited by the document, like
+//! textareas. Their text is wrapped into visual lines, and only the lines
+//! scrolled into view are laid out, along with the selection and caret.
+
+use super::{
+    text::{TextLayoutInfo, TextState},
+    LayoutChild, LayoutText, LayoutTreeNode, LogicalSize, RenderData,
+};
+use crate::document::DocumentState;
+use crate::dom::element::ElementState;
+use crate::dom::elements::control::accent_color;
+use crate::dom::node::{AnyNode, NodeRef};
+use crate::style::{BlockValues, ComputedValues};
+use crate::util::equal_rc::EqualRc;
+use crate::Color;
+use euclid::{point2, size2, vec2};
+use std::ops::Range;
+
+/// How wide the caret is drawn.
+const CARET_WIDTH: f32 = 1.0;
+
+/// The width of `text` laid out on a single line.
+fn measure(text: &str, size: f32) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let info = TextLayoutInfo::new(text.to_owned(), size);
+    TextState::new(&info)
+        .fill_line(f32::INFINITY, false)
+        .map_or(0.0, |line| line.width)
+}
+
+/// Splits `text` into the visual lines it wraps to within `width`, as
+/// byte ranges of it, along with the height of a line. Lines are broken
+/// at newlines, which aren't part of any line, and between words. Words
+/// longer than a line are left to overflow it.
+fn wrap_lines(input: &(String, f32, f32)) -> (Vec<Range<usize>>, f32) {
+    let (text, width, size) = input;
+
+    let info = TextLayoutInfo::new("M".to_owned(), *size);
+    let line_height = TextState::new(&info)
+        .fill_line(f32::INFINITY, false)
+        .map_or(*size, |line| line.height);
+
+    let mut lines = vec![];
+    let mut start = 0;
+    for hard_line in text.split('\n') {
+        if hard_line.is_empty() {
+            lines.push(start..start);
+        } else {
+            let info = TextLayoutInfo::new(hard_line.to_owned(), *size);
+            let mut state = TextState::new(&info);
+            while !state.finished() {
+                let line_start = state.offset();
+                let filled = state
+                    .fill_line(*width, false)
+                    .or_else(|| state.fill_line(f32::INFINITY, false));
+                if filled.is_none() {
+                    break;
+                }
+                lines.push(start + line_start..start + state.offset());
+            }
+        }
+        start += hard_line.len() + 1;
+    }
+    (lines, line_height)
+}
+
+/// What the laid out lines of an editable node depend on.
+type EditorLayoutInput = (
+    ComputedValues,
+    LogicalSize,
+    String,
+    Vec<Range<usize>>,
+    (usize, Range<usize>, usize, usize),
+    f32,
+    bool,
+    AnyNode,
+);
+
+fn calc_text_area_layout(input: &EditorLayoutInput) -> EqualRc<LayoutTreeNode> {
+    let (values, size, text, lines, editor_state, line_height, focused, node) = input;
+    let (caret, selection, scroll, visible) = editor_state.clone();
+    let block_values = *values.display.block().unwrap();
+    let text_size = values.text_size.get();
+    let inset = point2(
+        block_values.padding.left + values.border.left.width.get(),
+        block_values.padding.top + values.border.top.width.get(),
+    );
+
+    let mut selected = vec![];
+    let mut text_lines = vec![];
+    let mut caret_child = None;
+    for (index, line) in lines.iter().enumerate().skip(scroll).take(visible) {
+        let position = inset + vec2(0.0, (index - scroll) as f32 * line_height);
+        let x_of = |offset: usize| measure(&text[line.start..offset], text_size);
+
+        // Selected newlines are shown as a little space at the end of the
+        // line.
+        let newline = text[line.end..].starts_with('\n')
+            && selection.start <= line.end
+            && line.end < selection.end;
+        let start = selection.start.max(line.start);
+        let end = selection.end.min(line.end);
+        if start < end || newline {
+            let x = x_of(start);
+            let newline_width = if newline { text_size / 4.0 } else { 0.0 };
+            selected.push(LayoutChild {
+                position: position + vec2(x, 0.0),
+                layout: fill(
+                    size2(x_of(end) - x + newline_width, *line_height),
+                    Color {
+                        alpha: 80,
+                        ..accent_color()
+                    },
+                    node,
+                ),
+            });
+        }
+
+        if line.start < line.end {
+            let info = TextLayoutInfo::new(text[line.clone()].to_owned(), text_size);
+            if let Some(filled) = TextState::new(&info).fill_line(f32::INFINITY, false) {
+                text_lines.push(LayoutChild {
+                    position,
+                    layout: EqualRc::new(LayoutTreeNode {
+                        size: size2(filled.width, *line_height),
+                        margin: Default::default(),
+                        auto_margins: Default::default(),
+                        align_self: None,
+                        baseline: Some(filled.ascender),
+                        children: vec![],
+                        render: RenderData::Text {
+                            text: LayoutText {
+                                fragments: filled.fragments,
+                                size: filled.text_size,
+                                text: filled.text,
+                            },
+                            parent: node.clone(),
+                        },
+                    }),
+                });
+            }
+        }
+
+        // The caret at a soft break is shown at the start of the next
+        // line, like text typed there would be.
+        let next_start = lines.get(index + 1).map(|next| next.start);
+        if *focused
+            && line.start <= caret
+            && caret <= line.end
+            && next_start.map_or(true, |next| caret < next)
+        {
+            caret_child = Some(LayoutChild {
+                position: position + vec2(x_of(caret), 0.0),
+                layout: fill(size2(CARET_WIDTH, *line_height), values.text_color, node),
+            });
+        }
+    }
+
+    let mut children = selected;
+    children.extend(text_lines);
+    children.extend(caret_child);
+    EqualRc::new(LayoutTreeNode {
+        size: *size,
+        margin: block_values.margin,
+        auto_margins: block_values.margin_auto,
+        align_self: block_values.align_self,
+        baseline: None,
+        children,
+        render: RenderData::Node(node.clone()),
+    })
+}
+
+fn fill(size: LogicalSize, color: Color, parent: &AnyNode) -> EqualRc<LayoutTreeNode> {
+    EqualRc::new(LayoutTreeNode {
+        size,
+        margin: Default::default(),
+        auto_margins: Default::default(),
+        align_self: None,
+        baseline: None,
+        children: vec![],
+        render: RenderData::Fill {
+            color,
+            parent: parent.clone(),
+        },
+    })
+}
+
+/// Lays out the editable `node`, whose text is wrapped to its width and
+/// scrolled so that the caret stays visible. Unless it has a height, it's
+/// as tall as its text.
+pub(crate) fn layout_text_area(
+    state: &mut DocumentState,
+    node: NodeRef,
+    values: &ComputedValues,
+    block_values: &BlockValues,
+    max_size: LogicalSize,
+) -> EqualRc<LayoutTreeNode> {
+    let padding: LogicalSize = size2(
+        block_values.padding.horizontal(),
+        block_values.padding.vertical(),
+    );
+    let border: LogicalSize = size2(
+        values.border.left.width.get() + values.border.right.width.get(),
+        values.border.top.width.get() + values.border.bottom.width.get(),
+    );
+    let width = block_values
+        .width
+        .map_or(max_size.width, |width| width.get());
+    let inner_width = width - padding.width - border.width;
+
+    let focused = state.node_states(node.id()).contains(ElementState::Focus);
+    let editor = state.editor(node);
+    let text = editor.text().to_owned();
+    let (lines, line_height) = moxie::cache(
+        &(text.clone(), inner_width, values.text_size.get()),
+        wrap_lines,
+    );
+
+    let height = match block_values.height {
+        Some(height) => height.get(),
+        None => lines.len() as f32 * line_height + padding.height + border.height,
+    };
+    let inner_height = height - padding.height - border.height;
+    let visible = ((inner_height / line_height).floor() as usize).max(1);
+    editor.set_lines(lines.clone(), visible, line_height);
+
+    let editor_state = (editor.caret(), editor.selection(), editor.scroll(), visible);
+    moxie::cache(
+        &(
+            *values,
+            size2(width, height),
+            text,
+            lines,
+            editor_state,
+            line_height,
+            focused,
+            node.to_owned(),
+        ),
+        calc_text_area_layout,
+    )
+}
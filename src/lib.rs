@@ -50,6 +50,6 @@ pub mod style;
 pub mod style_impl;
 mod util;
 
-pub use runtime::Runtime;
+pub use runtime::{Runtime, UpdateHandle};
 pub use topo;
 pub use util::color::Color;
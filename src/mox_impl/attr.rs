@@ -9,4 +9,34 @@ macro_rules! attribute {
 }
 
 attribute!(style -> AttrStyle);
+attribute!(class -> AttrClass);
+attribute!(inline_style -> AttrInlineStyle);
 attribute!(title -> AttrTitle);
+attribute!(label -> AttrLabel);
+attribute!(description -> AttrDescription);
+attribute!(checked -> AttrChecked);
+attribute!(group -> AttrGroup);
+attribute!(min -> AttrMin);
+attribute!(max -> AttrMax);
+attribute!(step -> AttrStep);
+attribute!(track_style -> AttrTrackStyle);
+attribute!(fill_style -> AttrFillStyle);
+attribute!(thumb_style -> AttrThumbStyle);
+attribute!(tooltip_style -> AttrTooltipStyle);
+attribute!(modal -> AttrModal);
+attribute!(size -> AttrSize);
+attribute!(min_size -> AttrMinSize);
+attribute!(max_size -> AttrMaxSize);
+attribute!(position -> AttrPosition);
+attribute!(resizable -> AttrResizable);
+attribute!(maximized -> AttrMaximized);
+attribute!(fullscreen -> AttrFullscreen);
+attribute!(always_on_top -> AttrAlwaysOnTop);
+attribute!(decorations -> AttrDecorations);
+attribute!(icon -> AttrIcon);
+attribute!(background -> AttrBackground);
+
+/// Its type is picked by the element it's set on.
+pub fn value<Value>() -> AttrValue<Value> {
+    Default::default()
+}
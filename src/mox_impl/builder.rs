@@ -1,5 +1,6 @@
 use crate::dom::element::{Attribute, Element, Event, HasAttribute, HasEvent};
 use crate::dom::node::Node;
+use crate::style::Variables;
 use crate::util::event_handler::EventHandler;
 use std::marker::PhantomData;
 use std::sync::atomic::AtomicU64;
@@ -134,8 +135,13 @@ where
 {
     /// Creates a new builder.
     pub(crate) fn new() -> Self {
+        Builder::with_element(Elt::default())
+    }
+
+    /// Creates a builder for the element with its attributes already set.
+    pub(crate) fn with_element(element: Elt) -> Self {
         Builder {
-            element: Elt::default(),
+            element,
             handlers: Elt::Handlers::default(),
             children: vec![],
         }
@@ -186,9 +192,23 @@ where
 
         let id = moxie::once(|| ID_COUNTER.fetch_add(1, Ordering::Acquire));
 
+        let children = if children.is_empty() {
+            element.default_children()
+        } else {
+            children
+        };
+
+        // Style variables offered around the component are captured so
+        // that styling, which runs outside of it, can see them.
+        let variables = illicit::get::<Variables>()
+            .ok()
+            .map(|variables| (*variables).clone());
+
         let node = moxie::cache(
-            &(element, children),
-            |(elt, children): &(Elt, Vec<Elt::Child>)| Node::new(id, elt.clone(), children.clone()),
+            &(element, children, variables),
+            |(elt, children, variables): &(Elt, Vec<Elt::Child>, Option<Variables>)| {
+                Node::with_variables(id, elt.clone(), children.clone(), variables.clone())
+            },
         );
 
         *node.handlers().lock().unwrap() = handlers;
@@ -21,6 +21,47 @@ pub fn button() -> Builder<Button> {
     Builder::new()
 }
 
+/// A box that can be checked and unchecked.
+pub fn checkbox() -> Builder<Checkbox> {
+    Builder::new()
+}
+
+/// One of a group of options, of which one can be selected.
+pub fn radio() -> Builder<Radio> {
+    Builder::new()
+}
+
+/// A toggle switch, which behaves like a checkbox.
+pub fn switch() -> Builder<Switch> {
+    Builder::new()
+}
+
+/// Shows the chosen option, and opens a list of them when clicked.
+pub fn select() -> Builder<Select> {
+    Builder::new()
+}
+
+/// One of the choices of a select.
+pub fn option() -> Builder<SelectOption> {
+    Builder::new()
+}
+
+/// Shows its children above the rest of the window, like a dialog or a
+/// toast.
+pub fn overlay() -> Builder<Overlay> {
+    Builder::new()
+}
+
+/// Multiple lines of editable text.
+pub fn textarea() -> Builder<TextArea> {
+    Builder::new()
+}
+
+/// A thumb dragged along a track to pick a number.
+pub fn slider() -> Builder<Slider> {
+    Builder::new()
+}
+
 /// Container for inline text.
 pub fn span() -> Builder<Span> {
     Builder::new()
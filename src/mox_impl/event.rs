@@ -1,6 +1,10 @@
-use crate::dom::events::ClickEvent;
+use crate::dom::events::{ChangeEvent, ClickEvent};
 use std::marker::PhantomData;
 
 pub fn on_click() -> PhantomData<ClickEvent> {
     PhantomData
 }
+
+pub fn on_change<Value>() -> PhantomData<ChangeEvent<Value>> {
+    PhantomData
+}
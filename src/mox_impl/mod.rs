@@ -14,7 +14,7 @@
 use crate::dom::element::Element;
 
 pub mod attr;
-mod builder;
+pub(crate) mod builder;
 pub mod elt;
 pub mod event;
 
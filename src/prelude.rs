@@ -1,6 +1,9 @@
 // For conveniently naming various types
-pub use crate::dom::{events::*, App, Button, Node, Span, View, Window};
-pub use crate::style::Style;
+pub use crate::dom::{
+    events::*, App, Button, Checkbox, Node, Overlay, Radio, Select, Slider, Span, Switch, TextArea,
+    View, Window,
+};
+pub use crate::style::{InlineStyle, Style, Stylesheet, Variables};
 // mox!() macro
 pub use crate::mox_impl;
 pub use mox;
@@ -1,7 +1,14 @@
+use super::overlay::{FrameHistory, HISTORY_LEN};
 use crate::document::Document;
+use crate::dom::accessibility::AccessibilityTree;
+use crate::dom::devtools::{FrameTimings, LayoutSnapshot, Phase};
+use crate::dom::input::PointerPosition;
 use crate::dom::{Node, Window};
-use crate::layout::{LayoutText, LayoutTreeNode, LogicalPixel, RenderData};
-use crate::style::BorderStyle as DomBorderStyle;
+use crate::layout::{
+    LayoutText, LayoutTreeNode, LogicalPixel, LogicalPoint, LogicalSideOffsets, RenderData,
+};
+use crate::runtime::UserEvent;
+use crate::style::{BorderStyle as DomBorderStyle, Visibility};
 use crate::util::equal_rc::EqualRc;
 use gleam::gl;
 use log::debug;
@@ -9,13 +16,14 @@ use skribo::FontRef;
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::mpsc;
+use std::time::Duration;
 use webrender::{
     api::{
-        units::DeviceIntRect, units::DevicePixel, units::LayoutSideOffsets, BorderDetails,
-        BorderRadius, BorderSide, BorderStyle, ClipId, ClipMode, ColorF, CommonItemProperties,
-        ComplexClipRegion, DisplayListBuilder, DocumentId, Epoch, FontInstanceKey, FontKey,
-        GlyphInstance, HitTestFlags, NormalBorder, PipelineId, PrimitiveFlags, RenderApi,
-        RenderNotifier, SpaceAndClipInfo, SpatialId, Transaction,
+        units::DeviceIntRect, units::DevicePixel, units::LayoutRect, units::LayoutSideOffsets,
+        units::LayoutSize, BorderDetails, BorderRadius, BorderSide, BorderStyle, ClipId, ClipMode,
+        ColorF, CommonItemProperties, ComplexClipRegion, DisplayListBuilder, DocumentId, Epoch,
+        FontInstanceKey, FontKey, GlyphInstance, HitTestFlags, NormalBorder, PipelineId,
+        PrimitiveFlags, RenderApi, RenderNotifier, SpaceAndClipInfo, SpatialId, Transaction,
     },
     euclid::{point2, size2, Point2D, Rect, Scale, Size2D},
     Renderer, RendererOptions,
@@ -29,7 +37,7 @@ use winit::{
 /// Used to wait for frames to be ready in Webrender.
 #[derive(Clone)]
 struct Notifier {
-    events_proxy: EventLoopProxy<()>,
+    events_proxy: EventLoopProxy<UserEvent>,
     tx: mpsc::Sender<()>,
 }
 
@@ -40,7 +48,7 @@ impl RenderNotifier for Notifier {
 
     fn wake_up(&self) {
         self.tx.send(()).unwrap();
-        let _ = self.events_proxy.send_event(());
+        let _ = self.events_proxy.send_event(UserEvent::Wakeup);
     }
 
     fn new_frame_ready(&self, _: DocumentId, _: bool, _: bool, _: Option<u64>) {
@@ -63,13 +71,180 @@ pub struct Context {
     dpi_scale: f32,
     fonts: HashMap<String, FontKey>,
     font_instances: HashMap<(FontKey, usize), FontInstanceKey>,
+    /// Set while devtools are picking a node, so that every node is hit
+    /// tested rather than only the painted ones.
+    inspecting: bool,
+    /// The node whose boxes are drawn over the window.
+    highlight: Option<u64>,
+    /// The layout tree from the last render.
+    layout: Option<EqualRc<LayoutTreeNode>>,
+    history: FrameHistory,
+    /// Whether the frame rate and timings overlay is shown.
+    overlay: bool,
+}
+
+/// Setting this environment variable shows the frame overlay from the
+/// start, rather than only after pressing F3.
+const OVERLAY_VAR: &str = "MOXIE_NATIVE_OVERLAY";
+
+/// Layout of the frame overlay, in logical pixels.
+const OVERLAY_MARGIN: f32 = 8.0;
+const OVERLAY_PADDING: f32 = 6.0;
+const OVERLAY_TEXT_SIZE: f32 = 12.0;
+const OVERLAY_LINE_HEIGHT: f32 = 16.0;
+const OVERLAY_BAR_WIDTH: f32 = 2.0;
+const OVERLAY_GRAPH_HEIGHT: f32 = 60.0;
+/// Height of the graph per millisecond.
+const OVERLAY_GRAPH_SCALE: f32 = 2.0;
+/// A line is drawn across the graph at this duration, the budget for a
+/// frame at 60 FPS.
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+const OVERLAY_BACKGROUND: ColorF = ColorF {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    a: 0.75,
+};
+const OVERLAY_TEXT: ColorF = ColorF {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+    a: 1.0,
+};
+const OVERLAY_BUDGET: ColorF = ColorF {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+    a: 0.4,
+};
+
+/// How far from the cursor tooltips are placed, so that it doesn't cover
+/// them.
+const TOOLTIP_OFFSET: f32 = 16.0;
+
+/// The color of each phase in the graph, in the order of `Phase::ALL`.
+const PHASE_COLORS: [ColorF; 5] = [
+    ColorF {
+        r: 0.55,
+        g: 0.45,
+        b: 0.95,
+        a: 1.0,
+    },
+    ColorF {
+        r: 0.35,
+        g: 0.7,
+        b: 0.95,
+        a: 1.0,
+    },
+    ColorF {
+        r: 0.4,
+        g: 0.85,
+        b: 0.45,
+        a: 1.0,
+    },
+    ColorF {
+        r: 0.95,
+        g: 0.75,
+        b: 0.3,
+        a: 1.0,
+    },
+    ColorF {
+        r: 0.95,
+        g: 0.4,
+        b: 0.35,
+        a: 1.0,
+    },
+];
+
+fn millis(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+/// Colors for the boxes of a highlighted node.
+const MARGIN_HIGHLIGHT: ColorF = ColorF {
+    r: 0.97,
+    g: 0.65,
+    b: 0.35,
+    a: 0.5,
+};
+const BORDER_HIGHLIGHT: ColorF = ColorF {
+    r: 0.99,
+    g: 0.86,
+    b: 0.6,
+    a: 0.5,
+};
+const PADDING_HIGHLIGHT: ColorF = ColorF {
+    r: 0.76,
+    g: 0.82,
+    b: 0.54,
+    a: 0.5,
+};
+const CONTENT_HIGHLIGHT: ColorF = ColorF {
+    r: 0.44,
+    g: 0.66,
+    b: 0.75,
+    a: 0.5,
+};
+
+/// Finds the node with `id` in the layout tree, along with where it was
+/// laid out.
+fn find_layout(
+    layout: &LayoutTreeNode,
+    position: LogicalPoint,
+    id: u64,
+) -> Option<(LogicalPoint, &LayoutTreeNode)> {
+    if let RenderData::Node(ref node) = layout.render {
+        if node.id() == id {
+            return Some((position, layout));
+        }
+    }
+    layout
+        .children
+        .iter()
+        .find_map(|child| find_layout(&child.layout, position + child.position.to_vector(), id))
+}
+
+fn layout_offsets(offsets: LogicalSideOffsets) -> LayoutSideOffsets {
+    LayoutSideOffsets::new(offsets.top, offsets.right, offsets.bottom, offsets.left)
+}
+
+/// Draws the area between `rect` and its inner rect, where `widths` are
+/// the distances between them.
+fn push_frame(
+    builder: &mut DisplayListBuilder,
+    space_and_clip: &SpaceAndClipInfo,
+    rect: LayoutRect,
+    widths: LayoutSideOffsets,
+    color: ColorF,
+) {
+    if widths.top == 0.0 && widths.right == 0.0 && widths.bottom == 0.0 && widths.left == 0.0 {
+        return;
+    }
+    let side = BorderSide {
+        color,
+        style: BorderStyle::Solid,
+    };
+    builder.push_border(
+        &CommonItemProperties::new(rect, *space_and_clip),
+        rect,
+        widths,
+        BorderDetails::Normal(NormalBorder {
+            left: side,
+            right: side,
+            top: side,
+            bottom: side,
+            radius: BorderRadius::zero(),
+            do_aa: false,
+        }),
+    );
 }
 
 impl Context {
     pub fn new(
         gl: Rc<dyn gl::Gl>,
         parent_window: &WinitWindow,
-        events_proxy: EventLoopProxy<()>,
+        events_proxy: EventLoopProxy<UserEvent>,
         window: Node<Window>,
     ) -> Context {
         let (tx, rx) = mpsc::channel();
@@ -84,7 +259,7 @@ impl Context {
             gl,
             notifier,
             RendererOptions {
-                clear_color: Some(ColorF::new(1.0, 1.0, 1.0, 1.0)),
+                clear_color: Some(window.element().background.into()),
                 device_pixel_ratio: dpi_scale,
                 ..Default::default()
             },
@@ -111,6 +286,11 @@ impl Context {
             dpi_scale,
             fonts: HashMap::new(),
             font_instances: HashMap::new(),
+            inspecting: false,
+            highlight: None,
+            layout: None,
+            history: FrameHistory::default(),
+            overlay: std::env::var_os(OVERLAY_VAR).is_some(),
         }
     }
 
@@ -119,6 +299,12 @@ impl Context {
     }
 
     pub fn set_dom_window(&mut self, new_node: Node<Window>) {
+        let background = new_node.element().background;
+        if self.document.window().element().background != background {
+            if let Some(renderer) = &mut self.renderer {
+                renderer.set_clear_color(Some(background.into()));
+            }
+        }
         self.document.set_root(new_node);
     }
 
@@ -130,6 +316,64 @@ impl Context {
         );
     }
 
+    /// Turns hit testing of every node on or off, which takes effect on
+    /// the next render. Turning it off also clears the highlight.
+    pub fn set_inspecting(&mut self, inspecting: bool) {
+        self.inspecting = inspecting;
+        if !inspecting {
+            self.highlight = None;
+        }
+    }
+
+    pub fn highlight(&self) -> Option<u64> {
+        self.highlight
+    }
+
+    /// Sets the node whose margin, border, padding and content boxes are
+    /// drawn over the window, returning whether it changed.
+    pub fn set_highlight(&mut self, highlight: Option<u64>) -> bool {
+        let changed = self.highlight != highlight;
+        self.highlight = highlight;
+        changed
+    }
+
+    /// Shows or hides the frame overlay, which takes effect on the next
+    /// render.
+    pub fn toggle_overlay(&mut self) {
+        self.overlay = !self.overlay;
+    }
+
+    /// The accessibility tree as of the last render.
+    pub fn accessibility_tree(&self) -> Option<AccessibilityTree> {
+        self.layout
+            .as_ref()
+            .map(|layout| self.document.accessibility_tree(layout))
+    }
+
+    /// Where `position` is relative to the node with `id` as of the last
+    /// render.
+    pub fn pointer_position(
+        &self,
+        id: u64,
+        position: LogicalPosition<f32>,
+    ) -> Option<PointerPosition> {
+        let layout = self.layout.as_ref()?;
+        let (origin, layout) = find_layout(layout, LogicalPoint::zero(), id)?;
+        Some(PointerPosition {
+            x: position.x - origin.x,
+            y: position.y - origin.y,
+            width: layout.size.width,
+            height: layout.size.height,
+        })
+    }
+
+    /// Copies the layout tree from the last render for devtools.
+    pub fn layout_snapshot(&self) -> Option<LayoutSnapshot> {
+        self.layout
+            .as_ref()
+            .map(|layout| self.document.layout_snapshot(layout))
+    }
+
     fn get_font(&mut self, font: &FontRef, txn: &mut Transaction) -> FontKey {
         let full_name = font.font.full_name();
         if let Some(&key) = self.fonts.get(&full_name) {
@@ -174,6 +418,9 @@ impl Context {
         match layout.render {
             RenderData::Node(ref node) => {
                 let values = self.document.computed_values(node.id());
+                // Hidden elements aren't painted or hit tested, but their
+                // children may still be visible.
+                let visible = values.visibility == Visibility::Visible;
 
                 let corner_radius = BorderRadius {
                     top_left: size2(
@@ -194,7 +441,11 @@ impl Context {
                     ),
                 };
 
-                if values.background_color.alpha > 0 || node.interactive() {
+                let painted = values.background_color.alpha > 0 || node.interactive();
+                // Modal nodes catch clicks meant for what's below them, and
+                // nodes with tooltips have to be found under the pointer.
+                let hit_tested = painted || node.modal() || node.tooltip().is_some();
+                if visible && (hit_tested || self.inspecting) {
                     let clip_id = if !corner_radius.is_zero() {
                         let region = ComplexClipRegion::new(rect, corner_radius, ClipMode::Clip);
                         builder.define_clip(
@@ -211,11 +462,13 @@ impl Context {
                         spatial_id: SpatialId::root_scroll_node(pipeline_id),
                         flags: PrimitiveFlags::empty(),
                     };
-                    builder.push_rect(&item_props, rect, values.background_color.into());
+                    if painted {
+                        builder.push_rect(&item_props, rect, values.background_color.into());
+                    }
                     builder.push_hit_test(&item_props, (node.id(), 0));
                 }
 
-                if values.border.visible() {
+                if visible && values.border.visible() {
                     let common = CommonItemProperties::new(rect, space_and_clip);
                     let borders = values.border.map(|side| BorderSide {
                         style: match side.style {
@@ -264,49 +517,281 @@ impl Context {
                 }
             }
             RenderData::Text {
-                text:
-                    LayoutText {
-                        ref fragments,
-                        size,
-                    },
+                ref text,
                 ref parent,
             } => {
                 let values = self.document.computed_values(parent.id());
-                let color = values.text_color;
-                builder.push_simple_stacking_context(
-                    point2(0.0, 0.0),
-                    space_and_clip.spatial_id,
-                    PrimitiveFlags::IS_BACKFACE_VISIBLE,
+                if values.visibility == Visibility::Hidden {
+                    return;
+                }
+                let color = values.text_color.into();
+                self.render_text(builder, transaction, &space_and_clip, rect, text, color);
+            }
+            RenderData::Fill { color, ref parent } => {
+                let values = self.document.computed_values(parent.id());
+                if values.visibility == Visibility::Hidden {
+                    return;
+                }
+                let common = CommonItemProperties::new(rect, space_and_clip);
+                builder.push_rect(&common, rect, color.into());
+            }
+        }
+    }
+
+    fn render_text(
+        &mut self,
+        builder: &mut DisplayListBuilder,
+        transaction: &mut Transaction,
+        space_and_clip: &SpaceAndClipInfo,
+        rect: LayoutRect,
+        text: &LayoutText,
+        color: ColorF,
+    ) {
+        builder.push_simple_stacking_context(
+            point2(0.0, 0.0),
+            space_and_clip.spatial_id,
+            PrimitiveFlags::IS_BACKFACE_VISIBLE,
+        );
+        for fragment in &text.fragments {
+            let glyphs = fragment
+                .glyphs
+                .iter()
+                .map(|glyph| GlyphInstance {
+                    index: glyph.index,
+                    point: rect.origin + glyph.offset.to_vector().cast_unit(),
+                })
+                .collect::<Vec<_>>();
+            let font_key = self.get_font(&fragment.font, transaction);
+            let key = self.get_font_instance(font_key, text.size as usize, transaction);
+            builder.push_text(
+                &CommonItemProperties::new(rect, *space_and_clip),
+                rect,
+                &glyphs[..],
+                key,
+                color,
+                None,
+            );
+        }
+        builder.pop_stacking_context();
+    }
+
+    /// Draws the frame rate, the average time each phase took, and a graph
+    /// of the phases of recent frames over the top left of the window.
+    fn render_overlay(
+        &mut self,
+        pipeline_id: PipelineId,
+        builder: &mut DisplayListBuilder,
+        transaction: &mut Transaction,
+    ) {
+        let space_and_clip = SpaceAndClipInfo::root_scroll(pipeline_id);
+        let width = HISTORY_LEN as f32 * OVERLAY_BAR_WIDTH;
+
+        let total = Phase::ALL
+            .iter()
+            .map(|&phase| self.history.average(phase))
+            .sum::<Duration>();
+        let mut lines = vec![(
+            format!("{} FPS, {:.2} ms", self.history.fps(), millis(total)),
+            None,
+        )];
+        for (&phase, &color) in Phase::ALL.iter().zip(PHASE_COLORS.iter()) {
+            let average = self.history.average(phase);
+            lines.push((
+                format!("{} {:.2} ms", phase.name(), millis(average)),
+                Some(color),
+            ));
+        }
+
+        let height =
+            OVERLAY_PADDING * 3.0 + lines.len() as f32 * OVERLAY_LINE_HEIGHT + OVERLAY_GRAPH_HEIGHT;
+        let panel = LayoutRect::new(
+            point2(OVERLAY_MARGIN, OVERLAY_MARGIN),
+            size2(width + OVERLAY_PADDING * 2.0, height),
+        );
+        builder.push_rect(
+            &CommonItemProperties::new(panel, space_and_clip),
+            panel,
+            OVERLAY_BACKGROUND,
+        );
+
+        let left = OVERLAY_MARGIN + OVERLAY_PADDING;
+        let mut y = OVERLAY_MARGIN + OVERLAY_PADDING;
+        for (line, swatch) in lines {
+            let mut x = left;
+            if let Some(color) = swatch {
+                let size = OVERLAY_TEXT_SIZE * 0.75;
+                let rect = LayoutRect::new(
+                    point2(x, y + (OVERLAY_LINE_HEIGHT - size) / 2.0),
+                    size2(size, size),
                 );
-                for fragment in fragments {
-                    let glyphs = fragment
-                        .glyphs
-                        .iter()
-                        .map(|glyph| {
-                            let pos = position + glyph.offset.to_vector();
-                            GlyphInstance {
-                                index: glyph.index,
-                                point: pos * Scale::new(1.0),
-                            }
-                        })
-                        .collect::<Vec<_>>();
-                    let font_key = self.get_font(&fragment.font, transaction);
-                    let key = self.get_font_instance(font_key, size as usize, transaction);
-                    builder.push_text(
+                builder.push_rect(
+                    &CommonItemProperties::new(rect, space_and_clip),
+                    rect,
+                    color,
+                );
+                x += OVERLAY_TEXT_SIZE;
+            }
+            if let Some(text) = self.document.layout_line(&line, OVERLAY_TEXT_SIZE) {
+                let rect = LayoutRect::new(point2(x, y), size2(width, OVERLAY_LINE_HEIGHT));
+                self.render_text(
+                    builder,
+                    transaction,
+                    &space_and_clip,
+                    rect,
+                    &text,
+                    OVERLAY_TEXT,
+                );
+            }
+            y += OVERLAY_LINE_HEIGHT;
+        }
+
+        // Each frame is a bar with the phases stacked from the bottom.
+        let bottom = y + OVERLAY_PADDING + OVERLAY_GRAPH_HEIGHT;
+        for (index, timings) in self.history.timings().enumerate() {
+            let x = left + index as f32 * OVERLAY_BAR_WIDTH;
+            let mut top = bottom;
+            for (&phase, &color) in Phase::ALL.iter().zip(PHASE_COLORS.iter()) {
+                let height = (millis(timings.get(phase)) * OVERLAY_GRAPH_SCALE)
+                    .min(top - (bottom - OVERLAY_GRAPH_HEIGHT));
+                if height > 0.0 {
+                    top -= height;
+                    let rect = LayoutRect::new(point2(x, top), size2(OVERLAY_BAR_WIDTH, height));
+                    builder.push_rect(
                         &CommonItemProperties::new(rect, space_and_clip),
                         rect,
-                        &glyphs[..],
-                        key,
-                        color.into(),
-                        None,
+                        color,
                     );
                 }
-                builder.pop_stacking_context();
             }
         }
+
+        let budget = LayoutRect::new(
+            point2(left, bottom - millis(FRAME_BUDGET) * OVERLAY_GRAPH_SCALE),
+            size2(width, 1.0),
+        );
+        builder.push_rect(
+            &CommonItemProperties::new(budget, space_and_clip),
+            budget,
+            OVERLAY_BUDGET,
+        );
+    }
+
+    fn render_highlight(
+        &self,
+        pipeline_id: PipelineId,
+        builder: &mut DisplayListBuilder,
+        root: &LayoutTreeNode,
+        id: u64,
+    ) {
+        let (position, layout) = match find_layout(root, LogicalPoint::zero(), id) {
+            Some(found) => found,
+            None => return,
+        };
+        let values = self.document.computed_values(id);
+        let border = values.border.map(|side| side.width.get());
+        let border = LayoutSideOffsets::new(border.top, border.right, border.bottom, border.left);
+        let margin = layout_offsets(layout.margin);
+        let padding = values
+            .display
+            .block()
+            .map_or(LayoutSideOffsets::zero(), |block| {
+                layout_offsets(block.padding)
+            });
+
+        let border_box = Rect::new(position, layout.size) * Scale::new(1.0);
+        let margin_box = border_box.outer_rect(margin);
+        let padding_box = border_box.inner_rect(border);
+        let content_box = padding_box.inner_rect(padding);
+
+        let space_and_clip = SpaceAndClipInfo::root_scroll(pipeline_id);
+        push_frame(
+            builder,
+            &space_and_clip,
+            margin_box,
+            margin,
+            MARGIN_HIGHLIGHT,
+        );
+        push_frame(
+            builder,
+            &space_and_clip,
+            border_box,
+            border,
+            BORDER_HIGHLIGHT,
+        );
+        push_frame(
+            builder,
+            &space_and_clip,
+            padding_box,
+            padding,
+            PADDING_HIGHLIGHT,
+        );
+        builder.push_rect(
+            &CommonItemProperties::new(content_box, space_and_clip),
+            content_box,
+            CONTENT_HIGHLIGHT,
+        );
+    }
+
+    /// Draws the shown tooltip below and to the right of where the cursor
+    /// was, or flipped to the other side of it where it would leave the
+    /// window of `size`.
+    fn render_tooltip(
+        &mut self,
+        pipeline_id: PipelineId,
+        builder: &mut DisplayListBuilder,
+        transaction: &mut Transaction,
+        size: LayoutSize,
+    ) {
+        let cursor = match self.document.tooltip() {
+            Some((_, cursor)) => cursor,
+            None => return,
+        };
+        let tooltip = match self.document.get_tooltip_layout() {
+            Some(tooltip) => tooltip,
+            None => return,
+        };
+
+        let flip = |position: f32, extent: f32, available: f32| {
+            if position + TOOLTIP_OFFSET + extent > available && position >= extent {
+                position - extent
+            } else {
+                (position + TOOLTIP_OFFSET).min((available - extent).max(0.0))
+            }
+        };
+        let position = LogicalPoint::new(
+            flip(cursor.x, tooltip.size.width, size.width),
+            flip(cursor.y, tooltip.size.height, size.height),
+        );
+        self.render_child(pipeline_id, builder, transaction, position, &tooltip);
     }
 
-    pub fn render(&mut self) {
+    /// Lays out the open popup and places it below the node it belongs to,
+    /// or above if it doesn't fit below within `height`.
+    fn layout_popup(
+        &mut self,
+        root: &LayoutTreeNode,
+        height: f32,
+    ) -> Option<(LogicalPoint, EqualRc<LayoutTreeNode>)> {
+        let (position, anchor) = find_layout(root, LogicalPoint::zero(), self.document.popup()?)?;
+        let anchor_size = anchor.size;
+        let popup = self.document.get_popup_layout(anchor_size.width)?;
+        let below = position.y + anchor_size.height;
+        let y = if below + popup.size.height > height && position.y >= popup.size.height {
+            position.y - popup.size.height
+        } else {
+            below
+        };
+        Some((LogicalPoint::new(position.x, y), popup))
+    }
+
+    /// Renders a frame, returning how long each phase took. `app` is how
+    /// long running the app's components took, if that's why the frame
+    /// is being rendered.
+    pub fn render(&mut self, app: Duration) -> FrameTimings {
+        let mut timings = FrameTimings {
+            app,
+            ..FrameTimings::default()
+        };
         let client_size = self.client_size;
         let dpi_scale = Scale::new(self.dpi_scale);
         let content_size = client_size.to_f32() / dpi_scale;
@@ -316,7 +801,8 @@ impl Context {
         let mut builder = DisplayListBuilder::new(pipeline_id);
         let mut transaction = Transaction::new();
 
-        let root_layout = self.document.get_layout();
+        let root_layout = self.document.get_layout(&mut timings);
+        let span = Phase::DisplayList.enter();
 
         for layout in &root_layout.children {
             self.render_child(
@@ -328,6 +814,30 @@ impl Context {
             );
         }
 
+        // The open popup is drawn after the rest of the window, which puts
+        // it on top and makes hit testing find it first.
+        if let Some((position, popup)) = self.layout_popup(&root_layout, content_size.height) {
+            self.render_child(
+                pipeline_id,
+                &mut builder,
+                &mut transaction,
+                position,
+                &popup,
+            );
+        }
+
+        self.render_tooltip(pipeline_id, &mut builder, &mut transaction, content_size);
+
+        if let Some(id) = self.highlight {
+            self.render_highlight(pipeline_id, &mut builder, &root_layout, id);
+        }
+        if self.overlay {
+            self.render_overlay(pipeline_id, &mut builder, &mut transaction);
+        }
+        self.layout = Some(root_layout);
+
+        timings.display_list = span.exit();
+        let span = Phase::Render.enter();
         transaction.set_display_list(Epoch(0), None, content_size, builder.finalize(), true);
         transaction.set_root_pipeline(pipeline_id);
         transaction.generate_frame();
@@ -343,6 +853,10 @@ impl Context {
             let _ = renderer.render(client_size.to_i32());
             let _ = renderer.flush_pipeline_info();
         }
+        timings.render = span.exit();
+
+        self.history.push(timings);
+        timings
     }
 
     pub fn element_at(&mut self, position: LogicalPosition<f32>) -> Option<u64> {
@@ -2,5 +2,6 @@
 //! and processing user input queries against it.
 
 pub mod context;
+mod overlay;
 
 pub use context::Context;
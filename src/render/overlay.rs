@@ -0,0 +1,51 @@
+//! Keeps track of how long recent frames took, for the overlay that
+//! shows the frame rate and a graph of each phase's timings.
+
+use crate::dom::devtools::{FrameTimings, Phase};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How many frames are graphed.
+pub const HISTORY_LEN: usize = 120;
+
+#[derive(Default)]
+pub struct FrameHistory {
+    frames: VecDeque<(Instant, FrameTimings)>,
+}
+
+impl FrameHistory {
+    pub fn push(&mut self, timings: FrameTimings) {
+        if self.frames.len() == HISTORY_LEN {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((Instant::now(), timings));
+    }
+
+    /// The number of frames rendered in the last second. Frames are only
+    /// rendered when something changes, so this is zero while idle
+    /// rather than matching the display's refresh rate.
+    pub fn fps(&self) -> usize {
+        let now = Instant::now();
+        self.frames
+            .iter()
+            .filter(|(rendered, _)| now.duration_since(*rendered) < Duration::from_secs(1))
+            .count()
+    }
+
+    /// How long the phase took on average over the graphed frames.
+    pub fn average(&self, phase: Phase) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::default();
+        }
+        let total = self
+            .frames
+            .iter()
+            .map(|(_, timings)| timings.get(phase))
+            .sum::<Duration>();
+        total / self.frames.len() as u32
+    }
+
+    pub fn timings(&self) -> impl Iterator<Item = &FrameTimings> {
+        self.frames.iter().map(|(_, timings)| timings)
+    }
+}
@@ -0,0 +1,156 @@
+//! Exposes each window's accessibility tree over AT-SPI through
+//! AccessKit, so screen readers on Linux can read and operate the app.
+
+use crate::dom::accessibility::{AccessibilityNode, AccessibilityTree, Action, Role};
+use crate::runtime::UserEvent;
+use accesskit::{
+    kurbo::Rect, Action as AccessKitAction, ActionHandler, ActionRequest, CheckedState,
+    DefaultActionVerb, Node, NodeId, Role as AccessKitRole, Tree, TreeUpdate,
+};
+use log::warn;
+use std::num::NonZeroU128;
+use std::sync::{Arc, Mutex};
+use winit::{event_loop::EventLoopProxy, window::WindowId};
+
+/// Connects a window to AT-SPI. Does nothing when the accessibility bus
+/// isn't available.
+pub struct Adapter {
+    adapter: Option<accesskit_unix::Adapter>,
+}
+
+impl Adapter {
+    pub fn new(
+        window: WindowId,
+        tree: AccessibilityTree,
+        proxy: EventLoopProxy<UserEvent>,
+    ) -> Adapter {
+        let app_name = tree.root.name.clone().unwrap_or_default();
+        let handler = Handler {
+            window,
+            proxy: Mutex::new(proxy),
+        };
+        let adapter = accesskit_unix::Adapter::new(
+            app_name,
+            "moxie-native".to_owned(),
+            env!("CARGO_PKG_VERSION").to_owned(),
+            move || convert_tree(&tree),
+            Box::new(handler),
+        );
+        if adapter.is_none() {
+            warn!("Couldn't connect to the accessibility bus");
+        }
+        Adapter { adapter }
+    }
+
+    pub fn update(&self, tree: &AccessibilityTree) {
+        if let Some(ref adapter) = self.adapter {
+            adapter.update(convert_tree(tree));
+        }
+    }
+}
+
+/// DOM ids start at 0, which AccessKit doesn't allow.
+fn node_id(id: u64) -> NodeId {
+    NodeId(NonZeroU128::new(id as u128 + 1).unwrap())
+}
+
+fn convert_tree(tree: &AccessibilityTree) -> TreeUpdate {
+    let mut nodes = vec![];
+    let root = convert_node(&tree.root, &mut nodes);
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(root)),
+        focus: tree.focus.map(node_id),
+    }
+}
+
+fn convert_node(node: &AccessibilityNode, nodes: &mut Vec<(NodeId, Arc<Node>)>) -> NodeId {
+    let id = node_id(node.id);
+    let children = node
+        .children
+        .iter()
+        .map(|child| convert_node(child, nodes))
+        .collect();
+    let converted = Node {
+        role: match node.role {
+            Role::Window => AccessKitRole::Window,
+            Role::Group => AccessKitRole::GenericContainer,
+            Role::Button => AccessKitRole::Button,
+            Role::Checkbox => AccessKitRole::CheckBox,
+            Role::RadioButton => AccessKitRole::RadioButton,
+            Role::Switch => AccessKitRole::Switch,
+            Role::Slider => AccessKitRole::Slider,
+            Role::ComboBox => AccessKitRole::PopupButton,
+            Role::ListOption => AccessKitRole::ListBoxOption,
+            Role::TextArea => AccessKitRole::TextField,
+            Role::Dialog => AccessKitRole::Dialog,
+            Role::Text => AccessKitRole::StaticText,
+        },
+        bounds: node.bounds.map(|bounds| Rect {
+            x0: bounds.x as f64,
+            y0: bounds.y as f64,
+            x1: (bounds.x + bounds.width) as f64,
+            y1: (bounds.y + bounds.height) as f64,
+        }),
+        children,
+        name: node.name.clone().map(Into::into),
+        description: node.description.clone().map(Into::into),
+        value: node.value.clone().map(Into::into),
+        focusable: node.focusable,
+        checked_state: node.checked.map(|checked| {
+            if checked {
+                CheckedState::True
+            } else {
+                CheckedState::False
+            }
+        }),
+        numeric_value: node.range.map(|range| range.value),
+        min_numeric_value: node.range.map(|range| range.min),
+        max_numeric_value: node.range.map(|range| range.max),
+        numeric_value_step: node
+            .range
+            .map(|range| range.step)
+            .filter(|step| *step > 0.0),
+        actions: node
+            .actions
+            .iter()
+            .map(|action| match action {
+                Action::Focus => AccessKitAction::Focus,
+                Action::Activate => AccessKitAction::Default,
+                Action::Increment => AccessKitAction::Increment,
+                Action::Decrement => AccessKitAction::Decrement,
+            })
+            .collect(),
+        default_action_verb: match (node.actions.contains(&Action::Activate), node.checked) {
+            (false, _) => None,
+            (true, None) => Some(DefaultActionVerb::Click),
+            (true, Some(false)) => Some(DefaultActionVerb::Check),
+            (true, Some(true)) => Some(DefaultActionVerb::Uncheck),
+        },
+        ..Node::default()
+    };
+    nodes.push((id, Arc::new(converted)));
+    id
+}
+
+/// Passes actions from assistive technology to the event loop, since
+/// they arrive on another thread.
+struct Handler {
+    window: WindowId,
+    proxy: Mutex<EventLoopProxy<UserEvent>>,
+}
+
+impl ActionHandler for Handler {
+    fn do_action(&self, request: ActionRequest) {
+        let action = match request.action {
+            AccessKitAction::Focus => Action::Focus,
+            AccessKitAction::Default => Action::Activate,
+            AccessKitAction::Increment => Action::Increment,
+            AccessKitAction::Decrement => Action::Decrement,
+            _ => return,
+        };
+        let id = request.target.0.get() as u64 - 1;
+        let event = UserEvent::AccessibilityAction(self.window, id, action);
+        let _ = self.proxy.lock().unwrap().send_event(event);
+    }
+}
@@ -1,16 +1,22 @@
-use crate::dom::devtools::DevToolsRegistry;
+use crate::dom::accessibility::Action;
+use crate::dom::devtools::{new_token, DevToolsRegistry, DevToolsServer, DevToolsShared, Phase};
 use crate::dom::{App, Node};
+use crate::style::Stylesheet;
 use crate::util::outer_join::{outer_join, Joined};
-use log::{debug, info};
+use log::{debug, error, info};
 use moxie::runtime::Runtime as MoxieRuntime;
 use std::collections::HashMap;
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use winit::{
-    event::Event,
+    event::{Event, StartCause},
     event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget},
     window::WindowId,
 };
 
+#[cfg(all(feature = "accessibility", target_os = "linux"))]
+mod accessibility;
 mod window;
 
 /// Contains the event loop and the root component of the application.
@@ -18,9 +24,45 @@ pub struct Runtime {
     state: RuntimeState,
     windows: HashMap<WindowId, window::Window>,
     window_ids: Vec<WindowId>,
-    proxy: Option<EventLoopProxy<()>>,
+    proxy: Option<EventLoopProxy<UserEvent>>,
+    stylesheets: Vec<Arc<Stylesheet>>,
+    devtools: Arc<DevToolsShared>,
+    /// The port and token devtools in other processes connect with.
+    devtools_server: Option<(u16, String)>,
+    updates: UpdateHandle,
 }
 
+/// Lets other threads ask the runtime to update the app, for example
+/// when data it shows has arrived. Requests made before the runtime
+/// starts are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateHandle(Arc<Mutex<Option<EventLoopProxy<UserEvent>>>>);
+
+impl UpdateHandle {
+    pub fn request_update(&self) {
+        if let Some(ref proxy) = *self.0.lock().unwrap() {
+            let _ = proxy.send_event(UserEvent::UpdateRequested);
+        }
+    }
+}
+
+/// Events sent to the event loop from other threads.
+#[derive(Debug)]
+pub(crate) enum UserEvent {
+    /// Webrender has a frame ready.
+    Wakeup,
+    /// A watched stylesheet was reloaded, so windows need restyling.
+    StylesheetChanged,
+    /// Something the app shows changed outside of it, such as the style
+    /// of the node inspected by devtools, so it needs updating.
+    UpdateRequested,
+    /// Assistive technology asked for an action on a node in a window.
+    AccessibilityAction(WindowId, u64, Action),
+}
+
+/// How often watched stylesheets are checked for changes.
+const STYLESHEET_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 enum RuntimeState {
     Stopped {
         root_func: Box<dyn FnMut() -> Node<App> + 'static + Sync + Send>,
@@ -40,7 +82,8 @@ enum RuntimeEvent {
 
 /// Events sent from the runtime thread to the main thread.
 enum MainEvent {
-    UpdateRuntime(Node<App>),
+    /// The app updated, which took the given time.
+    UpdateRuntime(Node<App>, Duration),
     Shutdown,
 }
 
@@ -50,11 +93,13 @@ struct RuntimeMessageSender(Sender<MainEvent>);
 impl Runtime {
     /// Create a new runtime based on the application's root component.
     pub fn new(mut root: impl FnMut() -> Node<App> + 'static + Sync + Send) -> Runtime {
+        let devtools = Arc::new(DevToolsShared::default());
+        let shared = devtools.clone();
         Runtime {
             state: RuntimeState::Stopped {
                 root_func: Box::new(move || {
                     illicit::Layer::new()
-                        .offer(DevToolsRegistry::new())
+                        .offer(DevToolsRegistry::new(shared.clone()))
                         .enter(|| {
                             topo::call(|| {
                                 let registry = illicit::expect::<DevToolsRegistry>();
@@ -68,14 +113,42 @@ impl Runtime {
             windows: HashMap::new(),
             window_ids: vec![],
             proxy: None,
+            stylesheets: vec![],
+            devtools,
+            devtools_server: None,
+            updates: UpdateHandle::default(),
         }
     }
 
+    /// Reloads the stylesheet whenever its file changes while the app is
+    /// running, and restyles every window with the new styles. Files that
+    /// fail to parse are reported and the previous styles are kept. The
+    /// runtime keeps the sheet alive until it stops.
+    pub fn watch_stylesheet(&mut self, stylesheet: Arc<Stylesheet>) {
+        self.stylesheets.push(stylesheet);
+    }
+
+    /// Lets devtools running in another process, such as the viewer in
+    /// `moxie-native-devtools`, connect on the given port once the app
+    /// starts. Only connections from the same machine are accepted, and
+    /// they have to give the returned token, which is new for every run
+    /// of the app.
+    pub fn serve_devtools(&mut self, port: u16) -> String {
+        let token = new_token();
+        self.devtools_server = Some((port, token.clone()));
+        token
+    }
+
+    /// Returns a handle for requesting updates from other threads.
+    pub fn update_handle(&self) -> UpdateHandle {
+        self.updates.clone()
+    }
+
     /// Handle events
     fn process(
         &mut self,
-        event: Event<()>,
-        target: &EventLoopWindowTarget<()>,
+        event: Event<UserEvent>,
+        target: &EventLoopWindowTarget<UserEvent>,
         control_flow: &mut ControlFlow,
     ) {
         let mut did_process = false;
@@ -89,19 +162,47 @@ impl Runtime {
                 let res = window.process(event);
                 did_process = res;
             }
-            _ => *control_flow = ControlFlow::Wait,
+            Event::UserEvent(UserEvent::StylesheetChanged) => {
+                for window in self.windows.values_mut() {
+                    window.render();
+                }
+            }
+            Event::UserEvent(UserEvent::UpdateRequested) => did_process = true,
+            Event::UserEvent(UserEvent::AccessibilityAction(window_id, id, action)) => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    did_process = window.perform_action(id, action);
+                }
+            }
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                let now = Instant::now();
+                for window in self.windows.values_mut() {
+                    window.update_hover(now);
+                }
+            }
+            _ => (),
         }
         if did_process {
             self.update_runtime(target);
         }
         if let RuntimeState::Shutdown = &self.state {
             *control_flow = ControlFlow::Exit;
+        } else {
+            // Wake up when the next tooltip is due to show.
+            let deadline = self
+                .windows
+                .values()
+                .filter_map(|window| window.hover_deadline())
+                .min();
+            *control_flow = match deadline {
+                Some(deadline) => ControlFlow::WaitUntil(deadline),
+                None => ControlFlow::Wait,
+            };
         }
     }
 
     /// Updates the moxie runtime and reconciles the DOM changes,
     /// re-rendering if things have changed.
-    fn update_runtime(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+    fn update_runtime(&mut self, event_loop: &EventLoopWindowTarget<UserEvent>) {
         if let RuntimeState::Running {
             sender, receiver, ..
         } = &self.state
@@ -110,13 +211,13 @@ impl Runtime {
 
             if let Ok(event) = receiver.recv() {
                 match event {
-                    MainEvent::UpdateRuntime(app) => {
+                    MainEvent::UpdateRuntime(app, app_time) => {
                         let window_ids = self.window_ids.drain(..).collect::<Vec<_>>();
                         for joined in outer_join(app.children(), window_ids) {
                             match joined {
                                 Joined::Both(dom_window, window_id) => {
                                     let window = self.windows.get_mut(&window_id).unwrap();
-                                    window.set_dom_window(dom_window.clone());
+                                    window.set_dom_window(dom_window.clone(), app_time);
                                     window.render();
                                     self.window_ids.push(window_id);
                                 }
@@ -125,6 +226,8 @@ impl Runtime {
                                         dom_window.clone(),
                                         event_loop,
                                         self.proxy.as_ref().unwrap().clone(),
+                                        self.devtools.clone(),
+                                        app_time,
                                     );
                                     let id = window.window_id();
                                     self.windows.insert(id, window);
@@ -155,6 +258,10 @@ impl Runtime {
             state,
             windows,
             window_ids,
+            stylesheets,
+            devtools,
+            devtools_server,
+            updates,
             ..
         } = self;
 
@@ -169,9 +276,11 @@ impl Runtime {
                 while let Ok(event) = receiver_b.recv() {
                     match event {
                         RuntimeEvent::UpdateRuntime => {
+                            let span = Phase::App.enter();
                             let app = moxie_runtime.run_once(&mut root_func);
+                            let app_time = span.exit();
 
-                            match sender_b.send(MainEvent::UpdateRuntime(app)) {
+                            match sender_b.send(MainEvent::UpdateRuntime(app, app_time)) {
                                 Ok(_) => (),
                                 Err(err) => {
                                     debug!("Runtime thread send error: {}", err);
@@ -188,7 +297,33 @@ impl Runtime {
                 debug!("Runtime thread exit");
             });
 
-            let event_loop = EventLoop::new();
+            let event_loop = EventLoop::with_user_event();
+            *updates.0.lock().unwrap() = Some(event_loop.create_proxy());
+
+            if let Some((port, token)) = &devtools_server {
+                let server =
+                    DevToolsServer::start(*port, token.clone(), devtools.clone(), updates.clone());
+                match server {
+                    Ok(server) => devtools.serve(server),
+                    Err(err) => error!("Failed to start devtools server: {}", err),
+                }
+            }
+
+            if !stylesheets.is_empty() {
+                let proxy = event_loop.create_proxy();
+                let stylesheets = stylesheets.clone();
+                std::thread::spawn(move || loop {
+                    std::thread::sleep(STYLESHEET_POLL_INTERVAL);
+                    let mut changed = false;
+                    for stylesheet in &stylesheets {
+                        changed |= stylesheet.reload_if_modified();
+                    }
+                    if changed && proxy.send_event(UserEvent::StylesheetChanged).is_err() {
+                        debug!("Stylesheet watcher exit");
+                        break;
+                    }
+                });
+            }
 
             self = Runtime {
                 state: RuntimeState::Running {
@@ -198,6 +333,10 @@ impl Runtime {
                 windows,
                 window_ids,
                 proxy: Some(event_loop.create_proxy()),
+                stylesheets,
+                devtools,
+                devtools_server,
+                updates,
             };
 
             illicit::Layer::new()
@@ -1,12 +1,25 @@
-use crate::dom::{Node, Window as DomWindow};
+use crate::dom::accessibility::Action;
+use crate::dom::devtools::{DevToolsShared, FrameTimings};
+use crate::dom::events::Theme;
+use crate::dom::input::{InputEvent, Key};
+use crate::dom::{Icon as DomIcon, Node, Window as DomWindow};
+use crate::layout::LogicalPoint;
 use crate::render::Context;
+use crate::runtime::UserEvent;
 use gleam::gl;
 use glutin::{ContextBuilder, ContextWrapper, PossiblyCurrent};
-use log::debug;
+use log::{debug, trace, warn};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use winit::{
-    event::{ElementState, MouseButton, WindowEvent},
+    dpi::{LogicalPosition, LogicalSize},
+    event::{
+        ElementState, KeyboardInput, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+    },
     event_loop::{EventLoopProxy, EventLoopWindowTarget},
-    window::{Window as WinitWindow, WindowBuilder, WindowId},
+    window::{
+        Fullscreen, Icon, Theme as WinitTheme, Window as WinitWindow, WindowBuilder, WindowId,
+    },
 };
 
 /// Wrapper around a `winit::Window` and a `Context` for rendering the
@@ -14,18 +27,49 @@ use winit::{
 pub struct Window {
     gl_context: ContextWrapper<PossiblyCurrent, WinitWindow>,
     context: Context,
+    devtools: Arc<DevToolsShared>,
+    proxy: EventLoopProxy<UserEvent>,
+    /// The id of the DOM window whose layout was last passed to devtools.
+    published_layout: Option<u64>,
+    /// How long the app took to update before the next render.
+    app_time: Duration,
+    modifiers: ModifiersState,
+    /// Where the cursor last was, in logical pixels.
+    cursor: LogicalPosition<f32>,
+    #[cfg(all(feature = "accessibility", target_os = "linux"))]
+    accessibility: Option<super::accessibility::Adapter>,
 }
 
 impl Window {
     pub fn new(
         dom_window: Node<DomWindow>,
-        event_loop: &EventLoopWindowTarget<()>,
-        proxy: EventLoopProxy<()>,
+        event_loop: &EventLoopWindowTarget<UserEvent>,
+        proxy: EventLoopProxy<UserEvent>,
+        devtools: Arc<DevToolsShared>,
+        app_time: Duration,
     ) -> Window {
-        let window_builder = WindowBuilder::new()
-            .with_title(&dom_window.element().title[..])
-            .with_decorations(true)
+        let element = dom_window.element();
+        let mut window_builder = WindowBuilder::new()
+            .with_title(&element.title[..])
+            .with_resizable(element.resizable)
+            .with_maximized(element.maximized)
+            .with_fullscreen(fullscreen(element.fullscreen))
+            .with_always_on_top(element.always_on_top)
+            .with_decorations(element.decorations)
+            .with_window_icon(element.icon.as_ref().and_then(window_icon))
             .with_transparent(true);
+        if let Some(size) = element.size {
+            window_builder = window_builder.with_inner_size(logical_size(size));
+        }
+        if let Some(size) = element.min_size {
+            window_builder = window_builder.with_min_inner_size(logical_size(size));
+        }
+        if let Some(size) = element.max_size {
+            window_builder = window_builder.with_max_inner_size(logical_size(size));
+        }
+        if let Some(position) = element.position {
+            window_builder = window_builder.with_position(logical_position(position));
+        }
 
         let gl_context = ContextBuilder::new()
             .with_gl(glutin::GlRequest::GlThenGles {
@@ -47,44 +91,331 @@ impl Window {
             glutin::Api::WebGl => unimplemented!(),
         };
 
-        let mut context = Context::new(gl, gl_context.window(), proxy, dom_window);
-        context.render();
+        let mut context = Context::new(gl, gl_context.window(), proxy.clone(), dom_window);
+        let timings = context.render(app_time);
         gl_context.swap_buffers().unwrap();
 
-        Window {
+        let mut window = Window {
             gl_context,
             context,
-        }
+            devtools,
+            proxy,
+            published_layout: None,
+            app_time: Duration::default(),
+            modifiers: ModifiersState::empty(),
+            cursor: LogicalPosition::new(0.0, 0.0),
+            #[cfg(all(feature = "accessibility", target_os = "linux"))]
+            accessibility: None,
+        };
+        window.publish_devtools(&timings);
+        window.publish_accessibility();
+        window
     }
 
     pub fn window_id(&self) -> WindowId {
         self.gl_context.window().id()
     }
 
-    pub fn set_dom_window(&mut self, new_node: Node<DomWindow>) {
-        self.gl_context
-            .window()
-            .set_title(&new_node.element().title[..]);
+    /// Updates the DOM window, where `app_time` is how long the app took
+    /// to produce it.
+    pub fn set_dom_window(&mut self, new_node: Node<DomWindow>, app_time: Duration) {
+        self.app_time = app_time;
+        self.apply_properties(new_node.element());
         self.context.set_dom_window(new_node);
     }
 
+    /// Applies the properties of `new` that differ from the current DOM
+    /// window's, so that the user's changes to the window, like resizing
+    /// it, are only undone when the app changes that property.
+    fn apply_properties(&self, new: &DomWindow) {
+        let old = self.context.document.window().element();
+        let window = self.gl_context.window();
+        if old.title != new.title {
+            window.set_title(&new.title[..]);
+        }
+        if old.size != new.size {
+            if let Some(size) = new.size {
+                window.set_inner_size(logical_size(size));
+            }
+        }
+        if old.min_size != new.min_size {
+            window.set_min_inner_size(new.min_size.map(logical_size));
+        }
+        if old.max_size != new.max_size {
+            window.set_max_inner_size(new.max_size.map(logical_size));
+        }
+        if old.position != new.position {
+            if let Some(position) = new.position {
+                window.set_outer_position(logical_position(position));
+            }
+        }
+        if old.resizable != new.resizable {
+            window.set_resizable(new.resizable);
+        }
+        if old.maximized != new.maximized {
+            window.set_maximized(new.maximized);
+        }
+        if old.fullscreen != new.fullscreen {
+            window.set_fullscreen(fullscreen(new.fullscreen));
+        }
+        if old.always_on_top != new.always_on_top {
+            window.set_always_on_top(new.always_on_top);
+        }
+        if old.decorations != new.decorations {
+            window.set_decorations(new.decorations);
+        }
+        if old.icon != new.icon {
+            window.set_window_icon(new.icon.as_ref().and_then(window_icon));
+        }
+    }
+
     pub fn render(&mut self) {
-        self.context.render();
+        self.context.set_inspecting(self.devtools.picking());
+        self.context
+            .document
+            .set_forced_states(self.devtools.forced_states());
+        let timings = self.context.render(std::mem::take(&mut self.app_time));
         self.gl_context.swap_buffers().unwrap();
+        trace!("Rendered frame: {:?}", timings);
+        self.publish_devtools(&timings);
+        self.publish_accessibility();
+    }
+
+    /// Gives assistive technology the accessibility tree from the last
+    /// render, connecting to it the first time.
+    #[cfg(all(feature = "accessibility", target_os = "linux"))]
+    fn publish_accessibility(&mut self) {
+        let tree = match self.context.accessibility_tree() {
+            Some(tree) => tree,
+            None => return,
+        };
+        match self.accessibility {
+            Some(ref adapter) => adapter.update(&tree),
+            None => {
+                let adapter =
+                    super::accessibility::Adapter::new(self.window_id(), tree, self.proxy.clone());
+                self.accessibility = Some(adapter);
+            }
+        }
+    }
+
+    #[cfg(not(all(feature = "accessibility", target_os = "linux")))]
+    fn publish_accessibility(&mut self) {}
+
+    /// Performs an action requested by assistive technology on the node
+    /// with `id`, returning whether the app needs updating.
+    pub fn perform_action(&mut self, id: u64, action: Action) -> bool {
+        let processed = self.context.document.perform_action(id, action);
+        self.publish_events();
+        processed
+    }
+
+    /// When a tooltip is due to show, so that the event loop wakes up
+    /// for it.
+    pub fn hover_deadline(&self) -> Option<Instant> {
+        self.context.document.hover_deadline()
+    }
+
+    /// Shows the tooltip the cursor is resting over if it's due by `now`.
+    pub fn update_hover(&mut self, now: Instant) {
+        let shown = self.context.document.update_hover(now);
+        self.publish_events();
+        if shown {
+            self.render();
+        }
+    }
+
+    /// Passes the window's layout to devtools and runs their hooks for
+    /// the frame, along with publishing the style of the inspected node
+    /// if it's in this window and has changed.
+    fn publish_devtools(&mut self, timings: &FrameTimings) {
+        let restyled = self.context.document.take_restyled();
+        if self.devtools.registered() {
+            let layout = self.context.layout_snapshot();
+            let id = layout.as_ref().map(|layout| layout.node);
+            if let Some(previous) = self
+                .published_layout
+                .filter(|&previous| Some(previous) != id)
+            {
+                self.devtools.publish_layout(previous, None);
+            }
+            if let Some(ref layout) = layout {
+                self.devtools
+                    .publish_layout(layout.node, Some(layout.clone()));
+            }
+            self.published_layout = id;
+
+            let document = &self.context.document;
+            let window = document.window_id();
+            self.devtools.each(|tools, root| {
+                let outside = |id: u64| root.map_or(true, |root| !document.is_inside(id, root));
+                let nodes = restyled
+                    .iter()
+                    .copied()
+                    .filter(|&id| outside(id))
+                    .collect::<Vec<_>>();
+                if !nodes.is_empty() {
+                    tools.on_style_recalculated(window, &nodes);
+                }
+                match (layout.as_ref(), root) {
+                    (Some(layout), Some(root)) => {
+                        tools.on_layout_done(window, &layout.without(root))
+                    }
+                    (Some(layout), None) => tools.on_layout_done(window, layout),
+                    (None, _) => (),
+                }
+                tools.on_frame_rendered(window, timings);
+            });
+        }
+
+        let id = match self.devtools.inspected() {
+            Some(id) => id,
+            None => return,
+        };
+        if let Some(style) = self.context.document.node_style(id) {
+            if self.devtools.publish_style(id, style) {
+                let _ = self.proxy.send_event(UserEvent::UpdateRequested);
+            }
+        }
+    }
+
+    /// Tells devtools about the events dispatched to the document.
+    fn publish_events(&mut self) {
+        let dispatched = self.context.document.take_dispatched();
+        if dispatched.is_empty() || !self.devtools.registered() {
+            return;
+        }
+        let document = &self.context.document;
+        let window = document.window_id();
+        self.devtools.each(|tools, root| {
+            for (target, event) in &dispatched {
+                if root.map_or(true, |root| !document.is_inside(*target, root)) {
+                    tools.on_event_dispatched(window, *target, event);
+                }
+            }
+        });
+    }
+
+    /// Handles input while devtools are picking a node, which highlights
+    /// the node under the mouse and picks it when clicked. Returns `None`
+    /// for events that picking doesn't affect. Escape stops picking.
+    fn process_picking(&mut self, event: &WindowEvent) -> Option<bool> {
+        match *event {
+            WindowEvent::CursorMoved { position, .. } => {
+                let scale = self.gl_context.window().scale_factor();
+                let element = self.context.element_at(position.to_logical(scale));
+                if self.context.set_highlight(element) {
+                    self.render();
+                }
+                Some(false)
+            }
+            WindowEvent::CursorLeft { .. } => {
+                if self.context.set_highlight(None) {
+                    self.render();
+                }
+                Some(false)
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => match self.context.highlight() {
+                Some(id) => {
+                    self.devtools.pick(id);
+                    Some(true)
+                }
+                None => Some(false),
+            },
+            WindowEvent::MouseInput { .. } => Some(false),
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::Escape),
+                        ..
+                    },
+                ..
+            } => {
+                self.devtools.stop_picking();
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// Tells the node that has the pointer captured where the cursor is.
+    fn move_captured(&mut self) -> bool {
+        let document = &self.context.document;
+        let position = document
+            .captured()
+            .and_then(|id| self.context.pointer_position(id, self.cursor));
+        match position {
+            Some(position) => self.context.document.pointer_moved(position),
+            None => false,
+        }
     }
 
     pub fn process(&mut self, event: WindowEvent) -> bool {
+        let processed = self.process_event(event);
+        self.publish_events();
+        processed
+    }
+
+    fn process_event(&mut self, event: WindowEvent) -> bool {
+        if self.devtools.picking() {
+            if let Some(processed) = self.process_picking(&event) {
+                return processed;
+            }
+        }
         match event {
             WindowEvent::Resized(size) => {
                 debug!("resize {}x{}", size.width, size.height);
                 let factor = self.gl_context.window().scale_factor();
                 self.context.resize(size, factor as f32);
                 self.render();
+                let size = size.to_logical(factor);
+                return self.context.document.window_changed(InputEvent::Resized {
+                    width: size.width,
+                    height: size.height,
+                });
+            }
+            WindowEvent::Moved(position) => {
+                let factor = self.gl_context.window().scale_factor();
+                let position = position.to_logical(factor);
+                return self.context.document.window_changed(InputEvent::Moved {
+                    x: position.x,
+                    y: position.y,
+                });
+            }
+            WindowEvent::Focused(focused) => {
+                return self
+                    .context
+                    .document
+                    .window_changed(InputEvent::WindowFocused { focused });
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                return self
+                    .context
+                    .document
+                    .window_changed(InputEvent::ScaleFactorChanged { scale_factor });
+            }
+            WindowEvent::ThemeChanged(theme) => {
+                let theme = match theme {
+                    WinitTheme::Light => Theme::Light,
+                    WinitTheme::Dark => Theme::Dark,
+                };
+                return self
+                    .context
+                    .document
+                    .window_changed(InputEvent::ThemeChanged { theme });
             }
             WindowEvent::CursorMoved { position, .. } => {
                 let scale = self.gl_context.window().scale_factor();
-                let element = self.context.element_at(position.to_logical(scale));
-                return self.context.document.mouse_move(element);
+                self.cursor = position.to_logical(scale);
+                let element = self.context.element_at(self.cursor);
+                let position = LogicalPoint::new(self.cursor.x, self.cursor.y);
+                let hovered = self.context.document.mouse_move(element, position);
+                return self.move_captured() || hovered;
             }
             WindowEvent::MouseInput {
                 state,
@@ -92,14 +423,83 @@ impl Window {
                 ..
             } => {
                 let pressed = state == ElementState::Pressed;
-                return self.context.document.mouse_button1(pressed);
+                let processed = self.context.document.mouse_button1(pressed);
+                return self.move_captured() || processed;
             }
             WindowEvent::CursorLeft { .. } => {
-                return self.context.document.mouse_move(None);
+                let position = LogicalPoint::new(self.cursor.x, self.cursor.y);
+                return self.context.document.mouse_move(None, position);
             }
             WindowEvent::CloseRequested => {
                 return self.context.document.close_requested();
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::Tab),
+                        ..
+                    },
+                ..
+            } => {
+                return self.context.document.focus_next(self.modifiers.shift());
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::Return | VirtualKeyCode::Space),
+                        ..
+                    },
+                ..
+            } => {
+                let document = &mut self.context.document;
+                return match document.focused() {
+                    Some(id) => document.activate(id),
+                    None => false,
+                };
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::F3),
+                        ..
+                    },
+                ..
+            } => {
+                self.context.toggle_overlay();
+                self.render();
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(key) = key_for(key, self.modifiers) {
+                    let shift = self.modifiers.shift();
+                    return self.context.document.key_pressed(key, shift);
+                }
+            }
+            WindowEvent::ReceivedCharacter(c) => {
+                // Shortcuts are passed as keys instead, and control
+                // characters other than newlines aren't typed.
+                if self.modifiers.ctrl() || self.modifiers.logo() {
+                    return false;
+                }
+                let c = if c == '\r' { '\n' } else { c };
+                if c.is_control() && c != '\n' {
+                    return false;
+                }
+                return self.context.document.text_input(c.encode_utf8(&mut [0; 4]));
+            }
             _ => (),
         }
         false
@@ -108,6 +508,60 @@ impl Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
+        if let Some(id) = self.published_layout {
+            self.devtools.publish_layout(id, None);
+        }
         self.context.deinit();
     }
 }
+
+fn logical_size((width, height): (f32, f32)) -> LogicalSize<f32> {
+    LogicalSize::new(width, height)
+}
+
+fn logical_position((x, y): (f32, f32)) -> LogicalPosition<f32> {
+    LogicalPosition::new(x, y)
+}
+
+/// Fullscreen windows cover the monitor they're on, without changing its
+/// video mode.
+fn fullscreen(fullscreen: bool) -> Option<Fullscreen> {
+    if fullscreen {
+        Some(Fullscreen::Borderless(None))
+    } else {
+        None
+    }
+}
+
+fn window_icon(icon: &DomIcon) -> Option<Icon> {
+    Icon::from_rgba(icon.rgba().to_vec(), icon.width(), icon.height())
+        .map_err(|err| warn!("Couldn't use the window icon: {}", err))
+        .ok()
+}
+
+/// The keys passed to the focused element.
+fn key_for(key: VirtualKeyCode, modifiers: ModifiersState) -> Option<Key> {
+    if modifiers.ctrl() || modifiers.logo() {
+        return match key {
+            VirtualKeyCode::Z if modifiers.shift() => Some(Key::Redo),
+            VirtualKeyCode::Z => Some(Key::Undo),
+            VirtualKeyCode::Y => Some(Key::Redo),
+            VirtualKeyCode::A => Some(Key::SelectAll),
+            _ => None,
+        };
+    }
+    match key {
+        VirtualKeyCode::Left => Some(Key::Left),
+        VirtualKeyCode::Right => Some(Key::Right),
+        VirtualKeyCode::Up => Some(Key::Up),
+        VirtualKeyCode::Down => Some(Key::Down),
+        VirtualKeyCode::Home => Some(Key::Home),
+        VirtualKeyCode::End => Some(Key::End),
+        VirtualKeyCode::PageUp => Some(Key::PageUp),
+        VirtualKeyCode::PageDown => Some(Key::PageDown),
+        VirtualKeyCode::Escape => Some(Key::Escape),
+        VirtualKeyCode::Back => Some(Key::Backspace),
+        VirtualKeyCode::Delete => Some(Key::Delete),
+        _ => None,
+    }
+}
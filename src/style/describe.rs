@@ -0,0 +1,135 @@
+use super::{
+    Alignment, BlockValues, Border, BorderStyle, ComputedValues, Corners, Direction, DisplayType,
+    Edges, Visibility,
+};
+use crate::layout::{LogicalLength, LogicalSideOffsets};
+
+// Values are printed in the same syntax that styles are written in.
+
+fn length(length: LogicalLength) -> String {
+    format!("{} px", length.get())
+}
+
+fn optional_length(value: Option<LogicalLength>) -> String {
+    value.map_or_else(|| "auto".to_owned(), length)
+}
+
+fn alignment(alignment: Alignment) -> &'static str {
+    match alignment {
+        Alignment::Start => "start",
+        Alignment::Center => "center",
+        Alignment::End => "end",
+    }
+}
+
+fn edges(edges: Edges<String>) -> String {
+    if edges.left == edges.right && edges.left == edges.top && edges.left == edges.bottom {
+        edges.left
+    } else {
+        format!(
+            "Edges {{ left: {}, right: {}, top: {}, bottom: {} }}",
+            edges.left, edges.right, edges.top, edges.bottom
+        )
+    }
+}
+
+fn offsets(offsets: LogicalSideOffsets, auto: Edges<bool>) -> String {
+    let side = |value: f32, auto: bool| {
+        if auto {
+            "auto".to_owned()
+        } else {
+            length(LogicalLength::new(value))
+        }
+    };
+    edges(Edges {
+        left: side(offsets.left, auto.left),
+        right: side(offsets.right, auto.right),
+        top: side(offsets.top, auto.top),
+        bottom: side(offsets.bottom, auto.bottom),
+    })
+}
+
+fn border(border: Border) -> String {
+    let style = match border.style {
+        BorderStyle::None => "none",
+        BorderStyle::Solid => "solid",
+        BorderStyle::Double => "double",
+        BorderStyle::Dotted => "dotted",
+        BorderStyle::Dashed => "dashed",
+        BorderStyle::Hidden => "hidden",
+        BorderStyle::Groove => "groove",
+        BorderStyle::Ridge => "ridge",
+        BorderStyle::Inset => "inset",
+        BorderStyle::Outset => "outset",
+    };
+    format!(
+        "border({}, {}, {})",
+        length(border.width),
+        style,
+        border.color
+    )
+}
+
+fn corners(corners: Corners<LogicalLength>) -> String {
+    if corners.top_left == corners.top_right
+        && corners.top_left == corners.bottom_left
+        && corners.top_left == corners.bottom_right
+    {
+        length(corners.top_left)
+    } else {
+        format!(
+            "Corners {{ top_left: {}, top_right: {}, bottom_left: {}, bottom_right: {} }}",
+            length(corners.top_left),
+            length(corners.top_right),
+            length(corners.bottom_left),
+            length(corners.bottom_right)
+        )
+    }
+}
+
+fn describe_block(block: &BlockValues, values: &mut Vec<(&'static str, String)>) {
+    let direction = match block.direction {
+        Direction::Vertical => "vertical",
+        Direction::Horizontal => "horizontal",
+    };
+    values.push(("direction", direction.to_owned()));
+    values.push(("align_items", alignment(block.align_items).to_owned()));
+    if let Some(align_self) = block.align_self {
+        values.push(("align_self", alignment(align_self).to_owned()));
+    }
+    values.push(("margin", offsets(block.margin, block.margin_auto)));
+    values.push(("padding", offsets(block.padding, Edges::default())));
+    values.push(("width", optional_length(block.width)));
+    values.push(("height", optional_length(block.height)));
+}
+
+impl ComputedValues {
+    /// Lists every attribute's computed value by name, for devtools.
+    /// Attributes that only apply to blocks are left out for other
+    /// display types.
+    pub fn describe(&self) -> Vec<(&'static str, String)> {
+        let display = match self.display {
+            DisplayType::None => "none",
+            DisplayType::Inline(_) => "inline",
+            DisplayType::Block(_) => "block",
+            DisplayType::InlineBlock(_) => "inline_block",
+        };
+        let visibility = match self.visibility {
+            Visibility::Visible => "visible",
+            Visibility::Hidden => "hidden",
+        };
+        let mut values = vec![
+            ("display", display.to_owned()),
+            ("visibility", visibility.to_owned()),
+            ("text_size", length(self.text_size)),
+            ("text_color", self.text_color.to_string()),
+            ("background_color", self.background_color.to_string()),
+            ("border", edges(self.border.map(border))),
+            ("corner_radius", corners(self.corner_radius)),
+        ];
+        if let Some(block) = self.display.block() {
+            describe_block(block, &mut values);
+        }
+        values
+    }
+}
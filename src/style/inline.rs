@@ -0,0 +1,123 @@
+use super::{ComputedValues, Variables};
+use crate::style_impl::{Attribute, AttributeHasValue};
+use std::any::{type_name, Any};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Arc;
+
+trait Declaration: Debug + Send + Sync {
+    fn apply(&self, values: &mut ComputedValues);
+    fn as_any(&self) -> &dyn Any;
+    fn same_as(&self, other: &dyn Declaration) -> bool;
+}
+
+struct AttributeValue<Attr, Value> {
+    attribute: Attr,
+    value: Value,
+}
+
+impl<Attr, Value> Debug for AttributeValue<Attr, Value>
+where
+    Value: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = type_name::<Attr>();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        write!(f, "{}: {:?}", name, self.value)
+    }
+}
+
+impl<Attr, Value> Declaration for AttributeValue<Attr, Value>
+where
+    Attr: Attribute + AttributeHasValue<Value> + Send + Sync + 'static,
+    Value: Clone + PartialEq + Debug + Send + Sync + 'static,
+{
+    fn apply(&self, values: &mut ComputedValues) {
+        self.attribute.set(values, self.value.clone());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn same_as(&self, other: &dyn Declaration) -> bool {
+        match other.as_any().downcast_ref::<Self>() {
+            Some(other) => self.value == other.value,
+            None => false,
+        }
+    }
+}
+
+/// Style attributes set directly on an element from component code,
+/// for values that are only known at runtime. These are applied after
+/// the element's `style` list, so they take precedence over it.
+///
+/// ```rs
+/// InlineStyle::new()
+///     .set(style_impl::attribute::width(), style_impl::pixels(progress * 200.0))
+///     .set(style_impl::attribute::background_color(), accent_color)
+/// ```
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct InlineStyle {
+    declarations: Declarations,
+    variables: Variables,
+}
+
+impl InlineStyle {
+    pub fn new() -> InlineStyle {
+        InlineStyle::default()
+    }
+
+    /// Adds a declaration. Later declarations override earlier ones for
+    /// the same attribute.
+    pub fn set<Attr, Value>(mut self, attribute: Attr, value: Value) -> InlineStyle
+    where
+        Attr: Attribute + AttributeHasValue<Value> + Send + Sync + 'static,
+        Value: Clone + PartialEq + Debug + Send + Sync + 'static,
+    {
+        self.declarations
+            .0
+            .push(Arc::new(AttributeValue { attribute, value }));
+        self
+    }
+
+    /// Declares a style variable on the element, which it and its
+    /// descendants see through `var(name)`.
+    pub fn var(mut self, name: &str, value: impl Into<super::Value>) -> InlineStyle {
+        self.variables = self.variables.set(name, value);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarations.0.is_empty() && self.variables == Variables::default()
+    }
+
+    pub(crate) fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    pub(crate) fn apply(&self, values: &mut ComputedValues) {
+        for declaration in &self.declarations.0 {
+            declaration.apply(values);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Declarations(Vec<Arc<dyn Declaration>>);
+
+impl PartialEq for Declarations {
+    fn eq(&self, other: &Declarations) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(other.0.iter())
+                .all(|(left, right)| left.same_as(&**right))
+    }
+}
+
+impl Debug for Declarations {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
@@ -2,6 +2,16 @@ use crate::dom::element::ElementState;
 use crate::layout::{LogicalLength, LogicalSideOffsets};
 use crate::Color;
 use std::any::TypeId;
+use std::borrow::Cow;
+
+mod describe;
+mod inline;
+mod sheet;
+mod variables;
+
+pub use inline::InlineStyle;
+pub use sheet::{LoadError, ParseError, Stylesheet};
+pub use variables::{Value, Variables};
 
 /// Specifies which direction layout should be performed in.
 #[derive(Clone, PartialEq, Copy, Debug)]
@@ -10,13 +20,27 @@ pub(crate) enum Direction {
     Horizontal,
 }
 
+/// Where a child is placed along the cross axis of its parent's
+/// direction.
+#[derive(Clone, PartialEq, Copy, Debug)]
+pub(crate) enum Alignment {
+    Start,
+    Center,
+    End,
+}
+
 #[derive(Default, PartialEq, Clone, Copy, Debug)]
 pub(crate) struct InlineValues {}
 
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub(crate) struct BlockValues {
     pub direction: Direction,
+    pub align_items: Alignment,
+    pub align_self: Option<Alignment>,
     pub margin: LogicalSideOffsets,
+    /// Sides with `margin: auto`, which take up the remaining free
+    /// space on that side.
+    pub margin_auto: Edges<bool>,
     pub padding: LogicalSideOffsets,
     pub width: Option<LogicalLength>,
     pub height: Option<LogicalLength>,
@@ -30,7 +54,10 @@ impl Default for BlockValues {
     fn default() -> Self {
         BlockValues {
             direction: Direction::Vertical,
+            align_items: Alignment::Start,
+            align_self: None,
             margin: LogicalSideOffsets::new_all_same(0.0),
+            margin_auto: Edges::default(),
             padding: LogicalSideOffsets::new_all_same(0.0),
             width: None,
             height: None,
@@ -44,14 +71,48 @@ impl Default for BlockValues {
 
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub(crate) enum Display {
+    None,
     Inline,
     Block,
+    InlineBlock,
 }
 
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub(crate) enum DisplayType {
+    /// The element and its subtree don't generate any layout.
+    None,
     Inline(InlineValues),
     Block(BlockValues),
+    /// Laid out as a block internally, but placed on the line like a
+    /// single inline item.
+    InlineBlock(BlockValues),
+}
+
+impl DisplayType {
+    /// Returns the block values for both `Block` and `InlineBlock`.
+    pub fn block(&self) -> Option<&BlockValues> {
+        match self {
+            DisplayType::Block(ref block) | DisplayType::InlineBlock(ref block) => Some(block),
+            _ => None,
+        }
+    }
+
+    pub fn block_mut(&mut self) -> Option<&mut BlockValues> {
+        match self {
+            DisplayType::Block(ref mut block) | DisplayType::InlineBlock(ref mut block) => {
+                Some(block)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the element's own box is painted and hit tested. Unlike
+/// `display: none`, hidden elements still take up space.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(crate) enum Visibility {
+    Visible,
+    Hidden,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
@@ -147,6 +208,7 @@ pub struct ComputedValues {
     pub(crate) display: DisplayType,
     pub(crate) text_size: LogicalLength,
     pub(crate) text_color: Color,
+    pub(crate) visibility: Visibility,
     pub(crate) background_color: Color,
     pub(crate) border: Edges<Border>,
     pub(crate) corner_radius: Corners<LogicalLength>,
@@ -158,6 +220,7 @@ impl Default for ComputedValues {
             display: DisplayType::Block(BlockValues::default()),
             text_size: LogicalLength::new(16.0),
             text_color: Color::black(),
+            visibility: Visibility::Visible,
             background_color: Color::clear(),
             corner_radius: Corners::new_all_same(LogicalLength::new(0.0)),
             border: Edges::new_all_same(Border {
@@ -169,22 +232,106 @@ impl Default for ComputedValues {
     }
 }
 
+/// The view of a DOM node that selectors are evaluated against.
 pub trait NodeSelect {
     fn has_type(&self, ty: TypeId) -> bool;
     fn has_state(&self, state: ElementState) -> bool;
+    fn has_class(&self, name: &str) -> bool;
+    /// The element's name as used in markup, like `"view"`.
+    fn element_name(&self) -> &'static str;
+    fn parent(&self) -> Option<&dyn NodeSelect>;
+    /// Index of this node among its parent's element children, ignoring
+    /// text.
+    fn sibling_index(&self) -> usize;
+    /// Number of element children the parent has.
+    fn sibling_count(&self) -> usize;
 }
 
-impl dyn NodeSelect {
+impl<'a> dyn NodeSelect + 'a {
     pub fn is_type<Ty: 'static>(&self) -> bool {
         self.has_type(TypeId::of::<Ty>())
     }
+
+    pub fn matches_state(&self, state: impl StateSelector) -> bool {
+        state.matches(self)
+    }
+
+    /// Iterates over the parent chain, starting at the parent.
+    pub fn ancestors(&self) -> Ancestors {
+        Ancestors {
+            next: self.parent(),
+        }
+    }
+}
+
+pub struct Ancestors<'a> {
+    next: Option<&'a dyn NodeSelect>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = &'a dyn NodeSelect;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.parent();
+        Some(node)
+    }
+}
+
+/// Anything that can be used in a `state:` selector.
+pub trait StateSelector {
+    fn matches(&self, node: &dyn NodeSelect) -> bool;
+}
+
+impl StateSelector for ElementState {
+    fn matches(&self, node: &dyn NodeSelect) -> bool {
+        node.has_state(*self)
+    }
+}
+
+/// Structural states, which depend on the node's position among its
+/// siblings.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Structural {
+    FirstChild,
+    LastChild,
+    OnlyChild,
+    /// Matches the `a*n + b`th child (1-based) for any `n >= 0`, like
+    /// CSS's `:nth-child()`.
+    NthChild {
+        a: i32,
+        b: i32,
+    },
+}
+
+impl StateSelector for Structural {
+    fn matches(&self, node: &dyn NodeSelect) -> bool {
+        let index = node.sibling_index() as i32;
+        let count = node.sibling_count() as i32;
+        match *self {
+            Structural::FirstChild => index == 0,
+            Structural::LastChild => index + 1 == count,
+            Structural::OnlyChild => count == 1,
+            Structural::NthChild { a, b } => {
+                let position = index + 1;
+                if a == 0 {
+                    position == b
+                } else {
+                    let n = position - b;
+                    n % a == 0 && n / a >= 0
+                }
+            }
+        }
+    }
 }
 
 pub type Selector = fn(&dyn NodeSelect) -> bool;
 pub type ApplyFunc = fn(&mut ComputedValues);
+pub type DeclareFunc = fn(&mut Variables);
 pub type GetAttributes = fn() -> Vec<(&'static str, String)>;
 
 pub struct Attributes {
+    pub variables: DeclareFunc,
     pub apply: ApplyFunc,
     pub get_attributes: GetAttributes,
 }
@@ -197,6 +344,8 @@ impl std::fmt::Debug for Attributes {
 
 pub struct SubStyle {
     pub selector: Selector,
+    /// The selector as written in the style, for devtools.
+    pub selector_text: &'static str,
     pub attributes: Attributes,
 }
 
@@ -217,21 +366,374 @@ pub struct StyleData {
     pub line: u32,
 }
 
+/// One block of declarations from a style, as shown by devtools.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StyleRule {
+    /// The name of the style the rule is from.
+    pub style: String,
+    /// The sub-style's selector, or `None` for the style's own
+    /// declarations.
+    pub selector: Option<String>,
+    /// The name and value of each declaration, as written in the style.
+    /// Variables are named like `var(gap)`.
+    pub attributes: Vec<(String, String)>,
+}
+
+impl StyleRule {
+    fn from_attributes(style: &str, selector: Option<&str>, attributes: &Attributes) -> StyleRule {
+        StyleRule {
+            style: style.to_owned(),
+            selector: selector.map(str::to_owned),
+            attributes: (attributes.get_attributes)()
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
-pub struct Style(pub &'static StyleData);
+enum StyleSource {
+    Static(&'static StyleData),
+    /// A style in a stylesheet, by the sheet's id and the style's index
+    /// in its names.
+    Sheet(u64, usize),
+}
+
+/// A handle to a style, either defined with `define_style!` or loaded
+/// at runtime through a `Stylesheet`.
+///
+/// Since 0.2 its field is private. Styles made from a `StyleData` by
+/// hand are built with `Style::new` instead of `Style(&DATA)`.
+#[derive(Copy, Clone, Debug)]
+pub struct Style(StyleSource);
 
 impl Style {
-    pub fn name(self) -> &'static str {
-        self.0.name
+    pub const fn new(data: &'static StyleData) -> Style {
+        Style(StyleSource::Static(data))
+    }
+
+    /// The name of the style. Styles from a stylesheet that was dropped
+    /// have an empty name.
+    pub fn name(self) -> Cow<'static, str> {
+        match self.0 {
+            StyleSource::Static(data) => Cow::Borrowed(data.name),
+            StyleSource::Sheet(id, index) => Stylesheet::find(id)
+                .map_or(Cow::Borrowed(""), |sheet| {
+                    sheet.name(index).to_string().into()
+                }),
+        }
+    }
+
+    pub fn file(self) -> (Cow<'static, str>, u32) {
+        match self.0 {
+            StyleSource::Static(data) => (Cow::Borrowed(data.file), data.line),
+            StyleSource::Sheet(id, index) => Stylesheet::find(id)
+                .map_or((Cow::Borrowed(""), 0), |sheet| {
+                    (sheet.file().to_owned().into(), sheet.line(index))
+                }),
+        }
+    }
+
+    /// Returns the style's own declarations followed by every sub-style,
+    /// whether or not it would match.
+    pub fn rules(self) -> Vec<StyleRule> {
+        self.collect_rules(None)
+    }
+
+    /// Returns the style's own declarations followed by the sub-styles
+    /// that match the node, in the order they're applied.
+    pub fn matched_rules(self, node: &dyn NodeSelect) -> Vec<StyleRule> {
+        self.collect_rules(Some(node))
     }
 
-    pub fn file(self) -> (&'static str, u32) {
-        (self.0.file, self.0.line)
+    fn collect_rules(self, node: Option<&dyn NodeSelect>) -> Vec<StyleRule> {
+        match self.0 {
+            StyleSource::Static(data) => {
+                let mut rules = vec![StyleRule::from_attributes(
+                    data.name,
+                    None,
+                    &data.attributes,
+                )];
+                for sub_style in data.sub_styles {
+                    if node.map_or(true, |node| (sub_style.selector)(node)) {
+                        rules.push(StyleRule::from_attributes(
+                            data.name,
+                            Some(sub_style.selector_text),
+                            &sub_style.attributes,
+                        ));
+                    }
+                }
+                rules
+            }
+            StyleSource::Sheet(id, index) => {
+                Stylesheet::find(id).map_or_else(Vec::new, |sheet| sheet.rules(index, node))
+            }
+        }
+    }
+
+    /// Changes whenever the style's definition does, which only happens
+    /// when a stylesheet is reloaded.
+    pub(crate) fn generation(self) -> usize {
+        match self.0 {
+            StyleSource::Static(_) => 0,
+            StyleSource::Sheet(id, _) => Stylesheet::find(id).map_or(0, |sheet| sheet.generation()),
+        }
+    }
+
+    /// Declares the style's variables, including those of the sub-styles
+    /// that match the node.
+    pub(crate) fn declare_variables(self, node: &dyn NodeSelect, variables: &mut Variables) {
+        match self.0 {
+            StyleSource::Static(data) => {
+                (data.attributes.variables)(variables);
+
+                for sub_style in data.sub_styles {
+                    if (sub_style.selector)(node) {
+                        (sub_style.attributes.variables)(variables);
+                    }
+                }
+            }
+            StyleSource::Sheet(id, index) => {
+                if let Some(sheet) = Stylesheet::find(id) {
+                    sheet.declare_variables(index, node, variables);
+                }
+            }
+        }
+    }
+
+    /// Applies the style's attributes, followed by those of the
+    /// sub-styles that match the node.
+    pub(crate) fn apply(self, node: &dyn NodeSelect, values: &mut ComputedValues) {
+        match self.0 {
+            StyleSource::Static(data) => {
+                (data.attributes.apply)(values);
+
+                for sub_style in data.sub_styles {
+                    if (sub_style.selector)(node) {
+                        (sub_style.attributes.apply)(values);
+                    }
+                }
+            }
+            StyleSource::Sheet(id, index) => {
+                if let Some(sheet) = Stylesheet::find(id) {
+                    sheet.apply(index, node, values);
+                }
+            }
+        }
     }
 }
 
 impl PartialEq for Style {
     fn eq(&self, other: &Style) -> bool {
-        std::ptr::eq(self.0 as *const StyleData, other.0 as *const StyleData)
+        match (self.0, other.0) {
+            (StyleSource::Static(left), StyleSource::Static(right)) => std::ptr::eq(left, right),
+            (StyleSource::Sheet(left, left_index), StyleSource::Sheet(right, right_index)) => {
+                left == right && left_index == right_index
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The value of the `style` attribute. Styles are applied in order, so
+/// later styles override the attributes set by earlier ones.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct StyleList(Vec<Style>);
+
+impl StyleList {
+    pub fn new() -> StyleList {
+        StyleList(vec![])
+    }
+
+    pub fn as_slice(&self) -> &[Style] {
+        &self.0[..]
+    }
+}
+
+impl From<Style> for StyleList {
+    fn from(style: Style) -> StyleList {
+        StyleList(vec![style])
+    }
+}
+
+impl From<Option<Style>> for StyleList {
+    fn from(style: Option<Style>) -> StyleList {
+        StyleList(style.into_iter().collect())
+    }
+}
+
+impl From<Vec<Style>> for StyleList {
+    fn from(styles: Vec<Style>) -> StyleList {
+        StyleList(styles)
+    }
+}
+
+impl From<&[Style]> for StyleList {
+    fn from(styles: &[Style]) -> StyleList {
+        StyleList(styles.to_vec())
+    }
+}
+
+impl<const N: usize> From<[Style; N]> for StyleList {
+    fn from(styles: [Style; N]) -> StyleList {
+        StyleList(styles.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestNode<'a> {
+        classes: &'static [&'static str],
+        index: usize,
+        count: usize,
+        parent: Option<&'a TestNode<'a>>,
+    }
+
+    impl<'a> TestNode<'a> {
+        fn new(classes: &'static [&'static str], parent: Option<&'a TestNode<'a>>) -> Self {
+            TestNode {
+                classes,
+                index: 0,
+                count: 1,
+                parent,
+            }
+        }
+
+        /// The node at `position` (1-based) among `count` siblings.
+        fn nth(position: usize, count: usize) -> Self {
+            TestNode {
+                index: position - 1,
+                count,
+                ..TestNode::new(&[], None)
+            }
+        }
+    }
+
+    impl<'a> NodeSelect for TestNode<'a> {
+        fn has_type(&self, _ty: TypeId) -> bool {
+            false
+        }
+
+        fn has_state(&self, _state: ElementState) -> bool {
+            false
+        }
+
+        fn has_class(&self, name: &str) -> bool {
+            self.classes.contains(&name)
+        }
+
+        fn element_name(&self) -> &'static str {
+            "view"
+        }
+
+        fn parent(&self) -> Option<&dyn NodeSelect> {
+            self.parent.map(|parent| parent as &dyn NodeSelect)
+        }
+
+        fn sibling_index(&self) -> usize {
+            self.index
+        }
+
+        fn sibling_count(&self) -> usize {
+            self.count
+        }
+    }
+
+    /// The 1-based positions among `count` siblings that `state` matches.
+    fn matching(state: Structural, count: usize) -> Vec<usize> {
+        (1..=count)
+            .filter(|&position| {
+                let node = TestNode::nth(position, count);
+                (&node as &dyn NodeSelect).matches_state(state)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_first_last_and_only_children() {
+        assert_eq!(matching(Structural::FirstChild, 3), vec![1]);
+        assert_eq!(matching(Structural::LastChild, 3), vec![3]);
+        assert_eq!(matching(Structural::OnlyChild, 3), Vec::<usize>::new());
+        assert_eq!(matching(Structural::FirstChild, 1), vec![1]);
+        assert_eq!(matching(Structural::LastChild, 1), vec![1]);
+        assert_eq!(matching(Structural::OnlyChild, 1), vec![1]);
+    }
+
+    #[test]
+    fn matches_odd_and_even_children() {
+        assert_eq!(
+            matching(Structural::NthChild { a: 2, b: 1 }, 6),
+            vec![1, 3, 5]
+        );
+        assert_eq!(
+            matching(Structural::NthChild { a: 2, b: 0 }, 6),
+            vec![2, 4, 6]
+        );
+    }
+
+    #[test]
+    fn matches_a_single_child_without_a_step() {
+        assert_eq!(matching(Structural::NthChild { a: 0, b: 3 }, 5), vec![3]);
+        assert_eq!(
+            matching(Structural::NthChild { a: 0, b: 0 }, 5),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            matching(Structural::NthChild { a: 0, b: 7 }, 5),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn matches_leading_children_with_a_negative_step() {
+        assert_eq!(
+            matching(Structural::NthChild { a: -1, b: 3 }, 6),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            matching(Structural::NthChild { a: -2, b: 5 }, 8),
+            vec![1, 3, 5]
+        );
+        assert_eq!(
+            matching(Structural::NthChild { a: -1, b: 0 }, 3),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn skips_children_before_the_offset() {
+        assert_eq!(matching(Structural::NthChild { a: 2, b: 5 }, 8), vec![5, 7]);
+        assert_eq!(
+            matching(Structural::NthChild { a: 3, b: 4 }, 10),
+            vec![4, 7, 10]
+        );
+    }
+
+    #[test]
+    fn matches_parent_and_ancestor_combinators() {
+        // These are the selectors `define_style!` generates for
+        // `parent(class: a)` and `ancestor(class: a)`.
+        let parent: Selector = |node| node.parent().map_or(false, |node| node.has_class("a"));
+        let ancestor: Selector = |node| node.ancestors().any(|node| node.has_class("a"));
+
+        let root = TestNode::new(&["a"], None);
+        let child = TestNode::new(&["b"], Some(&root));
+        let grandchild = TestNode::new(&[], Some(&child));
+
+        assert!(!parent(&root));
+        assert!(!ancestor(&root));
+        assert!(parent(&child));
+        assert!(ancestor(&child));
+        assert!(!parent(&grandchild));
+        assert!(ancestor(&grandchild));
+
+        let classes = (&grandchild as &dyn NodeSelect)
+            .ancestors()
+            .map(|node| node.has_class("b"))
+            .collect::<Vec<_>>();
+        assert_eq!(classes, vec![true, false]);
     }
 }
@@ -0,0 +1,360 @@
+use super::parse::{
+    Condition, Declaration, Expr, Func, LengthUnit, Location, Operator, ParseError, State,
+    StyleDecl,
+};
+use crate::style::{BorderStyle, ComputedValues, NodeSelect, Value, Variables};
+use crate::style_impl::attribute::{self, AnyValue};
+use crate::style_impl::types::{Corners, Edges};
+use crate::style_impl::var::FromValue;
+use crate::style_impl::{func, keyword, pixels, view_height, view_width, Length};
+use crate::Color;
+use std::iter;
+
+impl Condition {
+    pub(super) fn matches(&self, node: &dyn NodeSelect) -> bool {
+        match self {
+            Condition::Element(name) => node.element_name() == *name,
+            Condition::State(State::Element(state)) => node.matches_state(*state),
+            Condition::State(State::Structural(state)) => node.matches_state(*state),
+            Condition::Class(name) => node.has_class(name),
+            Condition::Parent(inner) => node.parent().map_or(false, |node| inner.matches(node)),
+            Condition::Ancestor(inner) => node.ancestors().any(|node| inner.matches(node)),
+            Condition::Not(inner) => !inner.matches(node),
+            Condition::And(left, right) => left.matches(node) && right.matches(node),
+            Condition::Or(left, right) => left.matches(node) || right.matches(node),
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, String>;
+
+fn convert<T>(value: &Value, expected: &str) -> Result<T>
+where
+    T: FromValue,
+{
+    T::from_value(value).ok_or_else(|| format!("expected {}, found {:?}", expected, value))
+}
+
+fn border_style(value: &Value) -> Result<BorderStyle> {
+    macro_rules! any_of {
+        ( $( $class:ident ),+ ) => {
+            $(
+                if let Some(keyword) = keyword::$class::from_value(value) {
+                    return Ok(keyword.into());
+                }
+            )+
+        };
+    }
+    any_of!(None, Solid, Double, Dotted, Dashed, Hidden, Groove, Ridge, Inset, Outset);
+    Err(format!("expected a border style, found {:?}", value))
+}
+
+fn lookup_var(name: &str) -> Option<Value> {
+    illicit::get::<Variables>()
+        .ok()
+        .and_then(|variables| variables.get(name).cloned())
+}
+
+/// Evaluates a value that is allowed to be missing, which is the case
+/// for a `var()` without a fallback that makes up the whole value.
+fn eval_optional(expr: &Expr) -> Result<Option<Value>> {
+    match expr {
+        Expr::Var(name, None) => Ok(lookup_var(name)),
+        expr => eval(expr).map(Some),
+    }
+}
+
+fn eval(expr: &Expr) -> Result<Value> {
+    match expr {
+        Expr::Number(value) => Ok(Value::Number(*value)),
+        Expr::Keyword(name) => Ok(Value::Keyword(*name)),
+        Expr::Color(color) => Ok(Value::Color(*color)),
+        Expr::Length(unit, expr) => {
+            let value = convert::<f64>(&eval(expr)?, "a number")?;
+            let length = match unit {
+                LengthUnit::Pixels => pixels(value),
+                LengthUnit::ViewWidth => view_width(value),
+                LengthUnit::ViewHeight => view_height(value),
+            };
+            Ok(Value::Length(length))
+        }
+        Expr::Binary(left, oper, right) => {
+            let left = eval(left)?;
+            let right = eval(right)?;
+            match (left, oper, right) {
+                (Value::Number(left), Operator::Add, Value::Number(right)) => {
+                    Ok(Value::Number(left + right))
+                }
+                (Value::Number(left), Operator::Sub, Value::Number(right)) => {
+                    Ok(Value::Number(left - right))
+                }
+                (Value::Number(left), Operator::Mul, Value::Number(right)) => {
+                    Ok(Value::Number(left * right))
+                }
+                (Value::Number(left), Operator::Div, Value::Number(right)) => {
+                    Ok(Value::Number(left / right))
+                }
+                (Value::Length(left), Operator::Add, Value::Length(right)) => {
+                    Ok(Value::Length(left + right))
+                }
+                (Value::Length(left), Operator::Sub, Value::Length(right)) => {
+                    Ok(Value::Length(left - right))
+                }
+                (Value::Length(left), Operator::Mul, Value::Number(right)) => {
+                    Ok(Value::Length(left * right))
+                }
+                (Value::Number(left), Operator::Mul, Value::Length(right)) => {
+                    Ok(Value::Length(left * right))
+                }
+                (Value::Length(left), Operator::Div, Value::Number(right)) => {
+                    Ok(Value::Length(left / right))
+                }
+                (Value::Length(_), Operator::Mul, Value::Length(_)) => {
+                    Err("can't multiply two lengths, one side must be a number".to_owned())
+                }
+                (_, Operator::Div, Value::Length(_)) => Err("can't divide by a length".to_owned()),
+                (left, _, right) => Err(format!(
+                    "can't combine {:?} and {:?}, expected two numbers or two lengths",
+                    left, right
+                )),
+            }
+        }
+        Expr::Neg(expr) => match eval(expr)? {
+            Value::Number(value) => Ok(Value::Number(-value)),
+            Value::Length(value) => Ok(Value::Length(-value)),
+            value => Err(format!("expected a number or a length, found {:?}", value)),
+        },
+        Expr::Edges(edges) => {
+            let field = |field: &Option<Expr>| match field {
+                Some(expr) => eval_optional(expr),
+                None => Ok(None),
+            };
+            Ok(Value::Edges(Box::new(Edges {
+                left: field(&edges.left)?,
+                right: field(&edges.right)?,
+                top: field(&edges.top)?,
+                bottom: field(&edges.bottom)?,
+            })))
+        }
+        Expr::Corners(corners) => {
+            let field = |field: &Option<Expr>| match field {
+                Some(expr) => eval_optional(expr),
+                None => Ok(None),
+            };
+            Ok(Value::Corners(Box::new(Corners {
+                top_left: field(&corners.top_left)?,
+                top_right: field(&corners.top_right)?,
+                bottom_left: field(&corners.bottom_left)?,
+                bottom_right: field(&corners.bottom_right)?,
+            })))
+        }
+        Expr::Call(function, args) => {
+            let args = args.iter().map(eval).collect::<Result<Vec<_>>>()?;
+            let number = |value: &Value| convert::<f64>(value, "a number");
+            let color = |value: &Value| convert::<Color>(value, "a color");
+            let length = |value: &Value| convert::<Length>(value, "a length");
+            match (function, &args[..]) {
+                (Func::Rgb, [red, green, blue]) => Ok(Value::Color(func::rgb(
+                    number(red)?,
+                    number(green)?,
+                    number(blue)?,
+                ))),
+                (Func::Rgba, [red, green, blue, alpha]) => Ok(Value::Color(func::rgba(
+                    number(red)?,
+                    number(green)?,
+                    number(blue)?,
+                    number(alpha)?,
+                ))),
+                (Func::Hsl, [hue, saturation, lightness]) => Ok(Value::Color(func::hsl(
+                    number(hue)?,
+                    number(saturation)?,
+                    number(lightness)?,
+                ))),
+                (Func::Hsla, [hue, saturation, lightness, alpha]) => Ok(Value::Color(func::hsla(
+                    number(hue)?,
+                    number(saturation)?,
+                    number(lightness)?,
+                    number(alpha)?,
+                ))),
+                (Func::Hwb, [hue, whiteness, blackness]) => Ok(Value::Color(func::hwb(
+                    number(hue)?,
+                    number(whiteness)?,
+                    number(blackness)?,
+                ))),
+                (Func::Mix, [from, to, amount]) => Ok(Value::Color(func::mix(
+                    color(from)?,
+                    color(to)?,
+                    number(amount)?,
+                ))),
+                (Func::Lighten, [base, amount]) => {
+                    Ok(Value::Color(func::lighten(color(base)?, number(amount)?)))
+                }
+                (Func::Darken, [base, amount]) => {
+                    Ok(Value::Color(func::darken(color(base)?, number(amount)?)))
+                }
+                (Func::WithAlpha, [base, alpha]) => {
+                    Ok(Value::Color(func::with_alpha(color(base)?, number(alpha)?)))
+                }
+                (Func::Min, [a, b]) => Ok(Value::Length(func::min(length(a)?, length(b)?))),
+                (Func::Max, [a, b]) => Ok(Value::Length(func::max(length(a)?, length(b)?))),
+                (Func::Clamp, [min, value, max]) => Ok(Value::Length(func::clamp(
+                    length(min)?,
+                    length(value)?,
+                    length(max)?,
+                ))),
+                (Func::Border, [width, style, border_color]) => Ok(Value::Border(func::border(
+                    length(width)?,
+                    border_style(style)?,
+                    color(border_color)?,
+                ))),
+                _ => Err(format!("wrong number of arguments for {:?}", function)),
+            }
+        }
+        Expr::Var(name, fallback) => match lookup_var(name) {
+            Some(value) => Ok(value),
+            None => match fallback {
+                Some(fallback) => eval(fallback),
+                None => Err(format!("style variable {} is not set", name)),
+            },
+        },
+        Expr::Inherit | Expr::Initial | Expr::Unset => {
+            Err("`inherit`, `initial` and `unset` can't be part of a larger value".to_owned())
+        }
+    }
+}
+
+/// Whether the value uses style variables or the viewport size, which
+/// aren't known until a node is styled.
+fn needs_context(expr: &Expr) -> bool {
+    let field = |field: &Option<Expr>| field.as_ref().map_or(false, needs_context);
+    match expr {
+        Expr::Var(..) => true,
+        Expr::Length(LengthUnit::ViewWidth, _) | Expr::Length(LengthUnit::ViewHeight, _) => true,
+        Expr::Length(LengthUnit::Pixels, expr) | Expr::Neg(expr) => needs_context(expr),
+        Expr::Binary(left, _, right) => needs_context(left) || needs_context(right),
+        Expr::Edges(edges) => {
+            field(&edges.left) || field(&edges.right) || field(&edges.top) || field(&edges.bottom)
+        }
+        Expr::Corners(corners) => {
+            field(&corners.top_left)
+                || field(&corners.top_right)
+                || field(&corners.bottom_left)
+                || field(&corners.bottom_right)
+        }
+        Expr::Call(_, args) => args.iter().any(needs_context),
+        _ => false,
+    }
+}
+
+fn check_declaration(declaration: &Declaration) -> Result<()> {
+    match declaration {
+        Declaration::Attribute { name, value, .. } => match value {
+            Expr::Inherit | Expr::Initial | Expr::Unset => Ok(()),
+            value if needs_context(value) => Ok(()),
+            value => attribute::check_by_name(name, &eval(value)?),
+        },
+        Declaration::Variable { value, .. } if !needs_context(value) => eval(value).map(drop),
+        Declaration::Variable { .. } => Ok(()),
+    }
+}
+
+/// Evaluates every declaration that doesn't need a node and checks
+/// that its attribute accepts the value, so that type errors are
+/// reported once when the sheet is loaded instead of on every restyle.
+pub(super) fn check(styles: &[StyleDecl]) -> std::result::Result<(), ParseError> {
+    let declarations = styles.iter().flat_map(|style| {
+        iter::once(&style.declarations)
+            .chain(
+                style
+                    .sub_styles
+                    .iter()
+                    .map(|sub_style| &sub_style.declarations),
+            )
+            .flatten()
+    });
+    for declaration in declarations {
+        let location = match declaration {
+            Declaration::Attribute { location, .. } | Declaration::Variable { location, .. } => {
+                *location
+            }
+        };
+        check_declaration(declaration).map_err(|err| ParseError::new(location, err))?;
+    }
+    Ok(())
+}
+
+/// Reports an error in a declaration that could only be found while
+/// styling a node, like a variable with the wrong type.
+pub(super) type Warn<'a> = &'a dyn Fn(Location, &str);
+
+fn declare(warn: Warn, declarations: &[Declaration], variables: &mut Variables) {
+    for declaration in declarations {
+        if let Declaration::Variable {
+            name,
+            value,
+            location,
+        } = declaration
+        {
+            match eval_optional(value) {
+                Ok(Some(value)) => variables.insert(name, value),
+                Ok(None) => (),
+                Err(err) => warn(*location, &err),
+            }
+        }
+    }
+}
+
+fn apply(warn: Warn, declarations: &[Declaration], values: &mut ComputedValues) {
+    for declaration in declarations {
+        if let Declaration::Attribute {
+            name,
+            value,
+            location,
+        } = declaration
+        {
+            let value = match value {
+                Expr::Inherit => AnyValue::Inherit,
+                Expr::Initial => AnyValue::Initial,
+                Expr::Unset => AnyValue::Unset,
+                value => match eval_optional(value) {
+                    Ok(Some(value)) => AnyValue::Value(value),
+                    Ok(None) => continue,
+                    Err(err) => {
+                        warn(*location, &err);
+                        continue;
+                    }
+                },
+            };
+            if let Err(err) = attribute::set_by_name(name, values, &value) {
+                warn(*location, &err);
+            }
+        }
+    }
+}
+
+impl StyleDecl {
+    pub(super) fn declare_variables(
+        &self,
+        warn: Warn,
+        node: &dyn NodeSelect,
+        variables: &mut Variables,
+    ) {
+        declare(warn, &self.declarations, variables);
+
+        for sub_style in &self.sub_styles {
+            if sub_style.condition.matches(node) {
+                declare(warn, &sub_style.declarations, variables);
+            }
+        }
+    }
+
+    pub(super) fn apply(&self, warn: Warn, node: &dyn NodeSelect, values: &mut ComputedValues) {
+        apply(warn, &self.declarations, values);
+
+        for sub_style in &self.sub_styles {
+            if sub_style.condition.matches(node) {
+                apply(warn, &sub_style.declarations, values);
+            }
+        }
+    }
+}
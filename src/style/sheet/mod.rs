@@ -0,0 +1,366 @@
+use super::{ComputedValues, NodeSelect, Style, StyleRule, StyleSource, Variables};
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::SystemTime;
+
+mod eval;
+mod parse;
+mod print;
+
+pub use parse::ParseError;
+
+/// Failure to load a stylesheet from a file.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "couldn't read stylesheet: {}", err),
+            LoadError::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+            LoadError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> LoadError {
+        LoadError::Io(err)
+    }
+}
+
+impl From<ParseError> for LoadError {
+    fn from(err: ParseError) -> LoadError {
+        LoadError::Parse(err)
+    }
+}
+
+/// The stylesheets that are still alive, by id. Styles only hold the id
+/// of their sheet, so that they stay `Copy` without keeping it alive.
+static SHEETS: Lazy<RwLock<HashMap<u64, Weak<Stylesheet>>>> = Lazy::new(Default::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct SheetState {
+    /// Every name a style has been looked up or defined under. Styles
+    /// refer to their entry by index, so this is only ever added to.
+    names: Vec<Arc<str>>,
+    /// The definition for each name, if the current file has one.
+    styles: Vec<Option<Arc<parse::StyleDecl>>>,
+    modified: Option<SystemTime>,
+}
+
+impl SheetState {
+    fn index_of(&mut self, name: &str) -> usize {
+        match self.names.iter().position(|other| &**other == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.into());
+                self.styles.push(None);
+                self.names.len() - 1
+            }
+        }
+    }
+
+    fn replace(&mut self, styles: Vec<parse::StyleDecl>) {
+        for style in &mut self.styles {
+            *style = None;
+        }
+        for style in styles {
+            let index = self.index_of(&style.name);
+            self.styles[index] = Some(Arc::new(style));
+        }
+    }
+}
+
+/// Styles parsed at runtime from the same syntax that `define_style!`
+/// accepts, so that they can be changed without recompiling.
+///
+/// ```rs
+/// let sheet = Stylesheet::load("styles/app.style")?;
+/// let button_style = sheet.style("BUTTON");
+///
+/// let mut runtime = Runtime::new(move || app!(button_style));
+/// runtime.watch_stylesheet(sheet);
+/// runtime.start();
+/// ```
+///
+/// Styles only refer to the sheet, so it has to be kept alive for as
+/// long as they're used, such as by giving it to `watch_stylesheet`.
+/// Once the sheet is dropped, its styles don't apply anything.
+pub struct Stylesheet {
+    id: u64,
+    path: Option<PathBuf>,
+    file: Arc<str>,
+    state: RwLock<SheetState>,
+    generation: AtomicUsize,
+    /// Declarations that have already logged an error since the last
+    /// load, so that restyling doesn't repeat it for every node.
+    warned: Mutex<HashSet<parse::Location>>,
+}
+
+impl fmt::Debug for Stylesheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Stylesheet({})", self.file)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl Stylesheet {
+    fn new(path: Option<PathBuf>, file: &str, styles: Vec<parse::StyleDecl>) -> Arc<Stylesheet> {
+        let mut state = SheetState {
+            names: vec![],
+            styles: vec![],
+            modified: path.as_deref().and_then(modified),
+        };
+        state.replace(styles);
+        let sheet = Arc::new(Stylesheet {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            path,
+            file: file.into(),
+            state: RwLock::new(state),
+            generation: AtomicUsize::new(1),
+            warned: Mutex::new(HashSet::new()),
+        });
+        SHEETS
+            .write()
+            .unwrap()
+            .insert(sheet.id, Arc::downgrade(&sheet));
+        sheet
+    }
+
+    /// The sheet with `id`, unless it has been dropped.
+    pub(crate) fn find(id: u64) -> Option<Arc<Stylesheet>> {
+        SHEETS.read().unwrap().get(&id).and_then(Weak::upgrade)
+    }
+
+    /// Loads and parses the stylesheet at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Arc<Stylesheet>, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let styles = parse::parse(&source)?;
+        Ok(Stylesheet::new(
+            Some(path.to_owned()),
+            &path.display().to_string(),
+            styles,
+        ))
+    }
+
+    /// Parses a stylesheet that isn't backed by a file, like one included
+    /// with `include_str!`. `name` is used in warnings.
+    pub fn from_source(name: &str, source: &str) -> Result<Arc<Stylesheet>, ParseError> {
+        let styles = parse::parse(source)?;
+        Ok(Stylesheet::new(None, name, styles))
+    }
+
+    /// Returns the style called `name`. This works even if the sheet
+    /// doesn't define it yet, so that it can be added while the app is
+    /// running, but a warning is logged.
+    pub fn style(&self, name: &str) -> Style {
+        let mut state = self.state.write().unwrap();
+        let index = state.index_of(name);
+        if state.styles[index].is_none() {
+            warn!("{}: no style named {}", self.file, name);
+        }
+        Style(StyleSource::Sheet(self.id, index))
+    }
+
+    /// Reads the file again, keeping the current styles if it fails to
+    /// parse. Styles that were looked up keep working, and pick up their
+    /// new definitions the next time a window is restyled.
+    pub fn reload(&self) -> Result<(), LoadError> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+        let modified = modified(path);
+        let result = fs::read_to_string(path)
+            .map_err(LoadError::from)
+            .and_then(|source| parse::parse(&source).map_err(LoadError::from));
+        let mut state = self.state.write().unwrap();
+        state.modified = modified;
+        state.replace(result?);
+        self.warned.lock().unwrap().clear();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Reloads the file if it was modified since the last load, logging
+    /// any errors. Returns whether the styles changed.
+    pub(crate) fn reload_if_modified(&self) -> bool {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return false,
+        };
+        if modified(path) == self.state.read().unwrap().modified {
+            return false;
+        }
+        match self.reload() {
+            Ok(()) => {
+                info!("Reloaded stylesheet {}", self.file);
+                true
+            }
+            Err(LoadError::Parse(err)) => {
+                error!("{}:{}", self.file, err);
+                false
+            }
+            Err(err) => {
+                error!("{}: {}", self.file, err);
+                false
+            }
+        }
+    }
+
+    pub(crate) fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub(crate) fn generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn name(&self, index: usize) -> Arc<str> {
+        self.state.read().unwrap().names[index].clone()
+    }
+
+    fn warn(&self, location: parse::Location, message: &str) {
+        if self.warned.lock().unwrap().insert(location) {
+            warn!("{}:{}: {}", self.file, location, message);
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<Arc<parse::StyleDecl>> {
+        self.state.read().unwrap().styles[index].clone()
+    }
+
+    pub(crate) fn line(&self, index: usize) -> u32 {
+        self.get(index).map_or(0, |style| style.line)
+    }
+
+    pub(crate) fn rules(&self, index: usize, node: Option<&dyn NodeSelect>) -> Vec<StyleRule> {
+        match self.get(index) {
+            Some(style) => style.rules(&self.name(index), node),
+            None => vec![],
+        }
+    }
+
+    pub(crate) fn declare_variables(
+        &self,
+        index: usize,
+        node: &dyn NodeSelect,
+        variables: &mut Variables,
+    ) {
+        if let Some(style) = self.get(index) {
+            style.declare_variables(
+                &|location, message| self.warn(location, message),
+                node,
+                variables,
+            );
+        }
+    }
+
+    pub(crate) fn apply(&self, index: usize, node: &dyn NodeSelect, values: &mut ComputedValues) {
+        if let Some(style) = self.get(index) {
+            style.apply(
+                &|location, message| self.warn(location, message),
+                node,
+                values,
+            );
+        }
+    }
+}
+
+impl Drop for Stylesheet {
+    fn drop(&mut self) {
+        SHEETS.write().unwrap().remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::element::ElementState;
+    use crate::layout::{LogicalLength, LogicalSize};
+    use std::any::TypeId;
+
+    struct Root;
+
+    impl NodeSelect for Root {
+        fn has_type(&self, _ty: TypeId) -> bool {
+            false
+        }
+
+        fn has_state(&self, _state: ElementState) -> bool {
+            false
+        }
+
+        fn has_class(&self, _name: &str) -> bool {
+            false
+        }
+
+        fn element_name(&self) -> &'static str {
+            "view"
+        }
+
+        fn parent(&self) -> Option<&dyn NodeSelect> {
+            None
+        }
+
+        fn sibling_index(&self) -> usize {
+            0
+        }
+
+        fn sibling_count(&self) -> usize {
+            1
+        }
+    }
+
+    fn apply(style: Style) -> ComputedValues {
+        let mut values = ComputedValues::default();
+        illicit::Layer::new()
+            .offer(ComputedValues::default())
+            .offer(Variables::default())
+            .offer(LogicalSize::new(800.0, 600.0))
+            .enter(|| style.apply(&Root, &mut values));
+        values
+    }
+
+    #[test]
+    fn styles_stop_applying_once_their_sheet_is_dropped() {
+        let sheet = Stylesheet::from_source("test", "static BOX = { width: 10 px };").unwrap();
+        let style = sheet.style("BOX");
+        assert_eq!(style.name(), "BOX");
+        assert_eq!(style.file(), ("test".into(), 1));
+        assert_eq!(
+            apply(style).display.block().unwrap().width,
+            Some(LogicalLength::new(10.0))
+        );
+
+        drop(sheet);
+        assert_eq!(style.name(), "");
+        assert_eq!(apply(style), ComputedValues::default());
+    }
+}
@@ -0,0 +1,1002 @@
+use crate::dom::element::ElementState;
+use crate::style::Structural;
+use crate::style_impl::types::{Corners, Edges};
+use crate::style_impl::{attribute, keyword};
+use crate::Color;
+use std::error::Error;
+use std::fmt;
+
+/// A position in a stylesheet's source. Lines and columns start at 1.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub(super) struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A syntax error in a stylesheet, a reference to an attribute, keyword
+/// or function that doesn't exist, or a value of the wrong type.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(super) fn new(location: Location, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: location.line,
+            column: location.column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    /// A `#` directly followed by letters and digits.
+    Hex(String),
+    Punct(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Number(value) => write!(f, "`{}`", value),
+            Token::Str(_) => write!(f, "a string"),
+            Token::Hex(digits) => write!(f, "`#{}`", digits),
+            Token::Punct(punct) => write!(f, "`{}`", punct),
+            Token::End => write!(f, "the end of the file"),
+        }
+    }
+}
+
+const PUNCTS: &[&str] = &[
+    "&&", "||", "{", "}", "(", ")", "[", "]", ",", ":", ";", "=", "+", "-", "*", "/", "!", "#",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Location)>> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+    let mut location = Location { line: 1, column: 1 };
+
+    // Moves past `count` characters, keeping track of the location.
+    let advance = |index: &mut usize, location: &mut Location, count: usize| {
+        for _ in 0..count {
+            if chars[*index] == '\n' {
+                location.line += 1;
+                location.column = 1;
+            } else {
+                location.column += 1;
+            }
+            *index += 1;
+        }
+    };
+
+    while index < chars.len() {
+        let start = location;
+        let ch = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        if ch.is_whitespace() {
+            advance(&mut index, &mut location, 1);
+        } else if ch == '/' && next == Some('/') {
+            while index < chars.len() && chars[index] != '\n' {
+                advance(&mut index, &mut location, 1);
+            }
+        } else if ch == '/' && next == Some('*') {
+            advance(&mut index, &mut location, 2);
+            loop {
+                if index + 1 >= chars.len() {
+                    return Err(ParseError::new(start, "unterminated comment"));
+                }
+                if chars[index] == '*' && chars[index + 1] == '/' {
+                    advance(&mut index, &mut location, 2);
+                    break;
+                }
+                advance(&mut index, &mut location, 1);
+            }
+        } else if ch.is_alphabetic() || ch == '_' {
+            let mut name = String::new();
+            while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_') {
+                name.push(chars[index]);
+                advance(&mut index, &mut location, 1);
+            }
+            tokens.push((Token::Ident(name), start));
+        } else if ch.is_ascii_digit() {
+            let mut text = String::new();
+            while index < chars.len() && chars[index].is_ascii_digit() {
+                text.push(chars[index]);
+                advance(&mut index, &mut location, 1);
+            }
+            let has_fraction = chars.get(index) == Some(&'.')
+                && chars.get(index + 1).map_or(false, |ch| ch.is_ascii_digit());
+            if has_fraction {
+                text.push('.');
+                advance(&mut index, &mut location, 1);
+                while index < chars.len() && chars[index].is_ascii_digit() {
+                    text.push(chars[index]);
+                    advance(&mut index, &mut location, 1);
+                }
+            }
+            let value = text
+                .parse::<f64>()
+                .map_err(|_| ParseError::new(start, "invalid number"))?;
+            tokens.push((Token::Number(value), start));
+        } else if ch == '#' && next.map_or(false, |ch| ch.is_alphanumeric()) {
+            advance(&mut index, &mut location, 1);
+            let mut digits = String::new();
+            while index < chars.len() && chars[index].is_alphanumeric() {
+                digits.push(chars[index]);
+                advance(&mut index, &mut location, 1);
+            }
+            tokens.push((Token::Hex(digits), start));
+        } else if ch == '"' {
+            advance(&mut index, &mut location, 1);
+            let mut text = String::new();
+            loop {
+                match chars.get(index) {
+                    Some('"') => break,
+                    Some('\\') => match chars.get(index + 1) {
+                        Some(&escaped) if escaped == '"' || escaped == '\\' => {
+                            text.push(escaped);
+                            advance(&mut index, &mut location, 2);
+                        }
+                        _ => return Err(ParseError::new(location, "unsupported escape sequence")),
+                    },
+                    Some(&ch) => {
+                        text.push(ch);
+                        advance(&mut index, &mut location, 1);
+                    }
+                    None => return Err(ParseError::new(start, "unterminated string")),
+                }
+            }
+            advance(&mut index, &mut location, 1);
+            tokens.push((Token::Str(text), start));
+        } else {
+            let punct = PUNCTS.iter().find(|punct| {
+                punct
+                    .chars()
+                    .enumerate()
+                    .all(|(offset, ch)| chars.get(index + offset) == Some(&ch))
+            });
+            match punct {
+                Some(punct) => {
+                    advance(&mut index, &mut location, punct.len());
+                    tokens.push((Token::Punct(punct), start));
+                }
+                None => {
+                    return Err(ParseError::new(
+                        start,
+                        format!("unexpected character `{}`", ch),
+                    ))
+                }
+            }
+        }
+    }
+
+    tokens.push((Token::End, location));
+    Ok(tokens)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(super) enum LengthUnit {
+    Pixels,
+    ViewWidth,
+    ViewHeight,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(super) enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// The functions that can be called from a stylesheet, matching those
+/// in `style_impl::func`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(super) enum Func {
+    Rgb,
+    Rgba,
+    Hsl,
+    Hsla,
+    Hwb,
+    Mix,
+    Lighten,
+    Darken,
+    WithAlpha,
+    Min,
+    Max,
+    Clamp,
+    Border,
+}
+
+impl Func {
+    fn lookup(name: &str) -> Option<(Func, usize)> {
+        match name {
+            "rgb" => Some((Func::Rgb, 3)),
+            "rgba" => Some((Func::Rgba, 4)),
+            "hsl" => Some((Func::Hsl, 3)),
+            "hsla" => Some((Func::Hsla, 4)),
+            "hwb" => Some((Func::Hwb, 3)),
+            "mix" => Some((Func::Mix, 3)),
+            "lighten" => Some((Func::Lighten, 2)),
+            "darken" => Some((Func::Darken, 2)),
+            "with_alpha" => Some((Func::WithAlpha, 2)),
+            "min" => Some((Func::Min, 2)),
+            "max" => Some((Func::Max, 2)),
+            "clamp" => Some((Func::Clamp, 3)),
+            "border" => Some((Func::Border, 3)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(super) enum Expr {
+    Number(f64),
+    Keyword(&'static str),
+    Color(Color),
+    Length(LengthUnit, Box<Expr>),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+    Neg(Box<Expr>),
+    Edges(Box<Edges<Expr>>),
+    Corners(Box<Corners<Expr>>),
+    Call(Func, Vec<Expr>),
+    Var(String, Option<Box<Expr>>),
+    /// Only allowed as the whole value of an attribute.
+    Inherit,
+    Initial,
+    Unset,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(super) enum State {
+    Element(ElementState),
+    Structural(Structural),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(super) enum Condition {
+    /// Matches the element's name, like `view`.
+    Element(String),
+    State(State),
+    Class(String),
+    Parent(Box<Condition>),
+    Ancestor(Box<Condition>),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(super) enum Declaration {
+    Attribute {
+        name: String,
+        value: Expr,
+        location: Location,
+    },
+    Variable {
+        name: String,
+        value: Expr,
+        location: Location,
+    },
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct SubStyle {
+    pub condition: Condition,
+    pub declarations: Vec<Declaration>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct StyleDecl {
+    pub name: String,
+    pub line: u32,
+    pub declarations: Vec<Declaration>,
+    pub sub_styles: Vec<SubStyle>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, Location)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, offset: usize) -> &Token {
+        let index = (self.position + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].0
+    }
+
+    fn location(&self) -> Location {
+        self.tokens[self.position].1
+    }
+
+    fn next(&mut self) -> (Token, Location) {
+        let token = self.tokens[self.position].clone();
+        if self.position + 1 < self.tokens.len() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T> {
+        Err(ParseError::new(self.location(), message))
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        self.error(format!("expected {}, found {}", expected, self.peek()))
+    }
+
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Token::Punct(found) if *found == punct)
+    }
+
+    fn peek_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Token::Ident(found) if found == name)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if self.peek_punct(punct) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", punct))
+        }
+    }
+
+    fn expect_keyword(&mut self, name: &str) -> Result<()> {
+        if self.peek_ident(name) {
+            self.next();
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", name))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, Location)> {
+        match self.next() {
+            (Token::Ident(name), location) => Ok((name, location)),
+            (token, location) => Err(ParseError::new(
+                location,
+                format!("expected a name, found {}", token),
+            )),
+        }
+    }
+
+    /// Skips over a bracketed group, assuming the opening bracket has
+    /// already been consumed.
+    fn skip_group(&mut self, open: &str, close: &str) -> Result<()> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next().0 {
+                Token::Punct(punct) if punct == open => depth += 1,
+                Token::Punct(punct) if punct == close => depth -= 1,
+                Token::End => return self.unexpected(&format!("`{}`", close)),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_file(&mut self) -> Result<Vec<StyleDecl>> {
+        let mut styles = Vec::<StyleDecl>::new();
+        while *self.peek() != Token::End {
+            let location = self.location();
+            let style = self.parse_style()?;
+            if styles.iter().any(|other| other.name == style.name) {
+                return Err(ParseError::new(
+                    location,
+                    format!("style `{}` is defined more than once", style.name),
+                ));
+            }
+            styles.push(style);
+        }
+        Ok(styles)
+    }
+
+    fn parse_style(&mut self) -> Result<StyleDecl> {
+        // Attributes and visibility are accepted so that a file can be
+        // shared with `define_style!`, but they have no meaning here.
+        while self.eat_punct("#") {
+            self.expect_punct("[")?;
+            self.skip_group("[", "]")?;
+        }
+        if self.peek_ident("pub") {
+            self.next();
+            if self.eat_punct("(") {
+                self.skip_group("(", ")")?;
+            }
+        }
+
+        self.expect_keyword("static")?;
+        let (name, location) = self.expect_ident()?;
+        self.expect_punct("=")?;
+        self.expect_punct("{")?;
+
+        let mut declarations = vec![];
+        while !self.peek_ident("if") && !self.peek_punct("}") {
+            declarations.push(self.parse_declaration()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+
+        let mut sub_styles = vec![];
+        while self.peek_ident("if") {
+            sub_styles.push(self.parse_sub_style()?);
+        }
+
+        if !self.eat_punct("}") {
+            return self.unexpected("`,`, `if` or `}`");
+        }
+        self.expect_punct(";")?;
+
+        Ok(StyleDecl {
+            name,
+            line: location.line as u32,
+            declarations,
+            sub_styles,
+        })
+    }
+
+    fn parse_sub_style(&mut self) -> Result<SubStyle> {
+        self.expect_keyword("if")?;
+        let condition = self.parse_or()?;
+        if !self.eat_punct("{") {
+            return self.unexpected("`{`, `&&` or `||`");
+        }
+        let mut declarations = vec![];
+        while !self.peek_punct("}") {
+            declarations.push(self.parse_declaration()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        if !self.eat_punct("}") {
+            return self.unexpected("`,` or `}`");
+        }
+        Ok(SubStyle {
+            condition,
+            declarations,
+        })
+    }
+
+    fn parse_declaration(&mut self) -> Result<Declaration> {
+        let location = self.location();
+        if self.peek_ident("var") && *self.peek_nth(1) == Token::Punct("(") {
+            self.next();
+            self.next();
+            let (name, _) = self.expect_ident()?;
+            self.expect_punct(")")?;
+            self.expect_punct(":")?;
+            let value = self.parse_expr()?;
+            return Ok(Declaration::Variable {
+                name,
+                value,
+                location,
+            });
+        }
+
+        let (name, _) = self.expect_ident()?;
+        if !attribute::NAMES.contains(&&name[..]) {
+            return Err(ParseError::new(
+                location,
+                format!("unknown attribute `{}`", name),
+            ));
+        }
+        self.expect_punct(":")?;
+        let value = match self.peek() {
+            Token::Ident(word) if word == "inherit" => Expr::Inherit,
+            Token::Ident(word) if word == "initial" => Expr::Initial,
+            Token::Ident(word) if word == "unset" => Expr::Unset,
+            _ => self.parse_expr()?,
+        };
+        if let Expr::Inherit | Expr::Initial | Expr::Unset = value {
+            self.next();
+        }
+        Ok(Declaration::Attribute {
+            name,
+            value,
+            location,
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<Condition> {
+        let mut left = self.parse_and()?;
+        while self.eat_punct("||") {
+            let right = self.parse_and()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition> {
+        let mut left = self.parse_unary()?;
+        while self.eat_punct("&&") {
+            let right = self.parse_unary()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition> {
+        if self.eat_punct("!") {
+            Ok(Condition::Not(Box::new(self.parse_unary()?)))
+        } else if self.eat_punct("(") {
+            let inner = self.parse_or()?;
+            self.expect_punct(")")?;
+            Ok(inner)
+        } else {
+            self.parse_selector()
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<Condition> {
+        let kind = match self.peek() {
+            Token::Ident(kind) => kind.clone(),
+            _ => return self.unexpected("a selector"),
+        };
+        match &kind[..] {
+            "element" => {
+                self.next();
+                self.expect_punct(":")?;
+                let (name, _) = self.expect_ident()?;
+                Ok(Condition::Element(name.to_lowercase()))
+            }
+            "state" => {
+                self.next();
+                self.expect_punct(":")?;
+                let (name, location) = self.expect_ident()?;
+                let args = if self.eat_punct("(") {
+                    let args = self.parse_nth_args()?;
+                    self.expect_punct(")")?;
+                    Some(args)
+                } else {
+                    None
+                };
+                let state = match (&name[..], args) {
+                    ("press", None) => State::Element(ElementState::Press),
+                    ("hover", None) => State::Element(ElementState::Hover),
+                    ("focus", None) => State::Element(ElementState::Focus),
+                    ("checked", None) => State::Element(ElementState::Checked),
+                    ("open", None) => State::Element(ElementState::Open),
+                    ("first_child", None) => State::Structural(Structural::FirstChild),
+                    ("last_child", None) => State::Structural(Structural::LastChild),
+                    ("only_child", None) => State::Structural(Structural::OnlyChild),
+                    ("nth_child", Some((a, b))) => State::Structural(Structural::NthChild { a, b }),
+                    ("nth_child", None) => {
+                        return Err(ParseError::new(
+                            location,
+                            "`nth_child` needs an argument, like `nth_child(2n + 1)`",
+                        ))
+                    }
+                    ("press", _)
+                    | ("hover", _)
+                    | ("focus", _)
+                    | ("checked", _)
+                    | ("open", _)
+                    | ("first_child", _)
+                    | ("last_child", _)
+                    | ("only_child", _) => {
+                        return Err(ParseError::new(
+                            location,
+                            format!("`{}` doesn't take an argument", name),
+                        ))
+                    }
+                    _ => {
+                        return Err(ParseError::new(
+                            location,
+                            format!("unknown state `{}`", name),
+                        ))
+                    }
+                };
+                Ok(Condition::State(state))
+            }
+            "class" => {
+                self.next();
+                self.expect_punct(":")?;
+                match self.next() {
+                    (Token::Ident(name), _) | (Token::Str(name), _) => Ok(Condition::Class(name)),
+                    (token, location) => Err(ParseError::new(
+                        location,
+                        format!("expected a class name, found {}", token),
+                    )),
+                }
+            }
+            "parent" | "ancestor" => {
+                self.next();
+                self.expect_punct("(")?;
+                let inner = Box::new(self.parse_or()?);
+                self.expect_punct(")")?;
+                if kind == "parent" {
+                    Ok(Condition::Parent(inner))
+                } else {
+                    Ok(Condition::Ancestor(inner))
+                }
+            }
+            _ => self.unexpected("a selector"),
+        }
+    }
+
+    fn parse_nth_int(&mut self) -> Result<i32> {
+        match self.next() {
+            (Token::Number(value), _) if value.fract() == 0.0 => Ok(value as i32),
+            (token, location) => Err(ParseError::new(
+                location,
+                format!("expected a whole number, found {}", token),
+            )),
+        }
+    }
+
+    /// Parses the `an+b` notation used by `nth_child()`.
+    fn parse_nth_args(&mut self) -> Result<(i32, i32)> {
+        if self.peek_ident("odd") {
+            self.next();
+            return Ok((2, 1));
+        }
+        if self.peek_ident("even") {
+            self.next();
+            return Ok((2, 0));
+        }
+
+        let sign = if self.eat_punct("-") { -1 } else { 1 };
+        let a = if self.peek_ident("n") {
+            1
+        } else {
+            let a = self.parse_nth_int()?;
+            if !self.peek_ident("n") {
+                return Ok((0, sign * a));
+            }
+            a
+        };
+        self.next();
+
+        let b = if self.eat_punct("+") {
+            self.parse_nth_int()?
+        } else if self.eat_punct("-") {
+            -self.parse_nth_int()?
+        } else {
+            0
+        };
+        if self.peek_ident("n") {
+            return self.error("`n` may only appear once");
+        }
+        Ok((sign * a, b))
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr> {
+        let mut left = self.parse_mul()?;
+        loop {
+            let oper = if self.eat_punct("+") {
+                Operator::Add
+            } else if self.eat_punct("-") {
+                Operator::Sub
+            } else {
+                return Ok(left);
+            };
+            let right = self.parse_mul()?;
+            left = Expr::Binary(Box::new(left), oper, Box::new(right));
+        }
+    }
+
+    fn parse_mul(&mut self) -> Result<Expr> {
+        let mut left = self.parse_negation()?;
+        loop {
+            let oper = if self.eat_punct("*") {
+                Operator::Mul
+            } else if self.eat_punct("/") {
+                Operator::Div
+            } else {
+                return Ok(left);
+            };
+            let right = self.parse_negation()?;
+            left = Expr::Binary(Box::new(left), oper, Box::new(right));
+        }
+    }
+
+    fn parse_negation(&mut self) -> Result<Expr> {
+        if self.eat_punct("-") {
+            Ok(Expr::Neg(Box::new(self.parse_negation()?)))
+        } else {
+            self.parse_suffix()
+        }
+    }
+
+    fn parse_suffix(&mut self) -> Result<Expr> {
+        let expr = self.parse_terminal()?;
+        let unit = match self.peek() {
+            Token::Ident(unit) if unit == "px" => LengthUnit::Pixels,
+            Token::Ident(unit) if unit == "vw" => LengthUnit::ViewWidth,
+            Token::Ident(unit) if unit == "vh" => LengthUnit::ViewHeight,
+            Token::Ident(unit) if unit == "em" => {
+                return self.error("`em` units aren't supported yet")
+            }
+            _ => return Ok(expr),
+        };
+        self.next();
+        Ok(Expr::Length(unit, Box::new(expr)))
+    }
+
+    fn parse_terminal(&mut self) -> Result<Expr> {
+        let location = self.location();
+        match self.next().0 {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Hex(digits) => match Color::from_hex(&digits) {
+                Some(color) => Ok(Expr::Color(color)),
+                None => Err(ParseError::new(
+                    location,
+                    format!(
+                        "invalid color `#{}`, expected 3, 4, 6 or 8 hex digits",
+                        digits
+                    ),
+                )),
+            },
+            Token::Str(_) => Err(ParseError::new(
+                location,
+                "strings aren't supported by any attribute",
+            )),
+            Token::Punct("(") => {
+                let expr = self.parse_expr()?;
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Token::Ident(name) => {
+                if self.eat_punct("(") {
+                    if name == "var" {
+                        self.parse_var()
+                    } else {
+                        self.parse_call(name, location)
+                    }
+                } else if self.eat_punct("{") {
+                    self.parse_struct(name, location)
+                } else if let "inherit" | "initial" | "unset" = &name[..] {
+                    Err(ParseError::new(
+                        location,
+                        format!(
+                            "`{}` can only be used as the whole value of an attribute",
+                            name
+                        ),
+                    ))
+                } else {
+                    match keyword::lookup(&name) {
+                        Some(keyword) => Ok(Expr::Keyword(keyword)),
+                        None if Color::named(&name).is_some() => {
+                            Ok(Expr::Color(Color::named(&name).unwrap()))
+                        }
+                        None => Err(ParseError::new(
+                            location,
+                            format!("unknown keyword `{}`", name),
+                        )),
+                    }
+                }
+            }
+            token => Err(ParseError::new(
+                location,
+                format!("expected a value, found {}", token),
+            )),
+        }
+    }
+
+    fn parse_var(&mut self) -> Result<Expr> {
+        let (name, _) = self.expect_ident()?;
+        let fallback = if self.eat_punct(",") {
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+        if !self.eat_punct(")") {
+            return self.unexpected("`,` or `)`");
+        }
+        Ok(Expr::Var(name, fallback))
+    }
+
+    fn parse_call(&mut self, name: String, location: Location) -> Result<Expr> {
+        let (func, arity) = match Func::lookup(&name) {
+            Some(func) => func,
+            None => {
+                return Err(ParseError::new(
+                    location,
+                    format!("unknown function `{}`", name),
+                ))
+            }
+        };
+        let mut args = vec![];
+        while !self.peek_punct(")") {
+            args.push(self.parse_expr()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        if !self.eat_punct(")") {
+            return self.unexpected("`,` or `)`");
+        }
+        if args.len() != arity {
+            return Err(ParseError::new(
+                location,
+                format!(
+                    "`{}` takes {} arguments, but {} were given",
+                    name,
+                    arity,
+                    args.len()
+                ),
+            ));
+        }
+        Ok(Expr::Call(func, args))
+    }
+
+    fn parse_struct(&mut self, name: String, location: Location) -> Result<Expr> {
+        if name != "Edges" && name != "Corners" {
+            return Err(ParseError::new(
+                location,
+                format!("unknown type `{}`, expected `Edges` or `Corners`", name),
+            ));
+        }
+        let mut edges = Edges::new();
+        let mut corners = Corners::new();
+        while !self.peek_punct("}") {
+            let (field, field_location) = self.expect_ident()?;
+            let slot = match (&name[..], &field[..]) {
+                ("Edges", "left") => &mut edges.left,
+                ("Edges", "right") => &mut edges.right,
+                ("Edges", "top") => &mut edges.top,
+                ("Edges", "bottom") => &mut edges.bottom,
+                ("Corners", "top_left") => &mut corners.top_left,
+                ("Corners", "top_right") => &mut corners.top_right,
+                ("Corners", "bottom_left") => &mut corners.bottom_left,
+                ("Corners", "bottom_right") => &mut corners.bottom_right,
+                _ => {
+                    return Err(ParseError::new(
+                        field_location,
+                        format!("`{}` has no field `{}`", name, field),
+                    ))
+                }
+            };
+            self.expect_punct(":")?;
+            *slot = Some(self.parse_expr()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        if !self.eat_punct("}") {
+            return self.unexpected("`,` or `}`");
+        }
+        if name == "Edges" {
+            Ok(Expr::Edges(Box::new(edges)))
+        } else {
+            Ok(Expr::Corners(Box::new(corners)))
+        }
+    }
+}
+
+/// Parses the contents of a stylesheet, which uses the same syntax as
+/// the body of `define_style!`.
+pub(super) fn parse(source: &str) -> Result<Vec<StyleDecl>> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+    };
+    let styles = parser.parse_file()?;
+    super::eval::check(&styles)?;
+    Ok(styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(source: &str) -> (usize, usize) {
+        let err = parse(source).unwrap_err();
+        (err.line, err.column)
+    }
+
+    #[test]
+    fn parses_styles() {
+        let styles = parse(
+            "
+            // A comment
+            static BUTTON = {
+                background_color: rgb(66, 135, 245),
+                text_color: #fff,
+                border: border(1 px, solid, mix(red, hsl(210, 50, 40), 25)),
+                padding: Edges { left: 4 px, right: 4 px },
+                var(gap): 8 px,
+                if state: hover && !class: disabled {
+                    width: (100 vw - var(gap, 2 px) * 2) / 3 + -1 px,
+                }
+                if state: nth_child(2n+1) {
+                    display: inherit
+                }
+            };
+            pub static OTHER = {};
+            ",
+        )
+        .unwrap();
+        assert_eq!(styles.len(), 2);
+        assert_eq!(styles[0].name, "BUTTON");
+        assert_eq!(styles[0].line, 3);
+        assert_eq!(styles[0].declarations.len(), 5);
+        assert_eq!(styles[0].sub_styles.len(), 2);
+        assert_eq!(
+            styles[0].sub_styles[1].condition,
+            Condition::State(State::Structural(Structural::NthChild { a: 2, b: 1 }))
+        );
+    }
+
+    #[test]
+    fn reports_locations() {
+        assert_eq!(error_at("static A = {\n  colour: red,\n};"), (2, 3));
+        assert_eq!(error_at("static A = {\n  width: 10 px\n}"), (3, 2));
+        assert_eq!(error_at("static A = {\n  display: blok,\n};"), (2, 12));
+        assert_eq!(
+            error_at("static A = {\n  text_color: rgb(1, 2),\n};"),
+            (2, 15)
+        );
+        assert_eq!(error_at("static A = { width: 1 px $ };"), (1, 26));
+        assert_eq!(error_at("static A = { text_color: #12345 };"), (1, 26));
+    }
+
+    #[test]
+    fn reports_type_errors_when_loaded() {
+        assert_eq!(error_at("static A = {\n  text_color: 10 px,\n};"), (2, 3));
+        assert_eq!(
+            error_at("static A = {\n  width: 2 px + rgb(255, 0, 0),\n};"),
+            (2, 3)
+        );
+        assert_eq!(error_at("static A = {\n  width: 2 px * 3 px,\n};"), (2, 3));
+        assert_eq!(
+            error_at(
+                "static A = {\n  if state: hover {\n    var(gap): rgb(255, 0, 0) + 1,\n  }\n};"
+            ),
+            (3, 5)
+        );
+        let err = parse("static A = { width: rgb(255, 0, 0) };").unwrap_err();
+        assert!(err.message.contains("width"), "{}", err.message);
+    }
+
+    #[test]
+    fn leaves_variables_and_viewport_lengths_until_styling() {
+        assert!(parse(
+            "static A = { width: var(gap) + 2 px, text_color: var(accent, rgb(255, 0, 0)) };"
+        )
+        .is_ok());
+        assert!(parse("static A = { width: 50 vw - 10 px };").is_ok());
+        assert!(parse("static A = { width: -var(gap) * 2 };").is_ok());
+    }
+}
@@ -0,0 +1,264 @@
+use super::parse::{Condition, Declaration, Expr, Func, LengthUnit, Operator, State, StyleDecl};
+use crate::dom::element::ElementState;
+use crate::style::{NodeSelect, Structural, StyleRule};
+use std::fmt::{self, Display};
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Func::Rgb => "rgb",
+            Func::Rgba => "rgba",
+            Func::Hsl => "hsl",
+            Func::Hsla => "hsla",
+            Func::Hwb => "hwb",
+            Func::Mix => "mix",
+            Func::Lighten => "lighten",
+            Func::Darken => "darken",
+            Func::WithAlpha => "with_alpha",
+            Func::Min => "min",
+            Func::Max => "max",
+            Func::Clamp => "clamp",
+            Func::Border => "border",
+        }
+    }
+}
+
+impl Expr {
+    /// How tightly the expression binds, for deciding where parentheses
+    /// are needed since the parser doesn't keep them.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary(_, Operator::Add, _) | Expr::Binary(_, Operator::Sub, _) => 1,
+            Expr::Binary(_, Operator::Mul, _) | Expr::Binary(_, Operator::Div, _) => 2,
+            Expr::Neg(_) | Expr::Length(..) => 3,
+            _ => 4,
+        }
+    }
+
+    fn fmt_at(&self, f: &mut fmt::Formatter, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "(")?;
+            self.fmt(f)?;
+            write!(f, ")")
+        } else {
+            self.fmt(f)
+        }
+    }
+}
+
+fn fmt_fields<'a>(
+    f: &mut fmt::Formatter,
+    name: &str,
+    fields: impl IntoIterator<Item = (&'a str, &'a Option<Expr>)>,
+) -> fmt::Result {
+    let fields = fields
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|value| format!("{}: {}", name, value)))
+        .collect::<Vec<_>>();
+    write!(f, "{} {{ {} }}", name, fields.join(", "))
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(value) => write!(f, "{}", value),
+            Expr::Keyword(name) => write!(f, "{}", name),
+            Expr::Color(color) => write!(f, "{}", color),
+            Expr::Length(unit, expr) => {
+                expr.fmt_at(f, 4)?;
+                let unit = match unit {
+                    LengthUnit::Pixels => "px",
+                    LengthUnit::ViewWidth => "vw",
+                    LengthUnit::ViewHeight => "vh",
+                };
+                write!(f, " {}", unit)
+            }
+            Expr::Binary(left, oper, right) => {
+                let precedence = self.precedence();
+                left.fmt_at(f, precedence)?;
+                let oper = match oper {
+                    Operator::Add => "+",
+                    Operator::Sub => "-",
+                    Operator::Mul => "*",
+                    Operator::Div => "/",
+                };
+                write!(f, " {} ", oper)?;
+                right.fmt_at(f, precedence + 1)
+            }
+            Expr::Neg(expr) => {
+                write!(f, "-")?;
+                expr.fmt_at(f, 3)
+            }
+            Expr::Edges(edges) => fmt_fields(
+                f,
+                "Edges",
+                vec![
+                    ("left", &edges.left),
+                    ("right", &edges.right),
+                    ("top", &edges.top),
+                    ("bottom", &edges.bottom),
+                ],
+            ),
+            Expr::Corners(corners) => fmt_fields(
+                f,
+                "Corners",
+                vec![
+                    ("top_left", &corners.top_left),
+                    ("top_right", &corners.top_right),
+                    ("bottom_left", &corners.bottom_left),
+                    ("bottom_right", &corners.bottom_right),
+                ],
+            ),
+            Expr::Call(func, args) => {
+                let args = args.iter().map(Expr::to_string).collect::<Vec<_>>();
+                write!(f, "{}({})", func.name(), args.join(", "))
+            }
+            Expr::Var(name, Some(fallback)) => write!(f, "var({}, {})", name, fallback),
+            Expr::Var(name, None) => write!(f, "var({})", name),
+            Expr::Inherit => write!(f, "inherit"),
+            Expr::Initial => write!(f, "initial"),
+            Expr::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            State::Element(ElementState::Hover) => write!(f, "hover"),
+            State::Element(ElementState::Press) => write!(f, "press"),
+            State::Element(ElementState::Focus) => write!(f, "focus"),
+            State::Element(ElementState::Checked) => write!(f, "checked"),
+            State::Element(ElementState::Open) => write!(f, "open"),
+            State::Structural(Structural::FirstChild) => write!(f, "first_child"),
+            State::Structural(Structural::LastChild) => write!(f, "last_child"),
+            State::Structural(Structural::OnlyChild) => write!(f, "only_child"),
+            State::Structural(Structural::NthChild { a: 0, b }) => write!(f, "nth_child({})", b),
+            State::Structural(Structural::NthChild { a, b: 0 }) => write!(f, "nth_child({}n)", a),
+            State::Structural(Structural::NthChild { a, b }) => {
+                write!(f, "nth_child({}n{:+})", a, b)
+            }
+        }
+    }
+}
+
+impl Condition {
+    fn precedence(&self) -> u8 {
+        match self {
+            Condition::Or(..) => 1,
+            Condition::And(..) => 2,
+            _ => 3,
+        }
+    }
+
+    fn fmt_at(&self, f: &mut fmt::Formatter, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "(")?;
+            self.fmt(f)?;
+            write!(f, ")")
+        } else {
+            self.fmt(f)
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Condition::Element(name) => write!(f, "element: {}", name),
+            Condition::State(state) => write!(f, "state: {}", state),
+            Condition::Class(name) => write!(f, "class: {}", name),
+            Condition::Parent(inner) => write!(f, "parent({})", inner),
+            Condition::Ancestor(inner) => write!(f, "ancestor({})", inner),
+            Condition::Not(inner) => {
+                write!(f, "!")?;
+                inner.fmt_at(f, 3)
+            }
+            Condition::And(left, right) => {
+                left.fmt_at(f, 2)?;
+                write!(f, " && ")?;
+                right.fmt_at(f, 2)
+            }
+            Condition::Or(left, right) => {
+                left.fmt_at(f, 1)?;
+                write!(f, " || ")?;
+                right.fmt_at(f, 1)
+            }
+        }
+    }
+}
+
+fn describe(declarations: &[Declaration]) -> Vec<(String, String)> {
+    declarations
+        .iter()
+        .map(|declaration| match declaration {
+            Declaration::Attribute { name, value, .. } => (name.clone(), value.to_string()),
+            Declaration::Variable { name, value, .. } => {
+                (format!("var({})", name), value.to_string())
+            }
+        })
+        .collect()
+}
+
+impl StyleDecl {
+    /// Describes the style's declarations and those of its sub-styles,
+    /// leaving out sub-styles that don't match `node` if one is given.
+    pub(super) fn rules(&self, name: &str, node: Option<&dyn NodeSelect>) -> Vec<StyleRule> {
+        let mut rules = vec![StyleRule {
+            style: name.to_owned(),
+            selector: None,
+            attributes: describe(&self.declarations),
+        }];
+        for sub_style in &self.sub_styles {
+            if node.map_or(true, |node| sub_style.condition.matches(node)) {
+                rules.push(StyleRule {
+                    style: name.to_owned(),
+                    selector: Some(sub_style.condition.to_string()),
+                    attributes: describe(&sub_style.declarations),
+                });
+            }
+        }
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse::parse;
+
+    #[test]
+    fn prints_rules() {
+        let styles = parse(
+            "
+            static BUTTON = {
+                width: (100 vw - var(gap, 2 px) * 2) / 3 + -(1 px),
+                var(gap): 8 px,
+                if state: hover && (class: a || !class: b) {
+                    padding: Edges { left: 4 px },
+                }
+            };
+            ",
+        )
+        .unwrap();
+        let rules = styles[0].rules("BUTTON", None);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0].attributes,
+            vec![
+                (
+                    "width".to_owned(),
+                    "(100 vw - var(gap, 2 px) * 2) / 3 + -1 px".to_owned()
+                ),
+                ("var(gap)".to_owned(), "8 px".to_owned()),
+            ]
+        );
+        assert_eq!(
+            rules[1].selector.as_deref(),
+            Some("state: hover && (class: a || !class: b)")
+        );
+        assert_eq!(
+            rules[1].attributes,
+            vec![("padding".to_owned(), "Edges { left: 4 px }".to_owned())]
+        );
+    }
+}
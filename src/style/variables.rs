@@ -0,0 +1,125 @@
+use super::Border;
+use crate::style_impl::types::{Corners, Edges};
+use crate::style_impl::Length;
+use crate::Color;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The value of a style variable.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Number(f64),
+    Length(Length),
+    Color(Color),
+    Keyword(&'static str),
+    Border(Border),
+    Edges(Box<Edges<Value>>),
+    Corners(Box<Corners<Value>>),
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::Number(value)
+    }
+}
+
+impl From<Length> for Value {
+    fn from(value: Length) -> Value {
+        Value::Length(value)
+    }
+}
+
+impl From<Color> for Value {
+    fn from(value: Color) -> Value {
+        Value::Color(value)
+    }
+}
+
+impl From<Border> for Value {
+    fn from(value: Border) -> Value {
+        Value::Border(value)
+    }
+}
+
+impl<V> From<Edges<V>> for Value
+where
+    V: Into<Value>,
+{
+    fn from(value: Edges<V>) -> Value {
+        Value::Edges(Box::new(Edges {
+            left: value.left.map(Into::into),
+            right: value.right.map(Into::into),
+            top: value.top.map(Into::into),
+            bottom: value.bottom.map(Into::into),
+        }))
+    }
+}
+
+impl<V> From<Corners<V>> for Value
+where
+    V: Into<Value>,
+{
+    fn from(value: Corners<V>) -> Value {
+        Value::Corners(Box::new(Corners {
+            top_left: value.top_left.map(Into::into),
+            top_right: value.top_right.map(Into::into),
+            bottom_left: value.bottom_left.map(Into::into),
+            bottom_right: value.bottom_right.map(Into::into),
+        }))
+    }
+}
+
+/// A set of named style variables, which styles refer to with
+/// `var(name)`. Variables are inherited down the tree, and can be
+/// declared in a style with `var(name): value`, set on an element with
+/// `InlineStyle::var`, or offered through the `illicit` environment
+/// around the components that should see them:
+///
+/// ```rs
+/// let theme = Variables::new()
+///     .set("accent", Color::new(66, 135, 245, 255))
+///     .set("gap", style_impl::pixels(8.0));
+/// illicit::Layer::new().offer(theme).enter(|| mox! { <my_view /> })
+/// ```
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Variables(Arc<HashMap<String, Value>>);
+
+impl Variables {
+    pub fn new() -> Variables {
+        Variables::default()
+    }
+
+    pub fn set(mut self, name: &str, value: impl Into<Value>) -> Variables {
+        self.insert(name, value.into());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub(crate) fn insert(&mut self, name: &str, value: Value) {
+        if self.get(name) != Some(&value) {
+            Arc::make_mut(&mut self.0).insert(name.to_owned(), value);
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Variables) {
+        if other.0.is_empty() {
+            return;
+        }
+        if self.0.is_empty() {
+            *self = other.clone();
+            return;
+        }
+        for (name, value) in other.0.iter() {
+            self.insert(name, value.clone());
+        }
+    }
+
+    /// Whether both refer to the same underlying set, which is a cheap
+    /// way to tell that nothing has been declared in between.
+    pub(crate) fn same(&self, other: &Variables) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
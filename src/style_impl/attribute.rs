@@ -1,13 +1,38 @@
-use super::{keyword, types::Corners, types::Edges, Attribute, AttributeHasValue, Length};
+use super::{
+    keyword, types::Corners, types::Edges, types::LengthOrAuto, var::FromValue, var::Var,
+    Attribute, AttributeHasValue, Inherit, Initial, Length, Unset,
+};
 use crate::layout::{LogicalLength, LogicalSideOffsets};
 use crate::style::{
-    Border, ComputedValues, Corners as StyleCorners, Direction, DisplayType, Edges as StyleEdges,
+    Alignment, Border, ComputedValues, Corners as StyleCorners, Direction, DisplayType,
+    Edges as StyleEdges, Value, Visibility,
 };
 use crate::Color;
 
+macro_rules! is_inherited {
+    () => {
+        false
+    };
+    (inherited) => {
+        true
+    };
+}
+
+/// Copies the given block value fields from `$from`, using the defaults
+/// if it isn't a block.
+macro_rules! block {
+    ($values:ident, $from:ident, $( $field:ident ),+) => {
+        if let Some(block) = $values.display.block_mut() {
+            let from = $from.display.block().copied().unwrap_or_default();
+            $( block.$field = from.$field; )+
+        }
+    };
+}
+
 macro_rules! define_attribute {
     (
-        $name:ident($class:ident) {
+        $name:ident($class:ident) $( $inherited:ident )? {
+            copy => |$id_copy_values:ident, $id_copy_from:ident| $copy:block
             $(
                 $value:ty => |$id_values:ident, $id_value:ident $(, $id_parent:ident)*| $expr:block
             )+
@@ -17,6 +42,38 @@ macro_rules! define_attribute {
 
         impl Attribute for $class {}
 
+        impl $class {
+            /// Whether the attribute takes its parent's value when no
+            /// style sets it.
+            pub const INHERITED: bool = is_inherited!($( $inherited )?);
+
+            fn copy($id_copy_values: &mut ComputedValues, $id_copy_from: &ComputedValues) {
+                $copy;
+            }
+
+            /// Sets the attribute using the first of its value types
+            /// that `value` converts to, returning false if none do.
+            fn set_value(&self, values: &mut ComputedValues, value: &Value) -> bool {
+                $(
+                    if let Some(value) = <$value as FromValue>::from_value(value) {
+                        <Self as AttributeHasValue<$value>>::set(self, values, value);
+                        return true;
+                    }
+                )+
+                false
+            }
+
+            /// Whether any of the attribute's value types accepts `value`.
+            fn accepts(value: &Value) -> bool {
+                $(
+                    if <$value as FromValue>::from_value(value).is_some() {
+                        return true;
+                    }
+                )+
+                false
+            }
+        }
+
         $(
             impl AttributeHasValue<$value> for $class {
                 $(#[illicit::from_env($id_parent: &ComputedValues)])*
@@ -26,95 +83,251 @@ macro_rules! define_attribute {
             }
         )+
 
+        impl AttributeHasValue<Inherit> for $class {
+            #[illicit::from_env(parent: &ComputedValues)]
+            fn set(&self, values: &mut ComputedValues, _value: Inherit) {
+                $class::copy(values, parent);
+            }
+        }
+
+        impl AttributeHasValue<Initial> for $class {
+            fn set(&self, values: &mut ComputedValues, _value: Initial) {
+                $class::copy(values, &ComputedValues::default());
+            }
+        }
+
+        impl AttributeHasValue<Unset> for $class {
+            fn set(&self, values: &mut ComputedValues, _value: Unset) {
+                if $class::INHERITED {
+                    self.set(values, Inherit);
+                } else {
+                    self.set(values, Initial);
+                }
+            }
+        }
+
+        impl AttributeHasValue<Var> for $class {
+            fn set(&self, values: &mut ComputedValues, var: Var) {
+                let value = match var.get() {
+                    Some(value) => value,
+                    None => return,
+                };
+                if !self.set_value(values, &value) {
+                    log::warn!(
+                        "style variable {} can't be used for {}: {:?}",
+                        var.name(),
+                        stringify!($name),
+                        value
+                    );
+                }
+            }
+        }
+
         pub fn $name() -> $class {
             $class
         }
     }
 }
 
-define_attribute! {
-    text_size(TextSize) {
+/// A value for an attribute whose type is only known at runtime.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum AnyValue {
+    Value(Value),
+    Inherit,
+    Initial,
+    Unset,
+}
+
+/// Defines every attribute, along with `inherit_defaults`, which gives a
+/// node the parent's values for all of the attributes marked `inherited`,
+/// and `set_by_name` for stylesheets parsed at runtime.
+macro_rules! define_attributes {
+    ( $( $name:ident($class:ident) $( $inherited:ident )? { $( $body:tt )* } )+ ) => {
+        $(
+            define_attribute! {
+                $name($class) $( $inherited )? { $( $body )* }
+            }
+        )+
+
+        pub(crate) fn inherit_defaults(values: &mut ComputedValues, parent: &ComputedValues) {
+            $(
+                if $class::INHERITED {
+                    $class::copy(values, parent);
+                }
+            )+
+        }
+
+        pub(crate) const NAMES: &[&str] = &[ $( stringify!($name) ),+ ];
+
+        /// Sets the attribute called `name`. Fails if there is no such
+        /// attribute, or if it doesn't accept the value.
+        pub(crate) fn set_by_name(
+            name: &str,
+            values: &mut ComputedValues,
+            value: &AnyValue,
+        ) -> Result<(), String> {
+            match name {
+                $(
+                    stringify!($name) => {
+                        match value {
+                            AnyValue::Inherit => $class.set(values, Inherit),
+                            AnyValue::Initial => $class.set(values, Initial),
+                            AnyValue::Unset => $class.set(values, Unset),
+                            AnyValue::Value(value) => {
+                                if !$class.set_value(values, value) {
+                                    return Err(format!(
+                                        "{} doesn't accept {:?}",
+                                        stringify!($name),
+                                        value
+                                    ));
+                                }
+                            }
+                        }
+                        Ok(())
+                    }
+                )+
+                _ => Err(format!("unknown attribute `{}`", name)),
+            }
+        }
+
+        /// Checks that the attribute called `name` exists and accepts
+        /// `value`, without setting it.
+        pub(crate) fn check_by_name(name: &str, value: &Value) -> Result<(), String> {
+            match name {
+                $(
+                    stringify!($name) => {
+                        if $class::accepts(value) {
+                            Ok(())
+                        } else {
+                            Err(format!("{} doesn't accept {:?}", stringify!($name), value))
+                        }
+                    }
+                )+
+                _ => Err(format!("unknown attribute `{}`", name)),
+            }
+        }
+    };
+}
+
+define_attributes! {
+    text_size(TextSize) inherited {
+        copy => |values, from| {
+            values.text_size = from.text_size
+        }
         Length => |values, value| {
             values.text_size = value.into()
         }
     }
-}
 
-define_attribute! {
-    text_color(TextColor) {
+    text_color(TextColor) inherited {
+        copy => |values, from| {
+            values.text_color = from.text_color
+        }
         Color => |values, value| {
             values.text_color = value
         }
     }
-}
 
-define_attribute! {
     background_color(BackgroundColor) {
+        copy => |values, from| {
+            values.background_color = from.background_color
+        }
         Color => |values, value| {
             values.background_color = value
         }
     }
-}
 
-define_attribute! {
     width(Width) {
+        copy => |values, from| {
+            block!(values, from, width)
+        }
         Length => |values, value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.width = Some(value.into());
             }
         }
     }
-}
 
-define_attribute! {
     height(Height) {
+        copy => |values, from| {
+            block!(values, from, height)
+        }
         Length => |values, value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.height = Some(value.into());
             }
         }
     }
-}
 
-define_attribute! {
     display(DisplayAttr) {
+        copy => |values, from| {
+            values.display = match from.display {
+                DisplayType::None => DisplayType::None,
+                DisplayType::Inline(_) => DisplayType::Inline(Default::default()),
+                DisplayType::Block(_) => DisplayType::Block(Default::default()),
+                DisplayType::InlineBlock(_) => DisplayType::InlineBlock(Default::default()),
+            }
+        }
+        keyword::None => |values, _value| {
+            values.display = DisplayType::None
+        }
+        keyword::Inline => |values, _value| {
+            values.display = DisplayType::Inline(Default::default())
+        }
         keyword::Block => |values, _value| {
             values.display = DisplayType::Block(Default::default())
         }
+        keyword::InlineBlock => |values, _value| {
+            values.display = DisplayType::InlineBlock(Default::default())
+        }
+    }
+
+    visibility(VisibilityAttr) inherited {
+        copy => |values, from| {
+            values.visibility = from.visibility
+        }
+        keyword::Visible => |values, _value| {
+            values.visibility = Visibility::Visible
+        }
+        keyword::Hidden => |values, _value| {
+            values.visibility = Visibility::Hidden
+        }
     }
-}
 
-define_attribute! {
     direction(DirectionAttr) {
+        copy => |values, from| {
+            block!(values, from, direction)
+        }
         keyword::Horizontal => |values, _value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.direction = Direction::Horizontal;
             }
         }
         keyword::Vertical => |values, _value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.direction = Direction::Vertical;
             }
         }
     }
-}
 
-define_attribute! {
     padding(Padding) {
+        copy => |values, from| {
+            block!(values, from, padding)
+        }
         Length => |values, value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.padding = LogicalSideOffsets::from_length_all_same(value.into());
             }
         }
         Edges<Length> => |values, value, parent| {
-            if let DisplayType::Block(ref mut block) = values.display {
-                let parent_values = if let DisplayType::Block(ref parent_block) = parent.display {
-                    parent_block.padding
-                } else {
-                    LogicalSideOffsets::default()
-                };
+            if let Some(block) = values.display.block_mut() {
+                let parent_values = parent
+                    .display
+                    .block()
+                    .map(|parent_block| parent_block.padding)
+                    .unwrap_or_default();
                 block.padding = LogicalSideOffsets::from_lengths(
-                    value.top.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.left)),
+                    value.top.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.top)),
                     value.right.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.right)),
                     value.bottom.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.bottom)),
                     value.left.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.left)),
@@ -122,35 +335,107 @@ define_attribute! {
             }
         }
     }
-}
 
-define_attribute! {
     margin(Margin) {
+        copy => |values, from| {
+            block!(values, from, margin, margin_auto)
+        }
         Length => |values, value| {
-            if let DisplayType::Block(ref mut block) = values.display {
+            if let Some(block) = values.display.block_mut() {
                 block.margin = LogicalSideOffsets::from_length_all_same(value.into());
+                block.margin_auto = StyleEdges::new_all_same(false);
             }
         }
-        Edges<Length> => |values, value, parent| {
-            if let DisplayType::Block(ref mut block) = values.display {
-                let parent_values = if let DisplayType::Block(ref parent_block) = parent.display {
-                    parent_block.margin
-                } else {
-                    LogicalSideOffsets::default()
+        keyword::Auto => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.margin = LogicalSideOffsets::new_all_same(0.0);
+                block.margin_auto = StyleEdges::new_all_same(true);
+            }
+        }
+        Edges<LengthOrAuto> => |values, value, parent| {
+            if let Some(block) = values.display.block_mut() {
+                let (parent_values, parent_auto) = parent
+                    .display
+                    .block()
+                    .map(|parent_block| (parent_block.margin, parent_block.margin_auto))
+                    .unwrap_or_default();
+                let side = |value: Option<LengthOrAuto>,
+                            parent_value: f32,
+                            parent_auto: bool|
+                 -> (LogicalLength, bool) {
+                    match value {
+                        Some(LengthOrAuto::Length(length)) => (length.into(), false),
+                        Some(LengthOrAuto::Auto) => (LogicalLength::new(0.0), true),
+                        None => (LogicalLength::new(parent_value), parent_auto),
+                    }
+                };
+                let (top, top_auto) = side(value.top, parent_values.top, parent_auto.top);
+                let (right, right_auto) = side(value.right, parent_values.right, parent_auto.right);
+                let (bottom, bottom_auto) =
+                    side(value.bottom, parent_values.bottom, parent_auto.bottom);
+                let (left, left_auto) = side(value.left, parent_values.left, parent_auto.left);
+                block.margin = LogicalSideOffsets::from_lengths(top, right, bottom, left);
+                block.margin_auto = StyleEdges {
+                    left: left_auto,
+                    right: right_auto,
+                    top: top_auto,
+                    bottom: bottom_auto,
                 };
-                block.margin = LogicalSideOffsets::from_lengths(
-                    value.top.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.left)),
-                    value.right.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.right)),
-                    value.bottom.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.bottom)),
-                    value.left.map(Into::into).unwrap_or_else(|| LogicalLength::new(parent_values.left)),
-                );
             }
         }
     }
-}
 
-define_attribute! {
+    align_items(AlignItems) {
+        copy => |values, from| {
+            block!(values, from, align_items)
+        }
+        keyword::Start => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_items = Alignment::Start;
+            }
+        }
+        keyword::Center => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_items = Alignment::Center;
+            }
+        }
+        keyword::End => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_items = Alignment::End;
+            }
+        }
+    }
+
+    align_self(AlignSelf) {
+        copy => |values, from| {
+            block!(values, from, align_self)
+        }
+        keyword::Auto => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_self = None;
+            }
+        }
+        keyword::Start => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_self = Some(Alignment::Start);
+            }
+        }
+        keyword::Center => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_self = Some(Alignment::Center);
+            }
+        }
+        keyword::End => |values, _value| {
+            if let Some(block) = values.display.block_mut() {
+                block.align_self = Some(Alignment::End);
+            }
+        }
+    }
+
     border(BorderAttr) {
+        copy => |values, from| {
+            values.border = from.border
+        }
         Border => |values, value| {
             values.border = StyleEdges::new_all_same(value);
         }
@@ -163,10 +448,11 @@ define_attribute! {
             };
         }
     }
-}
 
-define_attribute! {
     corner_radius(CornerRadiusAttr) {
+        copy => |values, from| {
+            values.corner_radius = from.corner_radius
+        }
         Length => |values, value| {
             values.corner_radius = StyleCorners::new_all_same(value.into());
         }
@@ -197,3 +483,75 @@ margin
 width
 height
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::{BlockValues, InlineValues};
+    use crate::style_impl::{apply, pixels, types};
+
+    #[test]
+    fn applies_display_keywords() {
+        let mut values = ComputedValues::default();
+        apply(&mut values, display(), keyword::none());
+        assert_eq!(values.display, DisplayType::None);
+        apply(&mut values, display(), keyword::inline());
+        assert_eq!(values.display, DisplayType::Inline(InlineValues {}));
+        apply(&mut values, display(), keyword::inline_block());
+        assert_eq!(
+            values.display,
+            DisplayType::InlineBlock(BlockValues::default())
+        );
+        apply(&mut values, display(), keyword::block());
+        assert_eq!(values.display, DisplayType::Block(BlockValues::default()));
+    }
+
+    #[test]
+    fn applies_block_attributes_to_inline_blocks() {
+        let mut values = ComputedValues::default();
+        apply(&mut values, display(), keyword::inline_block());
+        apply(&mut values, width(), pixels(40.0));
+        assert_eq!(
+            values.display.block().unwrap().width,
+            Some(LogicalLength::new(40.0))
+        );
+
+        apply(&mut values, display(), keyword::inline());
+        apply(&mut values, width(), pixels(40.0));
+        assert_eq!(values.display.block(), None);
+    }
+
+    #[test]
+    fn applies_visibility_keywords() {
+        let mut values = ComputedValues::default();
+        assert_eq!(values.visibility, Visibility::Visible);
+        apply(&mut values, visibility(), keyword::hidden());
+        assert_eq!(values.visibility, Visibility::Hidden);
+        apply(&mut values, visibility(), keyword::visible());
+        assert_eq!(values.visibility, Visibility::Visible);
+    }
+
+    #[test]
+    fn falls_back_to_the_parent_for_unset_edges() {
+        let mut parent = ComputedValues::default();
+        apply(&mut parent, padding(), pixels(2.0));
+        apply(&mut parent, margin(), pixels(2.0));
+        if let Some(block) = parent.display.block_mut() {
+            block.padding.top = 10.0;
+            block.margin.top = 10.0;
+        }
+
+        let mut values = ComputedValues::default();
+        illicit::Layer::new().offer(parent).enter(|| {
+            apply(
+                &mut values,
+                padding(),
+                types::Edges::new().left(pixels(4.0)),
+            );
+            apply(&mut values, margin(), types::Edges::new().left(pixels(4.0)));
+        });
+        let block = values.display.block().unwrap();
+        assert_eq!(block.padding, LogicalSideOffsets::new(10.0, 2.0, 2.0, 4.0));
+        assert_eq!(block.margin, LogicalSideOffsets::new(10.0, 2.0, 2.0, 4.0));
+    }
+}
@@ -2,12 +2,58 @@ use super::Length;
 use crate::style::{Border, BorderStyle};
 use crate::Color;
 
+// Channels are 0-255, hues are in degrees, and saturation, lightness,
+// whiteness, blackness and amounts are percentages. Alpha is 0-1,
+// except in `rgba` where it's a channel like the others.
+
 pub fn rgb(red: f64, green: f64, blue: f64) -> Color {
-    Color::new(red as u8, green as u8, blue as u8, 255)
+    Color::from_channels(red, green, blue, 255.0)
 }
 
 pub fn rgba(red: f64, green: f64, blue: f64, alpha: f64) -> Color {
-    Color::new(red as u8, green as u8, blue as u8, alpha as u8)
+    Color::from_channels(red, green, blue, alpha)
+}
+
+pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Color {
+    Color::from_hsl(hue, saturation, lightness, 1.0)
+}
+
+pub fn hsla(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Color {
+    Color::from_hsl(hue, saturation, lightness, alpha)
+}
+
+pub fn hwb(hue: f64, whiteness: f64, blackness: f64) -> Color {
+    Color::from_hwb(hue, whiteness, blackness, 1.0)
+}
+
+pub fn mix(color: Color, other: Color, amount: f64) -> Color {
+    color.mix(other, amount)
+}
+
+pub fn lighten(color: Color, amount: f64) -> Color {
+    color.lighten(amount)
+}
+
+pub fn darken(color: Color, amount: f64) -> Color {
+    color.darken(amount)
+}
+
+pub fn with_alpha(color: Color, alpha: f64) -> Color {
+    color.with_alpha(alpha)
+}
+
+pub fn min(a: Length, b: Length) -> Length {
+    a.min(b)
+}
+
+pub fn max(a: Length, b: Length) -> Length {
+    a.max(b)
+}
+
+/// Keeps `value` between `min` and `max`, with `min` winning if they
+/// overlap.
+pub fn clamp(min: Length, value: Length, max: Length) -> Length {
+    value.min(max).max(min)
 }
 
 pub fn border(width: Length, style: impl Into<BorderStyle>, color: Color) -> Border {
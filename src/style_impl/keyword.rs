@@ -1,32 +1,79 @@
-use crate::style::{BorderStyle, Direction, Display};
+use super::var::FromValue;
+use crate::style::{Alignment, BorderStyle, Direction, Display, Value, Visibility};
+
+/// Named colors are written like keywords, so they live here as well.
+pub use crate::util::color::named::*;
 
 macro_rules! keyword {
-    ($name:ident : $class:ident => $enum:ty as $variant:ident) => {
+    ($name:ident : $class:ident => $( $enum:ty as $variant:ident ),*) => {
+        #[derive(Copy, Clone, PartialEq, Debug)]
         pub struct $class;
 
         pub fn $name() -> $class {
             $class
         }
 
-        impl Into<$enum> for $class {
-            fn into(self) -> $enum {
-                <$enum>::$variant
+        impl From<$class> for Value {
+            fn from(_value: $class) -> Value {
+                Value::Keyword(stringify!($name))
+            }
+        }
+
+        impl FromValue for $class {
+            fn from_value(value: &Value) -> Option<Self> {
+                match *value {
+                    Value::Keyword(stringify!($name)) => Some($class),
+                    _ => Option::None,
+                }
             }
         }
+
+        $(
+            impl Into<$enum> for $class {
+                fn into(self) -> $enum {
+                    <$enum>::$variant
+                }
+            }
+        )*
     };
 }
 
-keyword!(block: Block => Display as Block);
-keyword!(inline: Inline => Display as Inline);
-keyword!(horizontal: Horizontal => Direction as Horizontal);
-keyword!(vertical: Vertical => Direction as Vertical);
-keyword!(none: None => BorderStyle as None);
-keyword!(solid: Solid => BorderStyle as Solid);
-keyword!(double: Double => BorderStyle as Double);
-keyword!(dotted: Dotted => BorderStyle as Dotted);
-keyword!(dashed: Dashed => BorderStyle as Dashed);
-keyword!(hidden: Hidden => BorderStyle as Hidden);
-keyword!(groove: Groove => BorderStyle as Groove);
-keyword!(ridge: Ridge => BorderStyle as Ridge);
-keyword!(inset: Inset => BorderStyle as Inset);
-keyword!(outset: Outset => BorderStyle as Outset);
+/// Defines every keyword, along with `lookup`, which finds a keyword's
+/// name from a string for stylesheets parsed at runtime.
+macro_rules! keywords {
+    ( $( $name:ident : $class:ident => $( $enum:ty as $variant:ident ),* ; )+ ) => {
+        $(
+            keyword!($name: $class => $( $enum as $variant ),*);
+        )+
+
+        pub(crate) fn lookup(name: &str) -> Option<&'static str> {
+            match name {
+                $( stringify!($name) => Some(stringify!($name)), )+
+                _ => Option::None,
+            }
+        }
+    };
+}
+
+keywords! {
+    block: Block => Display as Block;
+    inline: Inline => Display as Inline;
+    inline_block: InlineBlock => Display as InlineBlock;
+    horizontal: Horizontal => Direction as Horizontal;
+    vertical: Vertical => Direction as Vertical;
+    start: Start => Alignment as Start;
+    center: Center => Alignment as Center;
+    end: End => Alignment as End;
+    visible: Visible => Visibility as Visible;
+    none: None => BorderStyle as None, Display as None;
+    solid: Solid => BorderStyle as Solid;
+    double: Double => BorderStyle as Double;
+    dotted: Dotted => BorderStyle as Dotted;
+    dashed: Dashed => BorderStyle as Dashed;
+    hidden: Hidden => BorderStyle as Hidden, Visibility as Hidden;
+    groove: Groove => BorderStyle as Groove;
+    ridge: Ridge => BorderStyle as Ridge;
+    inset: Inset => BorderStyle as Inset;
+    outset: Outset => BorderStyle as Outset;
+    auto: Auto =>;
+}
@@ -7,6 +7,7 @@ pub mod func;
 pub mod keyword;
 pub mod state;
 pub mod types;
+pub mod var;
 
 pub trait Attribute {}
 
@@ -14,8 +15,18 @@ pub trait AttributeHasValue<Value> {
     fn set(&self, values: &mut ComputedValues, value: Value);
 }
 
+/// Takes the parent's value.
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Inherit;
 
+/// Resets the attribute to its value in `ComputedValues::default()`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Initial;
+
+/// Acts as `Inherit` for inherited attributes and `Initial` otherwise.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Unset;
+
 pub fn apply<Attr, Value>(values: &mut ComputedValues, attribute: Attr, value: Value)
 where
     Attr: Attribute,
@@ -24,7 +35,7 @@ where
     attribute.set(values, value);
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Length(isize);
 
 impl Into<LogicalLength> for Length {
@@ -49,6 +60,38 @@ impl ops::Sub for Length {
     }
 }
 
+impl ops::Neg for Length {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Length(-self.0)
+    }
+}
+
+impl ops::Mul<f64> for Length {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Length((self.0 as f64 * rhs).round() as isize)
+    }
+}
+
+impl ops::Mul<Length> for f64 {
+    type Output = Length;
+
+    fn mul(self, rhs: Length) -> Length {
+        rhs * self
+    }
+}
+
+impl ops::Div<f64> for Length {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Length((self.0 as f64 / rhs).round() as isize)
+    }
+}
+
 pub fn pixels(value: f64) -> Length {
     Length((value * 60.0) as isize)
 }
@@ -66,3 +109,30 @@ pub fn view_width(value: f64) -> Length {
 pub fn view_height(value: f64) -> Length {
     Length((value / 100.0 * viewport_size.height as f64 * 60.0) as isize)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negates_lengths() {
+        assert_eq!(-pixels(2.0), pixels(-2.0));
+        assert_eq!(-(pixels(3.0) - pixels(5.0)), pixels(2.0));
+    }
+
+    #[test]
+    fn scales_lengths_by_numbers() {
+        assert_eq!(pixels(3.0) * 2.0, pixels(6.0));
+        assert_eq!(2.0 * pixels(3.0), pixels(6.0));
+        assert_eq!(pixels(10.0) / 4.0, pixels(2.5));
+        assert_eq!(pixels(1.0) * -1.5, pixels(-1.5));
+    }
+
+    #[test]
+    fn rounds_scaled_lengths_to_the_nearest_unit() {
+        assert_eq!(Length(1) * 0.4, Length(0));
+        assert_eq!(Length(1) * 0.5, Length(1));
+        assert_eq!(Length(5) / 2.0, Length(3));
+        assert_eq!(Length(-5) / 2.0, Length(-3));
+    }
+}
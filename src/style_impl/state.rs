@@ -1,4 +1,5 @@
 use crate::dom::element::ElementState;
+use crate::style::Structural;
 
 pub fn press() -> ElementState {
     ElementState::Press
@@ -7,3 +8,31 @@ pub fn press() -> ElementState {
 pub fn hover() -> ElementState {
     ElementState::Hover
 }
+
+pub fn focus() -> ElementState {
+    ElementState::Focus
+}
+
+pub fn checked() -> ElementState {
+    ElementState::Checked
+}
+
+pub fn open() -> ElementState {
+    ElementState::Open
+}
+
+pub fn first_child() -> Structural {
+    Structural::FirstChild
+}
+
+pub fn last_child() -> Structural {
+    Structural::LastChild
+}
+
+pub fn only_child() -> Structural {
+    Structural::OnlyChild
+}
+
+pub fn nth_child(a: i32, b: i32) -> Structural {
+    Structural::NthChild { a, b }
+}
@@ -1,4 +1,26 @@
-#[derive(Default)]
+use super::var::{FromValue, Var};
+use super::{keyword, Length};
+
+/// A length, or `auto` to let layout decide.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LengthOrAuto {
+    Length(Length),
+    Auto,
+}
+
+impl From<Length> for LengthOrAuto {
+    fn from(value: Length) -> Self {
+        LengthOrAuto::Length(value)
+    }
+}
+
+impl From<keyword::Auto> for LengthOrAuto {
+    fn from(_value: keyword::Auto) -> Self {
+        LengthOrAuto::Auto
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
 pub struct Edges<Value> {
     pub left: Option<Value>,
     pub right: Option<Value>,
@@ -16,23 +38,33 @@ impl<Value> Edges<Value> {
         }
     }
 
-    pub fn left(mut self, value: Value) -> Self {
-        self.left = Some(value);
+    pub fn left(mut self, value: impl Into<Value>) -> Self {
+        self.left = Some(value.into());
+        self
+    }
+
+    pub fn right(mut self, value: impl Into<Value>) -> Self {
+        self.right = Some(value.into());
         self
     }
 
-    pub fn right(mut self, value: Value) -> Self {
-        self.right = Some(value);
+    pub fn top(mut self, value: impl Into<Value>) -> Self {
+        self.top = Some(value.into());
         self
     }
 
-    pub fn top(mut self, value: Value) -> Self {
-        self.top = Some(value);
+    pub fn bottom(mut self, value: impl Into<Value>) -> Self {
+        self.bottom = Some(value.into());
         self
     }
 
-    pub fn bottom(mut self, value: Value) -> Self {
-        self.bottom = Some(value);
+    /// Sets the field chosen by `field` from a style variable, leaving
+    /// it unset if the variable doesn't convert to `Value`.
+    pub fn set_var(mut self, field: fn(&mut Self) -> &mut Option<Value>, var: Var) -> Self
+    where
+        Value: FromValue,
+    {
+        *field(&mut self) = var.get().and_then(|value| Value::from_value(&value));
         self
     }
 
@@ -41,7 +73,7 @@ impl<Value> Edges<Value> {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, PartialEq, Debug)]
 pub struct Corners<Value> {
     pub top_left: Option<Value>,
     pub top_right: Option<Value>,
@@ -59,23 +91,33 @@ impl<Value> Corners<Value> {
         }
     }
 
-    pub fn top_left(mut self, value: Value) -> Self {
-        self.top_left = Some(value);
+    pub fn top_left(mut self, value: impl Into<Value>) -> Self {
+        self.top_left = Some(value.into());
+        self
+    }
+
+    pub fn top_right(mut self, value: impl Into<Value>) -> Self {
+        self.top_right = Some(value.into());
         self
     }
 
-    pub fn top_right(mut self, value: Value) -> Self {
-        self.top_right = Some(value);
+    pub fn bottom_left(mut self, value: impl Into<Value>) -> Self {
+        self.bottom_left = Some(value.into());
         self
     }
 
-    pub fn bottom_left(mut self, value: Value) -> Self {
-        self.bottom_left = Some(value);
+    pub fn bottom_right(mut self, value: impl Into<Value>) -> Self {
+        self.bottom_right = Some(value.into());
         self
     }
 
-    pub fn bottom_right(mut self, value: Value) -> Self {
-        self.bottom_right = Some(value);
+    /// Sets the field chosen by `field` from a style variable, leaving
+    /// it unset if the variable doesn't convert to `Value`.
+    pub fn set_var(mut self, field: fn(&mut Self) -> &mut Option<Value>, var: Var) -> Self
+    where
+        Value: FromValue,
+    {
+        *field(&mut self) = var.get().and_then(|value| Value::from_value(&value));
         self
     }
 
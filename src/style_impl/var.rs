@@ -0,0 +1,183 @@
+use super::types::{Corners, Edges, LengthOrAuto};
+use super::Length;
+use crate::style::{Border, Value, Variables};
+use crate::Color;
+use log::warn;
+
+/// A reference to a style variable, created by `var(name)` or
+/// `var(name, fallback)`. Any attribute accepts one, and uses the first
+/// of its value types that the variable converts to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Var {
+    name: &'static str,
+    fallback: Option<Value>,
+}
+
+impl Var {
+    pub fn new(name: &'static str, fallback: Option<Value>) -> Var {
+        Var { name, fallback }
+    }
+
+    /// Looks the variable up in the variables of the node being styled.
+    pub fn get(&self) -> Option<Value> {
+        illicit::get::<Variables>()
+            .ok()
+            .and_then(|variables| variables.get(self.name).cloned())
+            .or_else(|| self.fallback.clone())
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Conversion from a variable's value to one of the types an attribute
+/// accepts.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+/// Types that a `var()` can be used as inside a larger expression, like
+/// `var(gap) + 2 px`. There has to be a result even when the variable
+/// isn't set, so these need a value to fall back to.
+pub trait VarType: FromValue {
+    fn missing() -> Self;
+}
+
+pub fn var<T>(var: Var) -> T
+where
+    T: VarType,
+{
+    match var.get() {
+        Some(value) => T::from_value(&value).unwrap_or_else(|| {
+            warn!(
+                "style variable {} has the wrong type: {:?}",
+                var.name, value
+            );
+            T::missing()
+        }),
+        None => {
+            warn!("style variable {} is not set", var.name);
+            T::missing()
+        }
+    }
+}
+
+pub fn declare(variables: &mut Variables, name: &str, value: impl Into<Value>) {
+    variables.insert(name, value.into());
+}
+
+pub fn declare_var(variables: &mut Variables, name: &str, value: Var) {
+    if let Some(value) = value.get() {
+        variables.insert(name, value);
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl VarType for f64 {
+    fn missing() -> Self {
+        0.0
+    }
+}
+
+impl FromValue for Length {
+    fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Length(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl VarType for Length {
+    fn missing() -> Self {
+        super::pixels(0.0)
+    }
+}
+
+impl FromValue for Color {
+    fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Color(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl VarType for Color {
+    fn missing() -> Self {
+        Color::clear()
+    }
+}
+
+impl FromValue for LengthOrAuto {
+    fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Length(value) => Some(LengthOrAuto::Length(value)),
+            Value::Keyword("auto") => Some(LengthOrAuto::Auto),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Border {
+    fn from_value(value: &Value) -> Option<Self> {
+        match *value {
+            Value::Border(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a field that is set, failing if it doesn't convert.
+fn field<V>(value: &Option<Value>) -> Option<Option<V>>
+where
+    V: FromValue,
+{
+    match value {
+        Some(value) => V::from_value(value).map(Some),
+        None => Some(None),
+    }
+}
+
+impl<V> FromValue for Edges<V>
+where
+    V: FromValue,
+{
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Edges(edges) => Some(Edges {
+                left: field(&edges.left)?,
+                right: field(&edges.right)?,
+                top: field(&edges.top)?,
+                bottom: field(&edges.bottom)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl<V> FromValue for Corners<V>
+where
+    V: FromValue,
+{
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Corners(corners) => Some(Corners {
+                top_left: field(&corners.top_left)?,
+                top_right: field(&corners.top_right)?,
+                bottom_left: field(&corners.bottom_left)?,
+                bottom_right: field(&corners.bottom_right)?,
+            }),
+            _ => None,
+        }
+    }
+}
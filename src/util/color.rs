@@ -32,27 +32,376 @@ impl Color {
         Color::new(0, 0, 0, 255)
     }
 
-    // Parse a color from `R,G,B` or `R,G,B,A` format.
+    /// Creates a color from red, green, blue and alpha channels in the
+    /// range 0-255, rounding and clamping them.
+    pub fn from_channels(red: f64, green: f64, blue: f64, alpha: f64) -> Color {
+        Color::new(channel(red), channel(green), channel(blue), channel(alpha))
+    }
+
+    /// Creates a color from a hue in degrees, saturation and lightness in
+    /// percent, and alpha from 0 to 1.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Color {
+        let saturation = fraction(saturation / 100.0);
+        let lightness = fraction(lightness / 100.0);
+        let amount = saturation * lightness.min(1.0 - lightness);
+        let hue = hue.rem_euclid(360.0) / 30.0;
+        let component = |n: f64| {
+            let k = (n + hue) % 12.0;
+            lightness - amount * (k - 3.0).min(9.0 - k).min(1.0).max(-1.0)
+        };
+        Color::from_channels(
+            component(0.0) * 255.0,
+            component(8.0) * 255.0,
+            component(4.0) * 255.0,
+            fraction(alpha) * 255.0,
+        )
+    }
+
+    /// Creates a color from a hue in degrees, whiteness and blackness in
+    /// percent, and alpha from 0 to 1.
+    pub fn from_hwb(hue: f64, whiteness: f64, blackness: f64, alpha: f64) -> Color {
+        let mut whiteness = fraction(whiteness / 100.0);
+        let mut blackness = fraction(blackness / 100.0);
+        if whiteness + blackness > 1.0 {
+            let total = whiteness + blackness;
+            whiteness /= total;
+            blackness /= total;
+        }
+        let pure = Color::from_hsl(hue, 100.0, 50.0, 1.0);
+        let component =
+            |value: u8| (value as f64 / 255.0 * (1.0 - whiteness - blackness) + whiteness) * 255.0;
+        Color::from_channels(
+            component(pure.red),
+            component(pure.green),
+            component(pure.blue),
+            fraction(alpha) * 255.0,
+        )
+    }
+
+    /// Parses `rgb`, `rgba`, `rrggbb` or `rrggbbaa` hex digits, with or
+    /// without a leading `#`.
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).unwrap();
+        let pair = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+        match hex.len() {
+            3 => Some(Color::new(digit(0) * 17, digit(1) * 17, digit(2) * 17, 255)),
+            4 => Some(Color::new(
+                digit(0) * 17,
+                digit(1) * 17,
+                digit(2) * 17,
+                digit(3) * 17,
+            )),
+            6 => Some(Color::new(pair(0), pair(2), pair(4), 255)),
+            8 => Some(Color::new(pair(0), pair(2), pair(4), pair(6))),
+            _ => None,
+        }
+    }
+
+    /// Looks up a CSS named color, like `rebeccapurple`.
+    pub fn named(name: &str) -> Option<Color> {
+        NAMED_COLORS
+            .iter()
+            .find(|(other, _)| *other == name)
+            .map(|&(_, rgba)| Color::from_rgba_u32(rgba))
+    }
+
+    fn from_rgba_u32(rgba: u32) -> Color {
+        let [red, green, blue, alpha] = rgba.to_be_bytes();
+        Color::new(red, green, blue, alpha)
+    }
+
+    /// Returns the hue in degrees, and saturation and lightness in percent.
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let red = self.red as f64 / 255.0;
+        let green = self.green as f64 / 255.0;
+        let blue = self.blue as f64 / 255.0;
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness * 100.0);
+        }
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == red {
+            ((green - blue) / delta).rem_euclid(6.0)
+        } else if max == green {
+            (blue - red) / delta + 2.0
+        } else {
+            (red - green) / delta + 4.0
+        };
+        (hue * 60.0, saturation * 100.0, lightness * 100.0)
+    }
+
+    /// Blends `amount` percent of `other` into this color.
+    pub fn mix(self, other: Color, amount: f64) -> Color {
+        let amount = fraction(amount / 100.0);
+        let blend = |from: u8, to: u8| from as f64 + (to as f64 - from as f64) * amount;
+        Color::from_channels(
+            blend(self.red, other.red),
+            blend(self.green, other.green),
+            blend(self.blue, other.blue),
+            blend(self.alpha, other.alpha),
+        )
+    }
+
+    /// Raises the lightness by `amount` percentage points.
+    pub fn lighten(self, amount: f64) -> Color {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Color::from_hsl(
+            hue,
+            saturation,
+            lightness + amount,
+            self.alpha as f64 / 255.0,
+        )
+    }
+
+    /// Lowers the lightness by `amount` percentage points.
+    pub fn darken(self, amount: f64) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Replaces the alpha, given from 0 to 1.
+    pub fn with_alpha(self, alpha: f64) -> Color {
+        Color {
+            alpha: channel(fraction(alpha) * 255.0),
+            ..self
+        }
+    }
+
+    /// Parses a color the way styles write it: `#rrggbb` and the other
+    /// hex forms, `rgb(...)`, `rgba(...)`, `hsl(...)`, `hsla(...)`,
+    /// `hwb(...)` or a CSS named color. The older `R,G,B` and `R,G,B,A`
+    /// formats are still accepted.
     pub fn parse(string: &str) -> Result<Color, ()> {
-        let components = string
+        let string = string.trim();
+        if string.starts_with('#') {
+            return Color::from_hex(string).ok_or(());
+        }
+        if let Some(color) = Color::named(string) {
+            return Ok(color);
+        }
+        let (function, args) = match string.find('(') {
+            Some(index) if string.ends_with(')') => {
+                (string[..index].trim(), &string[index + 1..string.len() - 1])
+            }
+            Some(_) => return Err(()),
+            None => ("", string),
+        };
+        // Percent signs are optional, since the values are percentages
+        // either way.
+        let args = args
             .split(',')
-            .map(|s| s.parse::<u8>().map_err(|_| ()))
-            .collect::<Result<Vec<u8>, ()>>()?;
-        if components.len() == 4 {
-            Ok(Color::new(
-                components[0],
-                components[1],
-                components[2],
-                components[3],
-            ))
-        } else if components.len() == 3 {
-            Ok(Color::new(components[0], components[1], components[2], 255))
-        } else {
-            Err(())
+            .map(|arg| {
+                arg.trim()
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .map_err(|_| ())
+            })
+            .collect::<Result<Vec<f64>, ()>>()?;
+        match (function, &args[..]) {
+            ("" | "rgb" | "rgba", &[red, green, blue]) => {
+                Ok(Color::from_channels(red, green, blue, 255.0))
+            }
+            ("" | "rgba", &[red, green, blue, alpha]) => {
+                Ok(Color::from_channels(red, green, blue, alpha))
+            }
+            ("hsl", &[hue, saturation, lightness]) => {
+                Ok(Color::from_hsl(hue, saturation, lightness, 1.0))
+            }
+            ("hsla", &[hue, saturation, lightness, alpha]) => {
+                Ok(Color::from_hsl(hue, saturation, lightness, alpha))
+            }
+            ("hwb", &[hue, whiteness, blackness]) => {
+                Ok(Color::from_hwb(hue, whiteness, blackness, 1.0))
+            }
+            _ => Err(()),
         }
     }
 }
 
+fn channel(value: f64) -> u8 {
+    value.round().max(0.0).min(255.0) as u8
+}
+
+fn fraction(value: f64) -> f64 {
+    value.max(0.0).min(1.0)
+}
+
+/// Defines the table behind `Color::named`, and a function for each
+/// color so that `define_style!` can use them like keywords.
+macro_rules! named_colors {
+    ( $( $name:ident => $rgba:expr, )+ ) => {
+        const NAMED_COLORS: &[(&str, u32)] = &[ $( (stringify!($name), $rgba), )+ ];
+
+        pub mod named {
+            use super::Color;
+
+            $(
+                pub fn $name() -> Color {
+                    Color::from_rgba_u32($rgba)
+                }
+            )+
+        }
+    };
+}
+
+named_colors! {
+    aliceblue => 0xf0f8ffff,
+    antiquewhite => 0xfaebd7ff,
+    aqua => 0x00ffffff,
+    aquamarine => 0x7fffd4ff,
+    azure => 0xf0ffffff,
+    beige => 0xf5f5dcff,
+    bisque => 0xffe4c4ff,
+    black => 0x000000ff,
+    blanchedalmond => 0xffebcdff,
+    blue => 0x0000ffff,
+    blueviolet => 0x8a2be2ff,
+    brown => 0xa52a2aff,
+    burlywood => 0xdeb887ff,
+    cadetblue => 0x5f9ea0ff,
+    chartreuse => 0x7fff00ff,
+    chocolate => 0xd2691eff,
+    coral => 0xff7f50ff,
+    cornflowerblue => 0x6495edff,
+    cornsilk => 0xfff8dcff,
+    crimson => 0xdc143cff,
+    cyan => 0x00ffffff,
+    darkblue => 0x00008bff,
+    darkcyan => 0x008b8bff,
+    darkgoldenrod => 0xb8860bff,
+    darkgray => 0xa9a9a9ff,
+    darkgreen => 0x006400ff,
+    darkgrey => 0xa9a9a9ff,
+    darkkhaki => 0xbdb76bff,
+    darkmagenta => 0x8b008bff,
+    darkolivegreen => 0x556b2fff,
+    darkorange => 0xff8c00ff,
+    darkorchid => 0x9932ccff,
+    darkred => 0x8b0000ff,
+    darksalmon => 0xe9967aff,
+    darkseagreen => 0x8fbc8fff,
+    darkslateblue => 0x483d8bff,
+    darkslategray => 0x2f4f4fff,
+    darkslategrey => 0x2f4f4fff,
+    darkturquoise => 0x00ced1ff,
+    darkviolet => 0x9400d3ff,
+    deeppink => 0xff1493ff,
+    deepskyblue => 0x00bfffff,
+    dimgray => 0x696969ff,
+    dimgrey => 0x696969ff,
+    dodgerblue => 0x1e90ffff,
+    firebrick => 0xb22222ff,
+    floralwhite => 0xfffaf0ff,
+    forestgreen => 0x228b22ff,
+    fuchsia => 0xff00ffff,
+    gainsboro => 0xdcdcdcff,
+    ghostwhite => 0xf8f8ffff,
+    gold => 0xffd700ff,
+    goldenrod => 0xdaa520ff,
+    gray => 0x808080ff,
+    green => 0x008000ff,
+    greenyellow => 0xadff2fff,
+    grey => 0x808080ff,
+    honeydew => 0xf0fff0ff,
+    hotpink => 0xff69b4ff,
+    indianred => 0xcd5c5cff,
+    indigo => 0x4b0082ff,
+    ivory => 0xfffff0ff,
+    khaki => 0xf0e68cff,
+    lavender => 0xe6e6faff,
+    lavenderblush => 0xfff0f5ff,
+    lawngreen => 0x7cfc00ff,
+    lemonchiffon => 0xfffacdff,
+    lightblue => 0xadd8e6ff,
+    lightcoral => 0xf08080ff,
+    lightcyan => 0xe0ffffff,
+    lightgoldenrodyellow => 0xfafad2ff,
+    lightgray => 0xd3d3d3ff,
+    lightgreen => 0x90ee90ff,
+    lightgrey => 0xd3d3d3ff,
+    lightpink => 0xffb6c1ff,
+    lightsalmon => 0xffa07aff,
+    lightseagreen => 0x20b2aaff,
+    lightskyblue => 0x87cefaff,
+    lightslategray => 0x778899ff,
+    lightslategrey => 0x778899ff,
+    lightsteelblue => 0xb0c4deff,
+    lightyellow => 0xffffe0ff,
+    lime => 0x00ff00ff,
+    limegreen => 0x32cd32ff,
+    linen => 0xfaf0e6ff,
+    magenta => 0xff00ffff,
+    maroon => 0x800000ff,
+    mediumaquamarine => 0x66cdaaff,
+    mediumblue => 0x0000cdff,
+    mediumorchid => 0xba55d3ff,
+    mediumpurple => 0x9370dbff,
+    mediumseagreen => 0x3cb371ff,
+    mediumslateblue => 0x7b68eeff,
+    mediumspringgreen => 0x00fa9aff,
+    mediumturquoise => 0x48d1ccff,
+    mediumvioletred => 0xc71585ff,
+    midnightblue => 0x191970ff,
+    mintcream => 0xf5fffaff,
+    mistyrose => 0xffe4e1ff,
+    moccasin => 0xffe4b5ff,
+    navajowhite => 0xffdeadff,
+    navy => 0x000080ff,
+    oldlace => 0xfdf5e6ff,
+    olive => 0x808000ff,
+    olivedrab => 0x6b8e23ff,
+    orange => 0xffa500ff,
+    orangered => 0xff4500ff,
+    orchid => 0xda70d6ff,
+    palegoldenrod => 0xeee8aaff,
+    palegreen => 0x98fb98ff,
+    paleturquoise => 0xafeeeeff,
+    palevioletred => 0xdb7093ff,
+    papayawhip => 0xffefd5ff,
+    peachpuff => 0xffdab9ff,
+    peru => 0xcd853fff,
+    pink => 0xffc0cbff,
+    plum => 0xdda0ddff,
+    powderblue => 0xb0e0e6ff,
+    purple => 0x800080ff,
+    rebeccapurple => 0x663399ff,
+    red => 0xff0000ff,
+    rosybrown => 0xbc8f8fff,
+    royalblue => 0x4169e1ff,
+    saddlebrown => 0x8b4513ff,
+    salmon => 0xfa8072ff,
+    sandybrown => 0xf4a460ff,
+    seagreen => 0x2e8b57ff,
+    seashell => 0xfff5eeff,
+    sienna => 0xa0522dff,
+    silver => 0xc0c0c0ff,
+    skyblue => 0x87ceebff,
+    slateblue => 0x6a5acdff,
+    slategray => 0x708090ff,
+    slategrey => 0x708090ff,
+    snow => 0xfffafaff,
+    springgreen => 0x00ff7fff,
+    steelblue => 0x4682b4ff,
+    tan => 0xd2b48cff,
+    teal => 0x008080ff,
+    thistle => 0xd8bfd8ff,
+    tomato => 0xff6347ff,
+    turquoise => 0x40e0d0ff,
+    violet => 0xee82eeff,
+    wheat => 0xf5deb3ff,
+    white => 0xffffffff,
+    whitesmoke => 0xf5f5f5ff,
+    yellow => 0xffff00ff,
+    yellowgreen => 0x9acd32ff,
+    transparent => 0x00000000,
+}
+
 impl Into<ColorF> for Color {
     fn into(self) -> ColorF {
         ColorF::new(
@@ -79,3 +428,75 @@ impl fmt::Display for Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    #[test]
+    fn parses_hex() {
+        assert_eq!(
+            Color::parse("#1a2b3c"),
+            Ok(Color::new(0x1a, 0x2b, 0x3c, 255))
+        );
+        assert_eq!(Color::parse("#f0a"), Ok(Color::new(0xff, 0x00, 0xaa, 255)));
+        assert_eq!(
+            Color::parse("#f0a8"),
+            Ok(Color::new(0xff, 0x00, 0xaa, 0x88))
+        );
+        assert_eq!(
+            Color::parse("#1a2b3c4d"),
+            Ok(Color::new(0x1a, 0x2b, 0x3c, 0x4d))
+        );
+        assert_eq!(Color::parse("#1a2b3"), Err(()));
+        assert_eq!(Color::parse("#1a2b3g"), Err(()));
+    }
+
+    #[test]
+    fn parses_functions() {
+        assert_eq!(Color::parse("10,20,30"), Ok(Color::new(10, 20, 30, 255)));
+        assert_eq!(Color::parse("10,20,30,40"), Ok(Color::new(10, 20, 30, 40)));
+        assert_eq!(
+            Color::parse("rgb(10.6, 300, -4)"),
+            Ok(Color::new(11, 255, 0, 255))
+        );
+        assert_eq!(
+            Color::parse("hsl(120, 100%, 25%)"),
+            Ok(Color::new(0, 128, 0, 255))
+        );
+        assert_eq!(
+            Color::parse("hsla(0, 100, 50, 0.5)"),
+            Ok(Color::new(255, 0, 0, 128))
+        );
+        assert_eq!(
+            Color::parse("hwb(240, 20%, 20%)"),
+            Ok(Color::new(51, 51, 204, 255))
+        );
+        assert_eq!(Color::parse("hsl(120, 100%)"), Err(()));
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!(
+            Color::parse("rebeccapurple"),
+            Ok(Color::new(0x66, 0x33, 0x99, 255))
+        );
+        assert_eq!(Color::parse("transparent"), Ok(Color::clear()));
+        assert_eq!(Color::parse("notacolor"), Err(()));
+    }
+
+    #[test]
+    fn adjusts_colors() {
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 255);
+        assert_eq!(red.mix(blue, 25.0), Color::new(191, 0, 64, 255));
+        assert_eq!(red.lighten(25.0), Color::new(255, 128, 128, 255));
+        assert_eq!(red.darken(25.0), Color::new(128, 0, 0, 255));
+        assert_eq!(red.with_alpha(0.5), Color::new(255, 0, 0, 128));
+        let (hue, saturation, lightness) = Color::new(51, 102, 153, 255).to_hsl();
+        assert_eq!(
+            (hue.round(), saturation.round(), lightness.round()),
+            (210.0, 50.0, 40.0)
+        );
+    }
+}
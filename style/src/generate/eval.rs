@@ -1,8 +1,174 @@
-use crate::parse::{Expr, LengthUnit};
+use crate::parse::{BinaryExpr, Expr, LengthExpr, LengthUnit, Operator, VarExpr};
+use proc_macro2::Span;
+use proc_macro_error::emit_error;
 use quote::quote_spanned;
-use syn::{spanned::Spanned, Expr as SynExpr, Ident, LitFloat};
+use syn::{spanned::Spanned, Expr as SynExpr, Ident, LitFloat, LitStr};
+
+/// Where an expression appears, which decides what type a `var()` in
+/// that spot is resolved to.
+#[derive(Copy, Clone, PartialEq)]
+enum Position {
+    /// The whole value of an attribute or variable. The reference is
+    /// passed along as a `Var` and resolved by the attribute.
+    Value,
+    /// A function argument or struct field, where the type is inferred
+    /// from the parameter.
+    Argument,
+}
+
+/// What an arithmetic expression evaluates to.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Kind {
+    Number,
+    Length,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Number => "a number",
+            Kind::Length => "a length",
+        }
+    }
+}
+
+/// Functions from `style_impl::func` that return a length.
+const LENGTH_FUNCTIONS: &[&str] = &["min", "max", "clamp"];
+
+fn is_arithmetic(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::BinaryExpr(_) | Expr::Neg(_) | Expr::Paren(_) | Expr::LengthExpr(_)
+    )
+}
+
+/// Works out whether an operand is a number or a length. `var()`s can be
+/// either, so an expression made only of them gives `None`.
+fn infer(expr: &Expr) -> Result<Option<Kind>, (Span, String)> {
+    match expr {
+        Expr::Int(_) | Expr::Float(_) => Ok(Some(Kind::Number)),
+        Expr::Var(_) => Ok(None),
+        Expr::Paren(expr) => infer(&expr.expr),
+        Expr::Neg(expr) => infer(&expr.expr),
+        Expr::LengthExpr(expr) => infer_length(expr),
+        Expr::Call(call) if LENGTH_FUNCTIONS.contains(&&call.name.to_string()[..]) => {
+            Ok(Some(Kind::Length))
+        }
+        Expr::BinaryExpr(expr) => infer_binary(expr),
+        Expr::Error(_) => Ok(None),
+        expr => Err((
+            expr.span(),
+            "only numbers and lengths can be used in arithmetic".to_owned(),
+        )),
+    }
+}
+
+fn infer_length(expr: &LengthExpr) -> Result<Option<Kind>, (Span, String)> {
+    match infer(&expr.expr)? {
+        Some(Kind::Length) => Err((
+            expr.unit.span(),
+            "units can only follow a number, not a length".to_owned(),
+        )),
+        _ => Ok(Some(Kind::Length)),
+    }
+}
+
+fn infer_binary(expr: &BinaryExpr) -> Result<Option<Kind>, (Span, String)> {
+    let left = infer(&expr.left)?;
+    let right = infer(&expr.right)?;
+    let error = |message: String| Err((expr.oper.span(), message));
+    match expr.oper {
+        Operator::Add(_) | Operator::Sub(_) => match (left, right) {
+            (Some(left), Some(right)) if left != right => error(format!(
+                "can't combine {} and {}",
+                left.name(),
+                right.name()
+            )),
+            (Some(kind), _) | (_, Some(kind)) => Ok(Some(kind)),
+            (None, None) => Ok(None),
+        },
+        Operator::Mul(_) => match (left, right) {
+            (Some(Kind::Length), Some(Kind::Length)) => {
+                error("can't multiply two lengths, one side must be a number".to_owned())
+            }
+            (Some(Kind::Length), _) | (_, Some(Kind::Length)) => Ok(Some(Kind::Length)),
+            (Some(Kind::Number), Some(Kind::Number)) => Ok(Some(Kind::Number)),
+            _ => Ok(None),
+        },
+        Operator::Div(_) => match (left, right) {
+            (_, Some(Kind::Length)) => error("can't divide by a length".to_owned()),
+            (Some(kind), _) => Ok(Some(kind)),
+            (None, _) => Ok(None),
+        },
+    }
+}
+
+/// Generates an arithmetic expression that must evaluate to `kind`,
+/// after `infer` has checked it.
+fn generate_arith(attr_name: &Ident, expr: Expr, kind: Kind) -> SynExpr {
+    match expr {
+        Expr::Var(expr) => {
+            let span = expr.span();
+            let var = generate_var(attr_name, *expr);
+            match kind {
+                Kind::Number => parse_quote_spanned!(span => style_impl::var::var::<f64>(#var)),
+                Kind::Length => parse_quote_spanned!(
+                    span => style_impl::var::var::<style_impl::Length>(#var)
+                ),
+            }
+        }
+        Expr::Paren(expr) => generate_arith(attr_name, expr.expr, kind),
+        Expr::Neg(expr) => {
+            let span = expr.minus.span();
+            let inner = generate_arith(attr_name, expr.expr, kind);
+            parse_quote_spanned!(span => -(#inner))
+        }
+        Expr::BinaryExpr(exp) => {
+            let span = exp.oper.span();
+            let (left_kind, right_kind) = match exp.oper {
+                Operator::Add(_) | Operator::Sub(_) => (kind, kind),
+                Operator::Mul(_) if kind == Kind::Length => {
+                    match (infer(&exp.left), infer(&exp.right)) {
+                        (Ok(Some(Kind::Length)), _) | (Ok(None), Ok(Some(Kind::Number))) => {
+                            (Kind::Length, Kind::Number)
+                        }
+                        _ => (Kind::Number, Kind::Length),
+                    }
+                }
+                Operator::Div(_) => (kind, Kind::Number),
+                Operator::Mul(_) => (Kind::Number, Kind::Number),
+            };
+            let left = generate_arith(attr_name, exp.left, left_kind);
+            let right = generate_arith(attr_name, exp.right, right_kind);
+            let op = &exp.oper;
+            parse_quote_spanned!(span => (#left) #op (#right))
+        }
+        expr => generate_expr_at(attr_name, expr, Position::Argument),
+    }
+}
 
 pub fn generate_expr(attr_name: &Ident, expr: Expr) -> SynExpr {
+    generate_expr_at(attr_name, expr, Position::Value)
+}
+
+fn generate_var(attr_name: &Ident, expr: VarExpr) -> SynExpr {
+    let span = expr.span();
+    let name = LitStr::new(&expr.name.to_string(), expr.name.span());
+    match expr.fallback {
+        Some((_, fallback)) => {
+            let fallback = generate_expr_at(attr_name, fallback, Position::Value);
+            parse_quote_spanned!(
+                span => style_impl::var::Var::new(
+                    #name,
+                    Some(::std::convert::Into::<::moxie_native::style::Value>::into(#fallback)),
+                )
+            )
+        }
+        None => parse_quote_spanned!(span => style_impl::var::Var::new(#name, None)),
+    }
+}
+
+fn generate_expr_at(attr_name: &Ident, expr: Expr, position: Position) -> SynExpr {
     match expr {
         Expr::Int(int) => {
             let value_str = format!("{}f64", int.base10_digits());
@@ -22,8 +188,18 @@ pub fn generate_expr(attr_name: &Ident, expr: Expr) -> SynExpr {
         Expr::Enum(ident) => parse_quote_spanned!(
             ident.span() => style_impl::keyword::#ident()
         ),
+        Expr::Hex(hex) => {
+            let span = hex.span();
+            let [red, green, blue, alpha] = hex.channels;
+            parse_quote_spanned!(
+                span => ::moxie_native::Color::new(#red, #green, #blue, #alpha)
+            )
+        }
         Expr::LengthExpr(expr) => {
-            let res = generate_expr(attr_name, expr.expr);
+            if let Err((span, message)) = infer_length(&expr) {
+                emit_error!(span, message);
+            }
+            let res = generate_arith(attr_name, expr.expr, Kind::Number);
             match expr.unit {
                 LengthUnit::Pixels(kw) => parse_quote_spanned!(kw.span => style_impl::pixels(#res)),
                 LengthUnit::Ems(kw) => parse_quote_spanned!(kw.span => style_impl::ems(#res)),
@@ -35,12 +211,18 @@ pub fn generate_expr(attr_name: &Ident, expr: Expr) -> SynExpr {
                 }
             }
         }
-        Expr::BinaryExpr(exp) => {
-            let span = exp.span();
-            let left = generate_expr(attr_name, exp.left);
-            let right = generate_expr(attr_name, exp.right);
-            let op = &exp.oper;
-            parse_quote_spanned!(span => #left #op #right)
+        Expr::Paren(expr) if !is_arithmetic(&expr.expr) => {
+            generate_expr_at(attr_name, expr.expr, position)
+        }
+        expr @ Expr::BinaryExpr(_) | expr @ Expr::Neg(_) | expr @ Expr::Paren(_) => {
+            // Arithmetic on `var()`s alone is taken to be on lengths.
+            match infer(&expr) {
+                Ok(kind) => generate_arith(attr_name, expr, kind.unwrap_or(Kind::Length)),
+                Err((span, message)) => {
+                    emit_error!(span, message);
+                    parse_quote_spanned!(span => ())
+                }
+            }
         }
         Expr::Struct(expr) => {
             let span = expr.span();
@@ -48,11 +230,22 @@ pub fn generate_expr(attr_name: &Ident, expr: Expr) -> SynExpr {
             let fields = expr.fields.into_iter().map(|attr| {
                 let span = attr.span();
                 let name = &attr.name;
-                let value = generate_expr(attr_name, attr.value);
-                quote_spanned!(
-                    span =>
-                    .#name(#value)
-                )
+                match attr.value {
+                    Expr::Var(var) => {
+                        let var = generate_var(attr_name, *var);
+                        quote_spanned!(
+                            span =>
+                            .set_var(|value| &mut value.#name, #var)
+                        )
+                    }
+                    value => {
+                        let value = generate_expr_at(attr_name, value, Position::Argument);
+                        quote_spanned!(
+                            span =>
+                            .#name(#value)
+                        )
+                    }
+                }
             });
             parse_quote_spanned!(
                     span =>
@@ -67,12 +260,123 @@ pub fn generate_expr(attr_name: &Ident, expr: Expr) -> SynExpr {
             let args = expr
                 .args
                 .into_iter()
-                .map(|expr| generate_expr(attr_name, expr));
+                .map(|expr| generate_expr_at(attr_name, expr, Position::Argument));
             parse_quote_spanned!(
                 span => style_impl::func::#name(#(#args),*)
             )
         }
+        Expr::Var(expr) => {
+            let span = expr.span();
+            let var = generate_var(attr_name, *expr);
+            match position {
+                Position::Value => var,
+                Position::Argument => parse_quote_spanned!(span => style_impl::var::var(#var)),
+            }
+        }
         Expr::Inherit(kw) => parse_quote_spanned!(kw.span => style_impl::Inherit),
+        Expr::Initial(kw) => parse_quote_spanned!(kw.span => style_impl::Initial),
+        Expr::Unset(kw) => parse_quote_spanned!(kw.span => style_impl::Unset),
         Expr::Error(span) => parse_quote_spanned!(span => ()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn parse(source: &str) -> Expr {
+        syn::parse_str(source).unwrap()
+    }
+
+    fn kind(source: &str) -> Result<Option<Kind>, String> {
+        infer(&parse(source)).map_err(|(_, message)| message)
+    }
+
+    fn generate(source: &str) -> String {
+        let attr_name = Ident::new("width", Span::call_site());
+        generate_expr(&attr_name, parse(source))
+            .into_token_stream()
+            .to_string()
+    }
+
+    #[test]
+    fn infers_numbers_and_lengths() {
+        assert_eq!(kind("1 + 2 * 3"), Ok(Some(Kind::Number)));
+        assert_eq!(kind("10 px - 2 px"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("2 * 3 px"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("10 px / 4"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("-(2 px)"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("-3"), Ok(Some(Kind::Number)));
+        assert_eq!(kind("(1 + 2) px"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("min(1 px, 2 px) * 2"), Ok(Some(Kind::Length)));
+    }
+
+    #[test]
+    fn infers_variables_from_the_other_side() {
+        assert_eq!(kind("var(gap) + 1 px"), Ok(Some(Kind::Length)));
+        assert_eq!(kind("var(gap) * 2"), Ok(None));
+        assert_eq!(kind("var(gap) / var(count)"), Ok(None));
+        assert_eq!(kind("-var(gap)"), Ok(None));
+    }
+
+    #[test]
+    fn rejects_mismatched_arithmetic() {
+        assert_eq!(
+            kind("2 px * 3 px"),
+            Err("can't multiply two lengths, one side must be a number".to_owned())
+        );
+        assert_eq!(
+            kind("10 / 2 px"),
+            Err("can't divide by a length".to_owned())
+        );
+        assert_eq!(
+            kind("1 px + 2"),
+            Err("can't combine a length and a number".to_owned())
+        );
+        assert_eq!(
+            kind("(2 px) px"),
+            Err("units can only follow a number, not a length".to_owned())
+        );
+        assert_eq!(
+            kind("1 px + \"wide\""),
+            Err("only numbers and lengths can be used in arithmetic".to_owned())
+        );
+    }
+
+    #[test]
+    fn generates_arithmetic() {
+        assert_eq!(
+            generate("2 * 3 px"),
+            "(2f64) * (style_impl :: pixels (3f64))"
+        );
+        assert_eq!(
+            generate("-(10 px / 4)"),
+            "- ((style_impl :: pixels (10f64)) / (4f64))"
+        );
+        assert_eq!(
+            generate("(1 + 2) px"),
+            "style_impl :: pixels ((1f64) + (2f64))"
+        );
+    }
+
+    #[test]
+    fn resolves_variables_by_position() {
+        assert_eq!(
+            generate("var(gap) * 2"),
+            "(style_impl :: var :: var :: < style_impl :: Length > \
+             (style_impl :: var :: Var :: new (\"gap\" , None))) * (2f64)"
+        );
+        assert_eq!(
+            generate("2 * var(gap) + 1 px"),
+            "((2f64) * (style_impl :: var :: var :: < style_impl :: Length > \
+             (style_impl :: var :: Var :: new (\"gap\" , None)))) + \
+             (style_impl :: pixels (1f64))"
+        );
+        assert_eq!(
+            generate("var(scale) px"),
+            "style_impl :: pixels (style_impl :: var :: var :: < f64 > \
+             (style_impl :: var :: Var :: new (\"scale\" , None)))"
+        );
+    }
+}
@@ -1,11 +1,16 @@
-use super::parse::{Attribute, Selector, Style, SubStyle};
+use super::parse::{
+    Attribute, Condition, Declaration, Expr, NthArgs, Selector, Style, SubStyle, VariableDecl,
+};
 use proc_macro2::Span;
-use syn::{spanned::Spanned, Expr, ExprStruct, ItemStatic};
+use quote::quote_spanned;
+use syn::{spanned::Spanned, Expr as SynExpr, ExprStruct, ItemStatic, LitStr};
 
 mod eval;
+mod print;
 pub use eval::*;
+use print::{print_condition, print_expr};
 
-fn generate_selector(selector: Selector) -> Expr {
+fn generate_selector(selector: Selector) -> SynExpr {
     match selector {
         Selector::Element(selector) => {
             let span = selector.span();
@@ -17,12 +22,55 @@ fn generate_selector(selector: Selector) -> Expr {
         Selector::State(selector) => {
             let span = selector.span();
             let ident = selector.ident;
-            parse_quote_spanned!(span => node.has_state(style_impl::state::#ident()))
+            match selector.args {
+                Some(NthArgs { a, b, .. }) => parse_quote_spanned!(
+                    span => node.matches_state(style_impl::state::#ident(#a, #b))
+                ),
+                None => parse_quote_spanned!(
+                    span => node.matches_state(style_impl::state::#ident())
+                ),
+            }
+        }
+        Selector::Class(selector) => {
+            let span = selector.span();
+            let name = selector.name();
+            parse_quote_spanned!(span => node.has_class(#name))
+        }
+        Selector::Parent(selector) => {
+            let span = selector.span();
+            let inner = generate_condition(*selector.condition);
+            parse_quote_spanned!(span => node.parent().map_or(false, |node| #inner))
+        }
+        Selector::Ancestor(selector) => {
+            let span = selector.span();
+            let inner = generate_condition(*selector.condition);
+            parse_quote_spanned!(span => node.ancestors().any(|node| #inner))
         }
     }
 }
 
-fn generate_attribute(attribute: Attribute) -> Expr {
+fn generate_condition(condition: Condition) -> SynExpr {
+    match condition {
+        Condition::Selector(selector) => generate_selector(selector),
+        Condition::Not(op, inner) => {
+            let inner = generate_condition(*inner);
+            parse_quote_spanned!(op.span() => !(#inner))
+        }
+        Condition::And(left, op, right) => {
+            let left = generate_condition(*left);
+            let right = generate_condition(*right);
+            parse_quote_spanned!(op.span() => (#left) && (#right))
+        }
+        Condition::Or(left, op, right) => {
+            let left = generate_condition(*left);
+            let right = generate_condition(*right);
+            parse_quote_spanned!(op.span() => (#left) || (#right))
+        }
+        Condition::Group(_, inner) => generate_condition(*inner),
+    }
+}
+
+fn generate_attribute(attribute: Attribute) -> SynExpr {
     let span = attribute.span();
     let name = &attribute.name;
     let value = generate_expr(name, attribute.value);
@@ -31,15 +79,54 @@ fn generate_attribute(attribute: Attribute) -> Expr {
     )
 }
 
-fn generate_attributes(span: Span, attributes: impl Iterator<Item = Attribute>) -> ExprStruct {
-    let attributes = attributes.map(generate_attribute);
+fn generate_variable(variable: VariableDecl) -> SynExpr {
+    let span = variable.span();
+    let name = LitStr::new(&variable.name.to_string(), variable.name.span());
+    let is_var = matches!(variable.value, Expr::Var(_));
+    let value = generate_expr(&variable.name, variable.value);
+    if is_var {
+        parse_quote_spanned!(
+            span => style_impl::var::declare_var(variables, #name, #value)
+        )
+    } else {
+        parse_quote_spanned!(
+            span => style_impl::var::declare(variables, #name, #value)
+        )
+    }
+}
+
+fn generate_attributes(span: Span, declarations: impl Iterator<Item = Declaration>) -> ExprStruct {
+    let mut attributes = vec![];
+    let mut variables = vec![];
+    let mut descriptions = vec![];
+    for declaration in declarations {
+        match declaration {
+            Declaration::Attribute(attribute) => {
+                let name = LitStr::new(&attribute.name.to_string(), attribute.name.span());
+                let value = LitStr::new(&print_expr(&attribute.value), attribute.name.span());
+                descriptions
+                    .push(quote_spanned!(span => (#name, ::std::string::String::from(#value))));
+                attributes.push(generate_attribute(attribute));
+            }
+            Declaration::Variable(variable) => {
+                let name = LitStr::new(&format!("var({})", variable.name), variable.name.span());
+                let value = LitStr::new(&print_expr(&variable.value), variable.name.span());
+                descriptions
+                    .push(quote_spanned!(span => (#name, ::std::string::String::from(#value))));
+                variables.push(generate_variable(variable));
+            }
+        }
+    }
     parse_quote_spanned!(
         span => ::moxie_native::style::Attributes {
+            variables: |variables: &mut ::moxie_native::style::Variables| {
+                #(#variables;)*
+            },
             apply: |values: &mut ::moxie_native::style::ComputedValues| {
                 #(#attributes;)*
             },
             get_attributes: || {
-                vec![]
+                vec![#(#descriptions),*]
             },
         }
     )
@@ -47,14 +134,16 @@ fn generate_attributes(span: Span, attributes: impl Iterator<Item = Attribute>)
 
 fn generate_sub_style(style: SubStyle) -> ExprStruct {
     let span = style.span();
-    let selectors = style.selectors.into_iter().map(generate_selector);
-    let attributes = generate_attributes(style.brace.span, style.attributes.into_iter());
+    let selector_text = LitStr::new(&print_condition(&style.condition), span);
+    let condition = generate_condition(style.condition);
+    let attributes = generate_attributes(style.brace.span, style.declarations.into_iter());
     parse_quote_spanned!(
         span =>
         ::moxie_native::style::SubStyle {
             selector: |node: &dyn ::moxie_native::style::NodeSelect| -> bool {
-                #(#selectors)&&*
+                #condition
             },
+            selector_text: #selector_text,
             attributes: #attributes,
         }
     )
@@ -71,12 +160,12 @@ pub fn generate_style(style: Style) -> ItemStatic {
         ..
     } = style;
     let span = style.span();
-    let attributes = generate_attributes(style.brace.span, style.attributes.into_iter());
+    let attributes = generate_attributes(style.brace.span, style.declarations.into_iter());
     let sub_styles = style.sub_styles.into_iter().map(generate_sub_style);
     parse_quote_spanned!(
         span =>
         #(#outer)*
-        #visibility #kw_static #name: ::moxie_native::style::Style #equals ::moxie_native::style::Style(
+        #visibility #kw_static #name: ::moxie_native::style::Style #equals ::moxie_native::style::Style::new(
             &::moxie_native::style::StyleData {
                 name: stringify!(#name),
                 file: ::std::file!(),
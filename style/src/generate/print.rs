@@ -0,0 +1,113 @@
+use crate::parse::{
+    ClassName, Condition, Expr, LengthUnit, NthArgs, Operator, Selector, StructExpr, VarExpr,
+};
+
+/// Prints an expression the way it would be written in a style, for
+/// devtools.
+pub fn print_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Int(int) => int.base10_digits().to_owned(),
+        Expr::Float(float) => float.base10_digits().to_owned(),
+        Expr::Text(text) => format!("{:?}", text.value()),
+        Expr::Enum(ident) => ident.to_string(),
+        Expr::Hex(hex) => format!("#{}", hex.digits),
+        Expr::LengthExpr(expr) => {
+            let unit = match expr.unit {
+                LengthUnit::Pixels(_) => "px",
+                LengthUnit::Ems(_) => "em",
+                LengthUnit::ViewWidth(_) => "vw",
+                LengthUnit::ViewHeight(_) => "vh",
+            };
+            format!("{} {}", print_expr(&expr.expr), unit)
+        }
+        Expr::BinaryExpr(expr) => {
+            let oper = match expr.oper {
+                Operator::Add(_) => "+",
+                Operator::Sub(_) => "-",
+                Operator::Mul(_) => "*",
+                Operator::Div(_) => "/",
+            };
+            format!(
+                "{} {} {}",
+                print_expr(&expr.left),
+                oper,
+                print_expr(&expr.right)
+            )
+        }
+        Expr::Neg(expr) => format!("-{}", print_expr(&expr.expr)),
+        Expr::Paren(expr) => format!("({})", print_expr(&expr.expr)),
+        Expr::Struct(expr) => print_struct(expr),
+        Expr::Call(expr) => format!(
+            "{}({})",
+            expr.name,
+            expr.args
+                .iter()
+                .map(print_expr)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Expr::Var(expr) => print_var(expr),
+        Expr::Inherit(_) => "inherit".to_owned(),
+        Expr::Initial(_) => "initial".to_owned(),
+        Expr::Unset(_) => "unset".to_owned(),
+        Expr::Error(_) => "<error>".to_owned(),
+    }
+}
+
+fn print_struct(expr: &StructExpr) -> String {
+    let fields = expr
+        .fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, print_expr(&field.value)))
+        .collect::<Vec<_>>();
+    format!("{} {{ {} }}", expr.name, fields.join(", "))
+}
+
+fn print_var(expr: &VarExpr) -> String {
+    match expr.fallback {
+        Some((_, ref fallback)) => format!("var({}, {})", expr.name, print_expr(fallback)),
+        None => format!("var({})", expr.name),
+    }
+}
+
+fn print_nth(args: &NthArgs) -> String {
+    match (args.a, args.b) {
+        (0, b) => b.to_string(),
+        (a, 0) => format!("{}n", a),
+        (a, b) if b < 0 => format!("{}n{}", a, b),
+        (a, b) => format!("{}n+{}", a, b),
+    }
+}
+
+fn print_selector(selector: &Selector) -> String {
+    match selector {
+        Selector::Element(selector) => format!("element: {}", selector.ident),
+        Selector::State(selector) => match selector.args {
+            Some(ref args) => format!("state: {}({})", selector.ident, print_nth(args)),
+            None => format!("state: {}", selector.ident),
+        },
+        Selector::Class(selector) => match selector.name {
+            ClassName::Ident(ref ident) => format!("class: {}", ident),
+            ClassName::Str(ref lit) => format!("class: {:?}", lit.value()),
+        },
+        Selector::Parent(selector) => format!("parent({})", print_condition(&selector.condition)),
+        Selector::Ancestor(selector) => {
+            format!("ancestor({})", print_condition(&selector.condition))
+        }
+    }
+}
+
+/// Prints the condition of a sub-style, for devtools.
+pub fn print_condition(condition: &Condition) -> String {
+    match condition {
+        Condition::Selector(selector) => print_selector(selector),
+        Condition::Not(_, inner) => format!("!{}", print_condition(inner)),
+        Condition::And(left, _, right) => {
+            format!("{} && {}", print_condition(left), print_condition(right))
+        }
+        Condition::Or(left, _, right) => {
+            format!("{} || {}", print_condition(left), print_condition(right))
+        }
+        Condition::Group(_, inner) => format!("({})", print_condition(inner)),
+    }
+}
@@ -1,6 +1,6 @@
 use super::Attribute;
 use proc_macro2::Span;
-use proc_macro2::TokenStream;
+use proc_macro2::{TokenStream, TokenTree};
 use proc_macro_error::emit_error;
 use quote::{quote_spanned, ToTokens};
 use syn::{
@@ -15,6 +15,9 @@ mod kw {
     syn::custom_keyword!(vw);
     syn::custom_keyword!(vh);
     syn::custom_keyword!(inherit);
+    syn::custom_keyword!(initial);
+    syn::custom_keyword!(unset);
+    syn::custom_keyword!(var);
 }
 
 pub enum LengthUnit {
@@ -38,6 +41,8 @@ impl ToTokens for LengthUnit {
 pub enum Operator {
     Add(Token![+]),
     Sub(Token![-]),
+    Mul(Token![*]),
+    Div(Token![/]),
 }
 
 impl ToTokens for Operator {
@@ -45,6 +50,8 @@ impl ToTokens for Operator {
         match self {
             Operator::Add(symbol) => symbol.to_tokens(tokens),
             Operator::Sub(symbol) => symbol.to_tokens(tokens),
+            Operator::Mul(symbol) => symbol.to_tokens(tokens),
+            Operator::Div(symbol) => symbol.to_tokens(tokens),
         }
     }
 }
@@ -63,6 +70,30 @@ impl ToTokens for BinaryExpr {
     }
 }
 
+pub struct NegExpr {
+    pub minus: Token![-],
+    pub expr: Expr,
+}
+
+impl ToTokens for NegExpr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.minus.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+    }
+}
+
+pub struct ParenExpr {
+    pub paren: token::Paren,
+    pub expr: Expr,
+}
+
+impl ToTokens for ParenExpr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expr = &self.expr;
+        tokens.extend(quote_spanned!(self.paren.span => (#expr)));
+    }
+}
+
 pub struct LengthExpr {
     pub unit: LengthUnit,
     pub expr: Expr,
@@ -103,16 +134,121 @@ impl ToTokens for CallExpr {
     }
 }
 
+/// A reference to a style variable: `var(name)` or `var(name, fallback)`.
+pub struct VarExpr {
+    pub kw: kw::var,
+    pub paren: token::Paren,
+    pub name: Ident,
+    pub fallback: Option<(Token![,], Expr)>,
+}
+
+impl Parse for VarExpr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kw = input.parse::<kw::var>()?;
+        let contents;
+        let paren = parenthesized!(contents in input);
+        let name = contents.parse::<Ident>()?;
+        let fallback = if contents.peek(Token![,]) {
+            let comma = contents.parse::<Token![,]>()?;
+            Some((comma, contents.parse::<Expr>()?))
+        } else {
+            None
+        };
+        if !contents.is_empty() {
+            return Err(contents.error("expected `,` or `)`"));
+        }
+        Ok(VarExpr {
+            kw,
+            paren,
+            name,
+            fallback,
+        })
+    }
+}
+
+impl ToTokens for VarExpr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kw.to_tokens(tokens);
+        let name = &self.name;
+        let fallback = self.fallback.as_ref().map(|(comma, expr)| {
+            let mut tokens = TokenStream::new();
+            comma.to_tokens(&mut tokens);
+            expr.to_tokens(&mut tokens);
+            tokens
+        });
+        tokens.extend(quote_spanned!(self.paren.span => (#name #fallback)));
+    }
+}
+
+/// A hex color literal like `#1a2b3c`. Rust splits these into a `#`
+/// and an identifier or number literal, so the digits are recovered from
+/// the text of the second token.
+pub struct HexExpr {
+    pub hash: Token![#],
+    pub digits: TokenTree,
+    /// The red, green, blue and alpha channels.
+    pub channels: [u8; 4],
+}
+
+impl Parse for HexExpr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let hash = input.parse::<Token![#]>()?;
+        let digits = input.step(|cursor| match cursor.token_tree() {
+            Some((tree @ TokenTree::Ident(_), rest))
+            | Some((tree @ TokenTree::Literal(_), rest)) => Ok((tree, rest)),
+            _ => Err(cursor.error("expected hex digits after `#`")),
+        })?;
+        let hex = digits.to_string();
+        let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).unwrap();
+        let pair = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+        let channels = if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            None
+        } else {
+            match hex.len() {
+                3 => Some([digit(0) * 17, digit(1) * 17, digit(2) * 17, 255]),
+                4 => Some([digit(0) * 17, digit(1) * 17, digit(2) * 17, digit(3) * 17]),
+                6 => Some([pair(0), pair(2), pair(4), 255]),
+                8 => Some([pair(0), pair(2), pair(4), pair(6)]),
+                _ => None,
+            }
+        };
+        match channels {
+            Some(channels) => Ok(HexExpr {
+                hash,
+                digits,
+                channels,
+            }),
+            None => Err(syn::Error::new(
+                digits.span(),
+                format!("invalid color `#{}`, expected 3, 4, 6 or 8 hex digits", hex),
+            )),
+        }
+    }
+}
+
+impl ToTokens for HexExpr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.hash.to_tokens(tokens);
+        self.digits.to_tokens(tokens);
+    }
+}
+
 pub enum Expr {
     Int(LitInt),
     Float(LitFloat),
     Text(LitStr),
     Enum(Ident),
+    Hex(Box<HexExpr>),
     LengthExpr(Box<LengthExpr>),
     BinaryExpr(Box<BinaryExpr>),
+    Neg(Box<NegExpr>),
+    Paren(Box<ParenExpr>),
     Struct(Box<StructExpr>),
     Call(Box<CallExpr>),
+    Var(Box<VarExpr>),
     Inherit(kw::inherit),
+    Initial(kw::initial),
+    Unset(kw::unset),
     Error(Span),
 }
 
@@ -129,6 +265,21 @@ impl Expr {
         let lookahead = input.lookahead1();
         let expr = if lookahead.peek(kw::inherit) {
             Expr::Inherit(input.parse()?)
+        } else if lookahead.peek(kw::initial) {
+            Expr::Initial(input.parse()?)
+        } else if lookahead.peek(kw::unset) {
+            Expr::Unset(input.parse()?)
+        } else if input.peek(kw::var) && input.peek2(token::Paren) {
+            Expr::Var(Box::new(input.parse()?))
+        } else if input.peek(Token![#]) {
+            match input.parse() {
+                Ok(hex) => Expr::Hex(Box::new(hex)),
+                Err(err) => {
+                    let span = err.span();
+                    emit_error!(err);
+                    Expr::Error(span)
+                }
+            }
         } else if lookahead.peek(LitInt) {
             Expr::Int(input.parse()?)
         } else if lookahead.peek(LitFloat) {
@@ -137,15 +288,16 @@ impl Expr {
             Expr::Text(input.parse()?)
         } else if lookahead.peek(token::Paren) {
             let contents;
-            parenthesized!(contents in input);
-            match contents.call(Expr::parse_add) {
+            let paren = parenthesized!(contents in input);
+            let expr = match contents.call(Expr::parse_add) {
                 Ok(expr) => expr,
                 Err(err) => {
                     let span = err.span();
                     emit_error!(err);
                     Expr::Error(span)
                 }
-            }
+            };
+            Expr::Paren(Box::new(ParenExpr { paren, expr }))
         } else if lookahead.peek(Ident) {
             let ident = input.parse()?;
             if input.peek(token::Paren) {
@@ -213,19 +365,43 @@ impl Expr {
         }
     }
 
-    fn parse_add(input: ParseStream) -> Result<Self> {
-        let left = input.call(Expr::parse_suffix)?;
-
-        if input.peek(Token![+]) {
-            let op = input.parse::<Token![+]>()?;
-            let right = input.call(Expr::parse_suffix)?;
-            Ok(Expr::new_bin(left, right, Operator::Add(op)))
-        } else if input.peek(Token![-]) {
-            let op = input.parse::<Token![-]>()?;
-            let right = input.call(Expr::parse_suffix)?;
-            Ok(Expr::new_bin(left, right, Operator::Sub(op)))
+    fn parse_unary(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![-]) {
+            let minus = input.parse::<Token![-]>()?;
+            let expr = input.call(Expr::parse_unary)?;
+            Ok(Expr::Neg(Box::new(NegExpr { minus, expr })))
         } else {
-            Ok(left)
+            input.call(Expr::parse_suffix)
+        }
+    }
+
+    fn parse_mul(input: ParseStream) -> Result<Self> {
+        let mut left = input.call(Expr::parse_unary)?;
+        loop {
+            let oper = if input.peek(Token![*]) {
+                Operator::Mul(input.parse()?)
+            } else if input.peek(Token![/]) {
+                Operator::Div(input.parse()?)
+            } else {
+                return Ok(left);
+            };
+            let right = input.call(Expr::parse_unary)?;
+            left = Expr::new_bin(left, right, oper);
+        }
+    }
+
+    fn parse_add(input: ParseStream) -> Result<Self> {
+        let mut left = input.call(Expr::parse_mul)?;
+        loop {
+            let oper = if input.peek(Token![+]) {
+                Operator::Add(input.parse()?)
+            } else if input.peek(Token![-]) {
+                Operator::Sub(input.parse()?)
+            } else {
+                return Ok(left);
+            };
+            let right = input.call(Expr::parse_mul)?;
+            left = Expr::new_bin(left, right, oper);
         }
     }
 }
@@ -243,11 +419,17 @@ impl ToTokens for Expr {
             Expr::Float(value) => value.to_tokens(tokens),
             Expr::Text(value) => value.to_tokens(tokens),
             Expr::Enum(value) => value.to_tokens(tokens),
+            Expr::Hex(value) => value.to_tokens(tokens),
             Expr::LengthExpr(value) => value.to_tokens(tokens),
             Expr::BinaryExpr(value) => value.to_tokens(tokens),
+            Expr::Neg(value) => value.to_tokens(tokens),
+            Expr::Paren(value) => value.to_tokens(tokens),
             Expr::Struct(value) => value.to_tokens(tokens),
             Expr::Call(value) => value.to_tokens(tokens),
+            Expr::Var(value) => value.to_tokens(tokens),
             Expr::Inherit(value) => value.to_tokens(tokens),
+            Expr::Initial(value) => value.to_tokens(tokens),
+            Expr::Unset(value) => value.to_tokens(tokens),
             Expr::Error(value) => tokens.extend(quote_spanned!(*value => <syntax error>)),
         }
     }
@@ -4,8 +4,8 @@ use quote::{quote_spanned, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Result},
     punctuated::Punctuated,
-    spanned::Spanned,
-    token, {braced, Attribute as SynAttribute, Ident, Token, Visibility},
+    token,
+    {braced, parenthesized, Attribute as SynAttribute, Ident, LitInt, LitStr, Token, Visibility},
 };
 
 mod expr;
@@ -15,6 +15,13 @@ pub use expr::*;
 mod kw {
     syn::custom_keyword!(element);
     syn::custom_keyword!(state);
+    syn::custom_keyword!(class);
+    syn::custom_keyword!(var);
+    syn::custom_keyword!(parent);
+    syn::custom_keyword!(ancestor);
+    syn::custom_keyword!(n);
+    syn::custom_keyword!(odd);
+    syn::custom_keyword!(even);
 }
 
 pub struct ElementSelector {
@@ -40,10 +47,137 @@ impl ToTokens for ElementSelector {
     }
 }
 
+pub enum ClassName {
+    Ident(Ident),
+    Str(LitStr),
+}
+
+pub struct ClassSelector {
+    pub kw: kw::class,
+    pub colon: Token![:],
+    pub name: ClassName,
+}
+
+impl ClassSelector {
+    pub fn name(&self) -> LitStr {
+        match self.name {
+            ClassName::Ident(ref ident) => LitStr::new(&ident.to_string(), ident.span()),
+            ClassName::Str(ref lit) => lit.clone(),
+        }
+    }
+}
+
+impl Parse for ClassSelector {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kw = input.parse::<kw::class>()?;
+        let colon = input.parse::<Token![:]>()?;
+        let name = if input.peek(LitStr) {
+            ClassName::Str(input.parse()?)
+        } else {
+            ClassName::Ident(input.parse()?)
+        };
+        Ok(ClassSelector { kw, colon, name })
+    }
+}
+
+impl ToTokens for ClassSelector {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kw.to_tokens(tokens);
+        self.colon.to_tokens(tokens);
+        match self.name {
+            ClassName::Ident(ref ident) => ident.to_tokens(tokens),
+            ClassName::Str(ref lit) => lit.to_tokens(tokens),
+        }
+    }
+}
+
+/// The argument to `nth_child()`, in the same `an+b` notation as CSS.
+pub struct NthArgs {
+    pub paren: token::Paren,
+    pub a: i32,
+    pub b: i32,
+    pub tokens: TokenStream,
+}
+
+fn parse_nth_int(input: ParseStream) -> Result<(i32, bool)> {
+    let lit = input.parse::<LitInt>()?;
+    match lit.suffix() {
+        "" => Ok((lit.base10_parse::<i32>()?, false)),
+        "n" => Ok((lit.base10_parse::<i32>()?, true)),
+        _ => Err(syn::Error::new(lit.span(), "expected a number or `n`")),
+    }
+}
+
+impl NthArgs {
+    fn parse_inner(input: ParseStream) -> Result<(i32, i32)> {
+        if input.peek(kw::odd) {
+            input.parse::<kw::odd>()?;
+            return Ok((2, 1));
+        }
+        if input.peek(kw::even) {
+            input.parse::<kw::even>()?;
+            return Ok((2, 0));
+        }
+
+        let sign = if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            -1
+        } else {
+            1
+        };
+
+        let (a, has_n) = if input.peek(kw::n) {
+            input.parse::<kw::n>()?;
+            (1, true)
+        } else {
+            parse_nth_int(input)?
+        };
+
+        if !has_n {
+            return Ok((0, sign * a));
+        }
+
+        let b = if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            parse_nth_int(input)?
+        } else if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            let (b, has_n) = parse_nth_int(input)?;
+            (-b, has_n)
+        } else {
+            (0, false)
+        };
+        if b.1 {
+            return Err(input.error("`n` may only appear once"));
+        }
+
+        Ok((sign * a, b.0))
+    }
+}
+
+impl Parse for NthArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let contents;
+        let paren = parenthesized!(contents in input);
+        let tokens = contents.fork().parse::<TokenStream>()?;
+        let (a, b) = NthArgs::parse_inner(&contents)?;
+        if !contents.is_empty() {
+            return Err(contents.error("unexpected token in `nth_child()`"));
+        }
+        Ok(NthArgs {
+            paren,
+            a,
+            b,
+            tokens,
+        })
+    }
+}
+
 pub struct StateSelector {
     pub kw: kw::state,
     pub colon: Token![:],
     pub ident: Ident,
+    pub args: Option<NthArgs>,
 }
 
 impl Parse for StateSelector {
@@ -51,20 +185,74 @@ impl Parse for StateSelector {
         let kw = input.parse::<kw::state>()?;
         let colon = input.parse::<Token![:]>()?;
         let ident = input.parse::<Ident>()?;
-        Ok(StateSelector { kw, colon, ident })
+        let args = if input.peek(token::Paren) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(StateSelector {
+            kw,
+            colon,
+            ident,
+            args,
+        })
     }
 }
+
 impl ToTokens for StateSelector {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.kw.to_tokens(tokens);
         self.colon.to_tokens(tokens);
         self.ident.to_tokens(tokens);
+        if let Some(ref args) = self.args {
+            let inner = &args.tokens;
+            tokens.extend(quote_spanned!(args.paren.span => (#inner)));
+        }
+    }
+}
+
+/// Matches a condition against the parent (`parent(...)`) or any
+/// ancestor (`ancestor(...)`) of the node.
+pub struct RelativeSelector<Kw> {
+    pub kw: Kw,
+    pub paren: token::Paren,
+    pub condition: Box<Condition>,
+}
+
+impl<Kw> Parse for RelativeSelector<Kw>
+where
+    Kw: Parse,
+{
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kw = input.parse::<Kw>()?;
+        let contents;
+        let paren = parenthesized!(contents in input);
+        let condition = Box::new(contents.parse()?);
+        Ok(RelativeSelector {
+            kw,
+            paren,
+            condition,
+        })
+    }
+}
+
+impl<Kw> ToTokens for RelativeSelector<Kw>
+where
+    Kw: ToTokens,
+{
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kw.to_tokens(tokens);
+        let condition = &self.condition;
+        tokens.extend(quote_spanned!(self.paren.span => (#condition)));
     }
 }
 
 pub enum Selector {
     Element(ElementSelector),
     State(StateSelector),
+    Class(ClassSelector),
+    Parent(RelativeSelector<kw::parent>),
+    Ancestor(RelativeSelector<kw::ancestor>),
 }
 
 impl Parse for Selector {
@@ -74,6 +262,12 @@ impl Parse for Selector {
             Ok(Selector::Element(input.parse()?))
         } else if lookahead.peek(kw::state) {
             Ok(Selector::State(input.parse()?))
+        } else if lookahead.peek(kw::class) {
+            Ok(Selector::Class(input.parse()?))
+        } else if lookahead.peek(kw::parent) {
+            Ok(Selector::Parent(input.parse()?))
+        } else if lookahead.peek(kw::ancestor) {
+            Ok(Selector::Ancestor(input.parse()?))
         } else {
             Err(lookahead.error())
         }
@@ -85,6 +279,86 @@ impl ToTokens for Selector {
         match self {
             Selector::Element(selector) => selector.to_tokens(tokens),
             Selector::State(selector) => selector.to_tokens(tokens),
+            Selector::Class(selector) => selector.to_tokens(tokens),
+            Selector::Parent(selector) => selector.to_tokens(tokens),
+            Selector::Ancestor(selector) => selector.to_tokens(tokens),
+        }
+    }
+}
+
+/// A boolean combination of selectors, as used in `if` blocks.
+pub enum Condition {
+    Selector(Selector),
+    Not(Token![!], Box<Condition>),
+    And(Box<Condition>, Token![&&], Box<Condition>),
+    Or(Box<Condition>, Token![||], Box<Condition>),
+    Group(token::Paren, Box<Condition>),
+}
+
+impl Condition {
+    fn parse_or(input: ParseStream) -> Result<Self> {
+        let mut left = input.call(Condition::parse_and)?;
+        while input.peek(Token![||]) {
+            let op = input.parse::<Token![||]>()?;
+            let right = input.call(Condition::parse_and)?;
+            left = Condition::Or(Box::new(left), op, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(input: ParseStream) -> Result<Self> {
+        let mut left = input.call(Condition::parse_unary)?;
+        while input.peek(Token![&&]) {
+            let op = input.parse::<Token![&&]>()?;
+            let right = input.call(Condition::parse_unary)?;
+            left = Condition::And(Box::new(left), op, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![!]) {
+            let op = input.parse::<Token![!]>()?;
+            let inner = input.call(Condition::parse_unary)?;
+            Ok(Condition::Not(op, Box::new(inner)))
+        } else if input.peek(token::Paren) {
+            let contents;
+            let paren = parenthesized!(contents in input);
+            let inner = contents.call(Condition::parse_or)?;
+            Ok(Condition::Group(paren, Box::new(inner)))
+        } else {
+            Ok(Condition::Selector(input.parse()?))
+        }
+    }
+}
+
+impl Parse for Condition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.call(Condition::parse_or)
+    }
+}
+
+impl ToTokens for Condition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Condition::Selector(selector) => selector.to_tokens(tokens),
+            Condition::Not(op, inner) => {
+                op.to_tokens(tokens);
+                inner.to_tokens(tokens);
+            }
+            Condition::And(left, op, right) => {
+                left.to_tokens(tokens);
+                op.to_tokens(tokens);
+                right.to_tokens(tokens);
+            }
+            Condition::Or(left, op, right) => {
+                left.to_tokens(tokens);
+                op.to_tokens(tokens);
+                right.to_tokens(tokens);
+            }
+            Condition::Group(paren, inner) => {
+                tokens.extend(quote_spanned!(paren.span => (#inner)));
+            }
         }
     }
 }
@@ -112,33 +386,90 @@ impl ToTokens for Attribute {
     }
 }
 
+/// Declares a style variable, as in `var(accent): rgb(66, 135, 245)`.
+pub struct VariableDecl {
+    pub kw: kw::var,
+    pub paren: token::Paren,
+    pub name: Ident,
+    pub colon: Token![:],
+    pub value: Expr,
+}
+
+impl Parse for VariableDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kw = input.parse::<kw::var>()?;
+        let contents;
+        let paren = parenthesized!(contents in input);
+        let name = contents.parse::<Ident>()?;
+        if !contents.is_empty() {
+            return Err(contents.error("expected a variable name"));
+        }
+        let colon = input.parse::<Token![:]>()?;
+        let value = input.parse::<Expr>()?;
+        Ok(VariableDecl {
+            kw,
+            paren,
+            name,
+            colon,
+            value,
+        })
+    }
+}
+
+impl ToTokens for VariableDecl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kw.to_tokens(tokens);
+        let name = &self.name;
+        tokens.extend(quote_spanned!(self.paren.span => (#name)));
+        self.colon.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// An entry in a style body: either an attribute or a variable.
+pub enum Declaration {
+    Attribute(Attribute),
+    Variable(VariableDecl),
+}
+
+impl Parse for Declaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::var) && input.peek2(token::Paren) {
+            Ok(Declaration::Variable(input.parse()?))
+        } else {
+            Ok(Declaration::Attribute(input.parse()?))
+        }
+    }
+}
+
+impl ToTokens for Declaration {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Declaration::Attribute(attribute) => attribute.to_tokens(tokens),
+            Declaration::Variable(variable) => variable.to_tokens(tokens),
+        }
+    }
+}
+
 pub struct SubStyle {
     pub kw: Token![if],
-    pub selectors: Punctuated<Selector, Token![&&]>,
+    pub condition: Condition,
     pub brace: token::Brace,
-    pub attributes: Punctuated<Attribute, Token![,]>,
+    pub declarations: Punctuated<Declaration, Token![,]>,
 }
 
 impl Parse for SubStyle {
     fn parse(input: ParseStream) -> Result<Self> {
         let kw = input.parse::<Token![if]>()?;
-        let mut selectors = Punctuated::new();
-        loop {
-            selectors.push_value(input.parse::<Selector>()?);
-            let lookahead = input.lookahead1();
-            if lookahead.peek(Token![&&]) {
-                selectors.push_punct(input.parse::<Token![&&]>()?);
-            } else if lookahead.peek(token::Brace) {
-                break;
-            } else {
-                return Err(lookahead.error());
-            }
+        let condition = input.parse::<Condition>()?;
+        if !input.peek(token::Brace) {
+            return Err(input.error("expected `{`, `&&` or `||`"));
         }
         let content;
         let brace = braced!(content in input);
-        let attributes = content.parse_terminated(Attribute::parse);
-        let attributes = match attributes {
-            Ok(attributes) => attributes,
+        let declarations = content.parse_terminated(Declaration::parse);
+        let declarations = match declarations {
+            Ok(declarations) => declarations,
             Err(err) => {
                 emit_error!(err);
                 Punctuated::default()
@@ -146,9 +477,9 @@ impl Parse for SubStyle {
         };
         Ok(SubStyle {
             kw,
-            selectors,
+            condition,
             brace,
-            attributes,
+            declarations,
         })
     }
 }
@@ -156,15 +487,11 @@ impl Parse for SubStyle {
 impl ToTokens for SubStyle {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.kw.to_tokens(tokens);
-        let selectors = &self.selectors;
-        tokens.extend(quote_spanned!(
-            self.selectors.span() =>
-            #(#selectors)&&+
-        ));
-        let attributes = self.attributes.iter();
+        self.condition.to_tokens(tokens);
+        let declarations = self.declarations.iter();
         tokens.extend(quote_spanned!(
             self.brace.span => {
-                #(#attributes),*
+                #(#declarations),*
             }
         ))
     }
@@ -177,18 +504,18 @@ pub struct Style {
     pub name: Ident,
     pub equals: Token![=],
     pub brace: token::Brace,
-    pub attributes: Vec<Attribute>,
+    pub declarations: Vec<Declaration>,
     pub sub_styles: Vec<SubStyle>,
     pub semicolon: Token![;],
 }
 
-fn parse_style_body(input: ParseStream) -> Result<(Vec<Attribute>, Vec<SubStyle>)> {
-    let mut attributes = vec![];
+fn parse_style_body(input: ParseStream) -> Result<(Vec<Declaration>, Vec<SubStyle>)> {
+    let mut declarations = vec![];
     loop {
         if input.peek(token::If) || input.is_empty() {
             break;
         }
-        attributes.push(input.parse()?);
+        declarations.push(input.parse()?);
         if !input.peek(token::Comma) {
             break;
         }
@@ -202,7 +529,7 @@ fn parse_style_body(input: ParseStream) -> Result<(Vec<Attribute>, Vec<SubStyle>
     while input.peek(token::If) {
         sub_styles.push(input.parse()?);
     }
-    Ok((attributes, sub_styles))
+    Ok((declarations, sub_styles))
 }
 
 impl ToTokens for Style {
@@ -214,11 +541,11 @@ impl ToTokens for Style {
             ref name,
             ref equals,
             ref brace,
-            ref attributes,
+            ref declarations,
             ref sub_styles,
             ref semicolon,
         } = self;
-        let attributes = attributes.iter();
+        let declarations = declarations.iter();
         let sub_styles = sub_styles.iter();
         tokens.extend(quote_spanned!(
             brace.span =>
@@ -227,7 +554,7 @@ impl ToTokens for Style {
             #kw_static
             #name
             #equals
-            #(#attributes,)*
+            #(#declarations,)*
             #(#sub_styles)*
             #semicolon
         ));
@@ -243,7 +570,7 @@ impl Parse for Style {
         let equals = input.parse::<Token![=]>()?;
         let content;
         let brace = braced!(content in input);
-        let (attributes, sub_styles) = match parse_style_body(&content) {
+        let (declarations, sub_styles) = match parse_style_body(&content) {
             Ok(result) => result,
             Err(err) => {
                 emit_error!(err.span(), err);
@@ -258,7 +585,7 @@ impl Parse for Style {
             name,
             equals,
             brace,
-            attributes,
+            declarations,
             sub_styles,
             semicolon,
         })